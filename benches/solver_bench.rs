@@ -5,7 +5,10 @@
 
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use fpe::models::{Card, GameState, Hand, Position, Range};
+use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
 use fpe::solver::solve;
+use fpe::solver::solve_range_vs_range;
+use rayon::prelude::*;
 use std::str::FromStr;
 
 /// Benchmark: River decision with nuts vs range (100 iterations)
@@ -110,7 +113,8 @@ fn benchmark_iteration_scaling(c: &mut Criterion) {
                     0.0,
                     Position::IP,
                     villain_range.clone(),
-                ).unwrap();
+                )
+                .unwrap();
                 b.iter(|| solve(state.clone(), iter_count))
             },
         );
@@ -118,12 +122,98 @@ fn benchmark_iteration_scaling(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark: Turn range-vs-range solve with one shared eval cache versus a
+/// private cache per hero combo
+fn benchmark_range_vs_range_shared_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("range_vs_range");
+
+    let board = vec![
+        Card::from_str("Kh").unwrap(),
+        Card::from_str("9s").unwrap(),
+        Card::from_str("5c").unwrap(),
+        Card::from_str("2d").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("22+,AK,AQ,KQ").unwrap();
+    villain_range.remove_blockers(&board);
+    let hero_range = Range::from_notation("AA,KK,AK").unwrap();
+
+    let state = GameState::new(
+        Hand::from_str("AsAd").unwrap(),
+        board,
+        15.0,
+        75.0,
+        0.0,
+        Position::OOP,
+        villain_range,
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(100)
+        .samples_per_iteration(10)
+        .seed(1)
+        .build();
+
+    group.bench_function("shared_cache", |b| {
+        b.iter(|| solve_range_vs_range(&state, &hero_range, config.clone()))
+    });
+
+    group.bench_function("per_combo_cache", |b| {
+        b.iter(|| {
+            let combos: Vec<Hand> = hero_range
+                .hands()
+                .map(|(hand, _)| hand.clone())
+                .filter(|hand| !hand.cards.iter().any(|c| state.board.contains(c)))
+                .collect();
+            combos
+                .into_par_iter()
+                .map(|hand| {
+                    let mut combo_state = state.clone();
+                    combo_state.villain_range.remove_blockers(&hand.cards);
+                    combo_state.hero_hand = hand;
+                    solve_with_config(combo_state, config.clone())
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+/// Benchmark: flop runout equity against a narrow range (scored one combo
+/// at a time) and a wide one (scored in parallel)
+fn benchmark_runout_equity(c: &mut Criterion) {
+    use fpe::solver::equity::calculate_equity_runout;
+
+    let hero = Hand::from_str("AhKh").unwrap();
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("7h").unwrap(),
+        Card::from_str("2c").unwrap(),
+    ];
+
+    let mut group = c.benchmark_group("runout_equity");
+    group.sample_size(10);
+    for (name, notation) in [
+        ("narrow", "QQ,77,AQs"),
+        ("wide", "22+,A2s+,K9s+,QTs+,ATo+,KJo+"),
+    ] {
+        let villain_range = Range::from_notation(notation).unwrap();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(name),
+            &villain_range,
+            |b, range| b.iter(|| calculate_equity_runout(&hero, range, &board)),
+        );
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     benchmark_solver_river_nuts_100,
     benchmark_solver_river_nuts_1000,
     benchmark_solver_river_polarized,
     benchmark_solver_flop_medium_spr,
-    benchmark_iteration_scaling
+    benchmark_iteration_scaling,
+    benchmark_range_vs_range_shared_cache,
+    benchmark_runout_equity
 );
 criterion_main!(benches);
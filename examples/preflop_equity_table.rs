@@ -0,0 +1,21 @@
+//! Rebuild `src/solver/preflop_equity.bin`, the exact preflop all-in
+//! equity table (see `fpe::solver::preflop_equity`).
+//!
+//! ```text
+//! cargo run --release --example preflop_equity_table [PATH]
+//! ```
+//!
+//! Writes to `PATH`, by default the table in the source tree. Enumerating
+//! every board of all 47,008 matchup classes takes a few minutes.
+
+use fpe::solver::preflop_equity::build_table;
+
+fn main() -> std::io::Result<()> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/src/solver/preflop_equity.bin").to_string()
+    });
+    let table = build_table();
+    std::fs::write(&path, &table)?;
+    println!("Wrote {} matchups to {}", table.len() / 8, path);
+    Ok(())
+}
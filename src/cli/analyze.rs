@@ -0,0 +1,164 @@
+//! Spot analysis as a library call
+//!
+//! `analyze` is the `analyze` subcommand without argument parsing or
+//! printing: it solves an already validated `AnalyzeInput`, for a single
+//! hand or combo by combo for a range, and returns the strategy along with
+//! what the CLI needs to describe the solve.
+
+use crate::cli::args::AnalyzeRequest;
+use crate::error::ModelError;
+use crate::models::{Card, GameState, Range, RangeStrategy, Strategy};
+use crate::solver::{self, range_solve, MccfrConfig, SolveProfile, SolverKind};
+
+/// Which player of a spot makes the decision
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Perspective {
+    /// Hero acts with their hand or range
+    #[default]
+    Hero,
+    /// Villain acts with their range
+    Villain,
+}
+
+/// A validated spot and how to solve it
+#[derive(Debug, Clone)]
+pub struct AnalyzeInput {
+    /// Spot to solve, with villain combos blocked by known cards removed.
+    /// For a range solve `hero_hand` is only a placeholder.
+    pub game_state: GameState,
+    /// Hero's range to solve combo by combo instead of `game_state.hero_hand`
+    pub hero_range: Option<HeroRange>,
+    /// Algorithm to solve with; range solves need `SolverKind::Mccfr`
+    pub solver: SolverKind,
+    /// Settings for `SolverKind::Mccfr`; a uniform preview ignores them
+    pub config: MccfrConfig,
+    /// Also solve a range spot after every turn card (flop spots only)
+    pub turn_report: bool,
+}
+
+/// Hero's range in a range solve
+#[derive(Debug, Clone)]
+pub struct HeroRange {
+    /// The range as written, for display
+    pub notation: String,
+    /// Its combos, with board and dead card blockers removed
+    pub range: Range,
+}
+
+impl AnalyzeInput {
+    /// Validate `request` into an input solved by `solver` with `config`,
+    /// a range solve if the request has a hero range.
+    ///
+    /// `config.iterations` is taken from the request.
+    pub fn from_request(
+        request: &AnalyzeRequest,
+        solver: SolverKind,
+        mut config: MccfrConfig,
+    ) -> std::result::Result<Self, String> {
+        config.iterations = request.iterations;
+        let (game_state, hero_range) = if request.hero_range.is_empty() {
+            (request.to_game_state()?, None)
+        } else {
+            let (state, range) = request.to_range_spot()?;
+            let hero_range = HeroRange {
+                notation: request.hero_range.clone(),
+                range,
+            };
+            (state, Some(hero_range))
+        };
+        Ok(Self {
+            game_state,
+            hero_range,
+            solver,
+            config,
+            turn_report: false,
+        })
+    }
+}
+
+/// Result of `analyze`
+#[derive(Debug, Clone)]
+pub struct AnalyzeOutput {
+    /// Hero's strategy at the spot; for a range, weighted over its combos
+    pub strategy: Strategy,
+    /// Details of the solve
+    pub meta: AnalyzeMeta,
+}
+
+/// Details of an `analyze` solve beyond the strategy
+#[derive(Debug, Clone)]
+pub struct AnalyzeMeta {
+    /// The spot that was solved
+    pub game_state: GameState,
+    /// Where solve time went, if `config.profile` was set for a single-hand
+    /// MCCFR solve
+    pub profile: Option<SolveProfile>,
+    /// Per-combo results of a range solve
+    pub range: Option<RangeAnalysis>,
+}
+
+/// Per-combo results of a range solve
+#[derive(Debug, Clone)]
+pub struct RangeAnalysis {
+    /// Hero's range as written
+    pub notation: String,
+    /// Each combo's strategy
+    pub strategy: RangeStrategy,
+    /// The solve after each turn card, if `turn_report` was set
+    pub turns: Option<Vec<(Card, RangeStrategy)>>,
+}
+
+/// Solve `input`.
+///
+/// A single hand goes through `solve_with_kind`, so its actions default and
+/// are validated the same way as any other solve. A range is solved combo
+/// by combo with `solve_range_vs_range`.
+pub fn analyze(input: AnalyzeInput) -> crate::Result<AnalyzeOutput> {
+    let AnalyzeInput {
+        game_state,
+        hero_range,
+        solver,
+        config,
+        turn_report,
+    } = input;
+
+    let Some(HeroRange { notation, range }) = hero_range else {
+        let (strategy, profile) =
+            solver::solve_with_kind_profiled(game_state.clone(), solver, config)?;
+        return Ok(AnalyzeOutput {
+            strategy,
+            meta: AnalyzeMeta {
+                game_state,
+                profile,
+                range: None,
+            },
+        });
+    };
+
+    if solver != SolverKind::Mccfr {
+        return Err(ModelError::Solver(
+            "Range solves need the MCCFR solver".to_string(),
+        ));
+    }
+    let range_strategy = solver::solve_range_vs_range(&game_state, &range, config.clone())?;
+    let turns = turn_report
+        .then(|| range_solve::by_turn(&game_state, &range, config.clone()))
+        .transpose()?;
+    let strategy = Strategy::new(
+        range_strategy.aggregate(),
+        config.iterations,
+        range_strategy.convergence(),
+    );
+    Ok(AnalyzeOutput {
+        strategy,
+        meta: AnalyzeMeta {
+            game_state,
+            profile: None,
+            range: Some(RangeAnalysis {
+                notation,
+                strategy: range_strategy,
+                turns,
+            }),
+        },
+    })
+}
@@ -1 +1,231 @@
 //! CLI Argument parsing
+//!
+//! Holds the raw, string-typed inputs of an analysis request and turns them
+//! into a validated `GameState`. Shared by the `analyze` subcommand and the
+//! interactive session.
+
+use crate::cli::validation;
+use crate::models::{Card, GameState, Hand, Range};
+use crate::parse;
+use crate::solver::BlockerReport;
+
+/// Raw inputs describing a spot to analyze
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalyzeRequest {
+    /// Hero's hole cards (e.g., "AhKd")
+    pub hero: String,
+    /// Hero's range in Equilab notation, solved combo by combo (empty if unused)
+    pub hero_range: String,
+    /// Community cards (e.g., "Ts9s2h"), empty for preflop
+    pub board: String,
+    /// Known dead cards off the board (e.g., "2c7d"), empty if none
+    pub dead: String,
+    /// Opponent's range in Equilab notation
+    pub villain_range: String,
+    /// Pot size in big blinds
+    pub pot: f64,
+    /// Effective stack size in big blinds
+    pub stack: f64,
+    /// Amount to call in big blinds
+    pub to_call: f64,
+    /// Hero position: IP or OOP
+    pub position: String,
+    /// Solver iterations
+    pub iterations: u32,
+}
+
+impl Default for AnalyzeRequest {
+    fn default() -> Self {
+        Self {
+            hero: String::new(),
+            hero_range: String::new(),
+            board: String::new(),
+            dead: String::new(),
+            villain_range: String::new(),
+            pot: 0.0,
+            stack: 0.0,
+            to_call: 0.0,
+            position: "IP".to_string(),
+            iterations: 10_000,
+        }
+    }
+}
+
+impl AnalyzeRequest {
+    /// Parse and validate the request into a `GameState`.
+    ///
+    /// Villain combos blocked by hero's hand, the board or dead cards are
+    /// removed. Errors are returned as user-facing messages naming the offending input.
+    pub fn to_game_state(&self) -> std::result::Result<GameState, String> {
+        let hero_hand =
+            parse::parse_hand(&self.hero).map_err(|e| format!("Error parsing hero hand: {}", e))?;
+
+        let board_cards = parse_board(&self.board)?;
+
+        validation::check_duplicates(&hero_hand, &board_cards)
+            .map_err(|e| format!("Error: {}", e))?;
+
+        let position = parse::parse_position(&self.position)
+            .map_err(|e| format!("Error parsing position: {}", e))?;
+
+        let mut villain_range = parse::parse_range(&self.villain_range)
+            .map_err(|e| format!("Error parsing villain range: {}", e))?;
+
+        // Remove blockers from range
+        let mut blockers = Vec::new();
+        blockers.extend(hero_hand.cards);
+        blockers.extend(&board_cards);
+        villain_range.remove_blockers(&blockers);
+
+        let dead_cards = parse_cards(&self.dead, "Dead")?;
+
+        let mut state = GameState::new(
+            hero_hand,
+            board_cards,
+            self.pot,
+            self.stack,
+            self.to_call,
+            position,
+            villain_range,
+        )
+        .map_err(|e| format!("Error creating game state: {}", e))?;
+        state
+            .set_dead_cards(dead_cards)
+            .map_err(|e| format!("Error: {}", e))?;
+        Ok(state)
+    }
+
+    /// Parse and validate a hero-range request.
+    ///
+    /// Returns the spot with villain's board and dead card blockers removed,
+    /// plus hero's range with the same blockers removed. The state's `hero_hand` is just the
+    /// first remaining hero combo; range solves replace it per combo.
+    pub fn to_range_spot(&self) -> std::result::Result<(GameState, Range), String> {
+        let board_cards = parse_board(&self.board)?;
+        validation::check_board_duplicates(&board_cards).map_err(|e| format!("Error: {}", e))?;
+
+        let dead_cards = parse_cards(&self.dead, "Dead")?;
+
+        let mut hero_range = parse::parse_range(&self.hero_range)
+            .map_err(|e| format!("Error parsing hero range: {}", e))?;
+        hero_range.remove_blockers(&board_cards);
+        hero_range.remove_blockers(&dead_cards);
+
+        let mut hero_combos: Vec<&Hand> = hero_range.hands().map(|(hand, _)| hand).collect();
+        hero_combos.sort_by_key(|hand| hand.cards.map(|c| c.to_pokers_index()));
+        let placeholder = hero_combos
+            .first()
+            .map(|hand| (*hand).clone())
+            .ok_or("Error parsing hero range: no combos left after removing board cards")?;
+
+        let position = parse::parse_position(&self.position)
+            .map_err(|e| format!("Error parsing position: {}", e))?;
+
+        let mut villain_range = parse::parse_range(&self.villain_range)
+            .map_err(|e| format!("Error parsing villain range: {}", e))?;
+        villain_range.remove_blockers(&board_cards);
+
+        let mut state = GameState::new(
+            placeholder,
+            board_cards,
+            self.pot,
+            self.stack,
+            self.to_call,
+            position,
+            villain_range,
+        )
+        .map_err(|e| format!("Error creating game state: {}", e))?;
+        state
+            .set_dead_cards(dead_cards)
+            .map_err(|e| format!("Error: {}", e))?;
+
+        Ok((state, hero_range))
+    }
+
+    /// Villain value and bluff combos blocked by hero's hand, counted over
+    /// the villain range before hero's blockers are removed
+    pub fn blocker_report(&self) -> std::result::Result<BlockerReport, String> {
+        let hero_hand =
+            parse::parse_hand(&self.hero).map_err(|e| format!("Error parsing hero hand: {}", e))?;
+        let board_cards = parse_board(&self.board)?;
+        let villain_range = parse::parse_range(&self.villain_range)
+            .map_err(|e| format!("Error parsing villain range: {}", e))?;
+        Ok(villain_range.blocker_report(&hero_hand, &board_cards))
+    }
+
+    /// The same spot with villain deciding: villain's range acts, from
+    /// villain's position, against hero's hand or range as the opponent's
+    /// range.
+    ///
+    /// Only an unbet spot swaps: with a bet to call, villain made it and
+    /// has no decision left on this street. The result is a range request
+    /// (`hero_range` set, `hero` empty).
+    pub fn villain_perspective(&self) -> std::result::Result<Self, String> {
+        if self.to_call > 0.0 {
+            return Err(
+                "Error: --perspective villain requires --to-call 0; villain made the bet hero faces"
+                    .to_string(),
+            );
+        }
+        let opponent = if self.hero.is_empty() {
+            self.hero_range.clone()
+        } else {
+            parse::parse_hand(&self.hero)
+                .map_err(|e| format!("Error parsing hero hand: {}", e))?
+                .combo_notation()
+        };
+        let position = parse::parse_position(&self.position)
+            .map_err(|e| format!("Error parsing position: {}", e))?;
+
+        Ok(Self {
+            hero: String::new(),
+            hero_range: self.villain_range.clone(),
+            villain_range: opponent,
+            position: format!("{:?}", position.other()),
+            ..self.clone()
+        })
+    }
+}
+
+/// Effective stack from exactly one of an absolute `stack` or an `spr`
+/// (stack-to-pot ratio, giving `spr * pot`)
+pub fn resolve_stack(
+    pot: f64,
+    stack: Option<f64>,
+    spr: Option<f64>,
+) -> std::result::Result<f64, String> {
+    match (stack, spr) {
+        (Some(stack), None) => Ok(stack),
+        (None, Some(spr)) => Ok(spr * pot),
+        (Some(_), Some(_)) => Err("Error: Specify only one of --stack or --spr".to_string()),
+        (None, None) => Err("Error: One of --stack or --spr is required".to_string()),
+    }
+}
+
+/// Parse a board string such as "Ts9s2h"
+fn parse_board(board: &str) -> std::result::Result<Vec<Card>, String> {
+    parse::parse_board(board)
+        .map(Vec::from)
+        .map_err(|e| format!("Error: {}", e))
+}
+
+/// Parse a concatenated card string; `name` labels the input in errors
+fn parse_cards(cards: &str, name: &str) -> std::result::Result<Vec<Card>, String> {
+    let mut parsed = Vec::new();
+    if cards.is_empty() {
+        return Ok(parsed);
+    }
+
+    let chars: Vec<char> = cards.chars().collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(format!("Error: {} string length must be even", name));
+    }
+    for chunk in chars.chunks(2) {
+        let s: String = chunk.iter().collect();
+        let card = validation::validate_card(&s)
+            .map_err(|e| format!("Error parsing {} card '{}': {}", name.to_lowercase(), s, e))?;
+        parsed.push(card);
+    }
+
+    Ok(parsed)
+}
@@ -0,0 +1,93 @@
+//! Runout equity as a library call
+//!
+//! `equity` is the `equity` subcommand without argument parsing or
+//! printing: it works out hero's equity for an already validated
+//! `EquityInput`.
+
+use crate::models::{Card, Hand, Range};
+use crate::parse;
+use crate::solver::equity::{calculate_equity_runout, equity_by_next_card, Equity};
+
+/// A validated hand, board and villain range
+#[derive(Debug, Clone)]
+pub struct EquityInput {
+    /// Hero's hole cards
+    pub hero: Hand,
+    /// A flop, turn or river
+    pub board: Vec<Card>,
+    /// Villain's range, with combos blocked by hero or the board removed
+    pub villain_range: Range,
+    /// Also work out the equity after each possible next card
+    pub by_runout: bool,
+}
+
+impl EquityInput {
+    /// Parse and validate the `equity` subcommand's arguments.
+    ///
+    /// Errors are returned as user-facing messages.
+    pub fn parse(
+        hero: &str,
+        board: &str,
+        villain_range: &str,
+        by_runout: bool,
+    ) -> std::result::Result<Self, String> {
+        let parse_error = |e: crate::ModelError| format!("Error: {}", e);
+        let hero = parse::parse_hand(hero).map_err(parse_error)?;
+        let board = Vec::from(parse::parse_board(board).map_err(parse_error)?);
+        let mut villain_range = parse::parse_range(villain_range).map_err(parse_error)?;
+        if board.is_empty() {
+            return Err("Error: equity needs a flop, turn or river board".to_string());
+        }
+        if let Some(card) = hero.cards.iter().find(|c| board.contains(c)) {
+            return Err(format!(
+                "Error: {} is both in hero's hand and on the board",
+                card
+            ));
+        }
+        if by_runout && board.len() == 5 {
+            return Err("Error: --by-runout needs a flop or turn board".to_string());
+        }
+        villain_range.remove_blockers(&hero.cards);
+        villain_range.remove_blockers(&board);
+        if villain_range.is_empty() {
+            return Err(
+                "Error: villain range has no combos left after removing hero's cards and the board"
+                    .to_string(),
+            );
+        }
+
+        Ok(Self {
+            hero,
+            board,
+            villain_range,
+            by_runout,
+        })
+    }
+}
+
+/// Result of `equity`
+#[derive(Debug, Clone)]
+pub struct EquityOutput {
+    /// Hero's hole cards
+    pub hero: Hand,
+    /// The board the equity is for
+    pub board: Vec<Card>,
+    /// Hero's equity over every runout
+    pub overall: Equity,
+    /// Hero's equity after each next card, if `by_runout` was set
+    pub runouts: Option<Vec<(Card, Equity)>>,
+}
+
+/// Hero's equity against the villain range over every runout of the board
+pub fn equity(input: &EquityInput) -> EquityOutput {
+    let overall = calculate_equity_runout(&input.hero, &input.villain_range, &input.board);
+    let runouts = input
+        .by_runout
+        .then(|| equity_by_next_card(&input.hero, &input.villain_range, &input.board, &[]));
+    EquityOutput {
+        hero: input.hero.clone(),
+        board: input.board.clone(),
+        overall,
+        runouts,
+    }
+}
@@ -1,5 +1,8 @@
 //! CLI module components
 
+pub mod analyze;
 pub mod args;
+pub mod equity;
 pub mod output;
+pub mod repl;
 pub mod validation;
@@ -1,6 +1,17 @@
 //! Output formatting for CLI
 
-use crate::models::strategy::Strategy;
+use crate::cli::analyze::{AnalyzeOutput, Perspective, RangeAnalysis};
+use crate::cli::equity::EquityOutput;
+use crate::models::action::{Action, BetSize};
+use crate::models::card::{Card, Rank, Suit};
+use crate::models::game_state::GameState;
+use crate::models::hand::Hand;
+use crate::models::strategy::{descending_nan_last, ActionStrategy, RangeStrategy, Strategy};
+use crate::solver::blockers::BlockerReport;
+use crate::solver::equity::{calculate_equity_runout_with_dead, Equity};
+use crate::solver::evaluator::{hand_category, HandCategory};
+use crate::solver::range_solve::by_category;
+use std::fmt::Write;
 use tabled::{Table, Tabled};
 
 /// Row structure for the strategy table
@@ -16,18 +27,572 @@ struct StrategyRow {
     ev: String,
 }
 
-/// Format strategy as an ASCII table
+/// Row order of the strategy table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActionOrder {
+    /// Most frequent action first
+    #[default]
+    Frequency,
+    /// Fold, check, call, bets and raises by size, all-in
+    Canonical,
+}
+
+/// Format strategy as an ASCII table, most frequent action first
 pub fn format_strategy_table(strategy: &Strategy) -> String {
-    let sorted_actions = strategy.sorted_by_frequency();
+    format_strategy_table_with(strategy, ActionOrder::Frequency, None)
+}
+
+/// Format strategy as an ASCII table with `decimals` places for both
+/// frequencies and EVs
+pub fn format_strategy_table_with_precision(strategy: &Strategy, decimals: usize) -> String {
+    format_strategy_table_with(strategy, ActionOrder::Frequency, Some(decimals))
+}
+
+/// Format strategy as an ASCII table with rows in `order` and `precision`
+/// decimal places for frequencies and EVs (by default 1 and 2).
+///
+/// A strategy without actions renders as "(no actions)". When the strategy
+/// carries its game state, bets and raises sized relative to the pot or the
+/// bet faced also show the chips they commit, e.g. "Bet 50% pot (5.0 BB)".
+pub fn format_strategy_table_with(
+    strategy: &Strategy,
+    order: ActionOrder,
+    precision: Option<usize>,
+) -> String {
+    if strategy.actions.is_empty() {
+        return "(no actions)".to_string();
+    }
+    let sorted_actions = match order {
+        ActionOrder::Frequency => strategy.sorted_by_frequency(),
+        ActionOrder::Canonical => strategy.sorted_canonically(),
+    };
+    let frequency_decimals = precision.unwrap_or(1);
+    let ev_decimals = precision.unwrap_or(2);
 
     let rows: Vec<StrategyRow> = sorted_actions
         .into_iter()
         .map(|a| StrategyRow {
-            action: a.action.display_name(),
-            frequency: format!("{:.1}%", a.frequency * 100.0),
-            ev: format!("{:+6.2}", a.ev), // Always sign, width 6
+            action: action_label(&a.action, strategy.game_state.as_ref()),
+            frequency: format_percent(a.frequency, frequency_decimals),
+            ev: format_ev(a.ev, ev_decimals),
+        })
+        .collect();
+
+    Table::new(rows).to_string()
+}
+
+/// `action`'s display name, followed by the chips it commits at `state`
+/// when its name doesn't already say
+fn action_label(action: &Action, state: Option<&GameState>) -> String {
+    let name = action.display_name();
+    match (action, state) {
+        (
+            Action::Bet(BetSize::PotFraction(_) | BetSize::Multiplier(_))
+            | Action::Raise(BetSize::PotFraction(_) | BetSize::Multiplier(_)),
+            Some(state),
+        ) => format!(
+            "{} ({:.1} BB)",
+            name,
+            action.amount(state.pot_size, state.effective_stack, state.to_call)
+        ),
+        _ => name,
+    }
+}
+
+/// A share (0.0-1.0) as a percentage with `decimals` places, e.g. "62.5%".
+///
+/// Rust's formatting ignores the system locale, so the decimal separator is
+/// always `.`.
+pub fn format_percent(share: f64, decimals: usize) -> String {
+    format!("{:.*}%", decimals, share * 100.0)
+}
+
+/// EV in big blinds with `decimals` places, always signed and at least six
+/// characters wide, e.g. " +1.25"
+pub fn format_ev(ev: f64, decimals: usize) -> String {
+    format!("{:+6.*}", decimals, ev)
+}
+
+/// One-line description of the strategy shape, e.g. "Pure strategy: Bet 75% pot"
+pub fn format_strategy_summary(strategy: &Strategy) -> String {
+    match strategy.dominant_action() {
+        Some(a) if strategy.is_pure(Strategy::PURE_TOLERANCE) => {
+            format!("Pure strategy: {}", a.action.display_name())
+        }
+        Some(a) => format!(
+            "Mixed strategy (mostly {} at {:.1}%)",
+            a.action.display_name(),
+            a.frequency * 100.0
+        ),
+        None => "No actions available".to_string(),
+    }
+}
+
+/// Pot odds and minimum defense frequencies behind the strategy, one per
+/// line: hero's when facing a bet, villain's for each bet or raise hero
+/// takes. Empty when neither applies or the strategy has no game state.
+pub fn format_defense_summary(strategy: &Strategy) -> String {
+    let Some(state) = &strategy.game_state else {
+        return String::new();
+    };
+
+    let mut lines = Vec::new();
+    if state.to_call > 0.0 {
+        lines.push(format!(
+            "Pot odds: {:.1}% (MDF {:.1}%)",
+            state.pot_odds() * 100.0,
+            state.mdf() * 100.0
+        ));
+    }
+    for a in strategy.sorted_by_frequency() {
+        if a.frequency <= 0.0
+            || !matches!(a.action, Action::Bet(_) | Action::Raise(_) | Action::AllIn)
+        {
+            continue;
+        }
+        let amount = a
+            .action
+            .amount(state.pot_size, state.effective_stack, state.to_call);
+        lines.push(format!(
+            "Villain MDF vs {}: {:.1}%",
+            a.action.display_name(),
+            state.bet_mdf(amount) * 100.0
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Short plain-language rationale for the dominant action.
+///
+/// Combines hero's hand category, equity against the villain range, the
+/// price of calling when facing a bet, and the dominant action's frequency
+/// and EV. Empty if the strategy has no game state or no actions.
+pub fn format_explanation(strategy: &Strategy) -> String {
+    let (Some(state), Some(top)) = (&strategy.game_state, strategy.dominant_action()) else {
+        return String::new();
+    };
+
+    let equity = calculate_equity_runout_with_dead(
+        &state.hero_hand,
+        &state.villain_range,
+        &state.board,
+        &state.dead_cards,
+    );
+    let mut text = format!(
+        "You have {} with {:.0}% equity against the villain range.",
+        hand_category(&state.hero_hand, &state.board),
+        equity.equity() * 100.0
+    );
+    if state.to_call > 0.0 {
+        text.push_str(&format!(
+            " Calling needs {:.0}% equity.",
+            state.pot_odds() * 100.0
+        ));
+    }
+    text.push_str(&format!(
+        " {} is {:+.1} BB and chosen {:.0}% of the time.",
+        top.action.display_name(),
+        top.ev,
+        top.frequency * 100.0
+    ));
+    if let Some(recommended) = strategy.recommended_action() {
+        text.push_str(&format!(
+            " Playing one action only, {} is recommended.",
+            recommended.action.display_name()
+        ));
+    }
+    text
+}
+
+/// Row structure for the bet size comparison
+#[derive(Tabled)]
+struct SizeRow {
+    #[tabled(rename = "Size")]
+    size: String,
+
+    #[tabled(rename = "Pure EV (BB)")]
+    ev: String,
+
+    #[tabled(rename = "GTO Frequency")]
+    frequency: String,
+}
+
+/// Each bet or raise size's EV when always taken, against the solved
+/// responses, next to how often the mixed strategy uses it. Highest EV
+/// first, ending with e.g. "Best size: Bet 100% pot (+1.20 BB pure, used
+/// 30.0%)". Empty if the strategy has no bet or raise.
+pub fn format_size_comparison(strategy: &Strategy) -> String {
+    let mut sizes: Vec<&ActionStrategy> = strategy
+        .actions
+        .iter()
+        .filter(|a| matches!(a.action, Action::Bet(_) | Action::Raise(_) | Action::AllIn))
+        .collect();
+    sizes.sort_by(|a, b| descending_nan_last(a.ev, b.ev));
+    let Some(best) = sizes.first() else {
+        return String::new();
+    };
+
+    let summary = format!(
+        "Best size: {} ({:+.2} BB pure, used {})",
+        best.action.display_name(),
+        best.ev,
+        format_percent(best.frequency, 1)
+    );
+    let rows = sizes.iter().map(|a| SizeRow {
+        size: a.action.display_name(),
+        ev: format_ev(a.ev, 2),
+        frequency: format_percent(a.frequency, 1),
+    });
+    format!("{}\n{}", Table::new(rows), summary)
+}
+
+/// Row structure for the equity-by-next-card grid
+#[derive(Tabled)]
+struct RunoutRow {
+    #[tabled(rename = "Rank")]
+    rank: char,
+
+    #[tabled(rename = "♠")]
+    spades: String,
+
+    #[tabled(rename = "♥")]
+    hearts: String,
+
+    #[tabled(rename = "♣")]
+    clubs: String,
+
+    #[tabled(rename = "♦")]
+    diamonds: String,
+}
+
+/// Hero's equity after each next card (see `equity_by_next_card`) as a
+/// rank by suit grid, Aces first, followed by the best and worst card
+/// against the `overall` equity, e.g. "Best: 7♥ 88.6%, worst: 9♣ 21.0%
+/// (overall 45.2%)". Cards that can't come, or that leave villain no
+/// combos, show "-".
+pub fn format_runout_equity(runouts: &[(Card, Equity)], overall: f64) -> String {
+    let cell = |rank: Rank, suit: Suit| {
+        runouts
+            .iter()
+            .find(|(card, equity)| *card == Card::new(rank, suit) && equity.is_valid())
+            .map_or_else(|| "-".to_string(), |(_, e)| format_percent(e.equity(), 1))
+    };
+    let rows = Rank::ALL.iter().rev().map(|&rank| RunoutRow {
+        rank: char::from(rank),
+        spades: cell(rank, Suit::Spades),
+        hearts: cell(rank, Suit::Hearts),
+        clubs: cell(rank, Suit::Clubs),
+        diamonds: cell(rank, Suit::Diamonds),
+    });
+
+    let mut dealt: Vec<&(Card, Equity)> = runouts.iter().filter(|(_, e)| e.is_valid()).collect();
+    dealt.sort_by(|a, b| descending_nan_last(a.1.equity(), b.1.equity()));
+    let (Some(best), Some(worst)) = (dealt.first(), dealt.last()) else {
+        return Table::new(rows).to_string();
+    };
+    format!(
+        "{}\nBest: {} {}, worst: {} {} (overall {})",
+        Table::new(rows),
+        best.0,
+        format_percent(best.1.equity(), 1),
+        worst.0,
+        format_percent(worst.1.equity(), 1),
+        format_percent(overall, 1)
+    )
+}
+
+/// Villain value and bluff combos before and after hero's blockers, with
+/// the blocked combos listed, e.g.
+/// "Value combos: 6.0 -> 3.0 (blocked AhQh flush, ...)"
+pub fn format_blocker_report(report: &BlockerReport) -> String {
+    let list = |blocked: &[(Hand, HandCategory)]| {
+        if blocked.is_empty() {
+            return String::new();
+        }
+        let combos: Vec<String> = blocked
+            .iter()
+            .map(|(hand, category)| format!("{} {}", hand.combo_notation(), category))
+            .collect();
+        format!(" (blocked {})", combos.join(", "))
+    };
+    format!(
+        "Value combos: {:.1} -> {:.1}{}\nBluff combos: {:.1} -> {:.1}{}",
+        report.value_combos,
+        report.value_combos_after(),
+        list(&report.blocked_value),
+        report.bluff_combos,
+        report.bluff_combos_after(),
+        list(&report.blocked_bluffs)
+    )
+}
+
+/// Row structure for the hand category report
+#[derive(Tabled)]
+struct CategoryRow {
+    #[tabled(rename = "Category")]
+    category: String,
+
+    #[tabled(rename = "Combos")]
+    combos: usize,
+
+    #[tabled(rename = "Strategy")]
+    strategy: String,
+
+    #[tabled(rename = "EV (BB)")]
+    ev: String,
+}
+
+/// Format a range solve grouped by hand category, e.g. "top pair | Bet 50% pot 80%, Check 20%"
+pub fn format_category_report(range_strategy: &RangeStrategy, board: &[Card]) -> String {
+    let rows: Vec<CategoryRow> = by_category(range_strategy, board)
+        .into_iter()
+        .map(|(category, group)| {
+            let mut actions = group.aggregate();
+            let ev: f64 = actions.iter().map(|a| a.frequency * a.ev).sum();
+            actions.sort_by(|a, b| descending_nan_last(a.frequency, b.frequency));
+            CategoryRow {
+                category: category.to_string(),
+                combos: group.combos.len(),
+                strategy: format_action_mix(&actions),
+                ev: format!("{:+6.2}", ev),
+            }
         })
         .collect();
 
     Table::new(rows).to_string()
 }
+
+/// Row structure for the turn report
+#[derive(Tabled)]
+struct TurnRow {
+    #[tabled(rename = "Turn")]
+    turn: String,
+
+    #[tabled(rename = "Bet")]
+    bet: String,
+
+    #[tabled(rename = "Strategy")]
+    strategy: String,
+}
+
+/// Format per-turn range strategies (from `range_solve::by_turn`), most
+/// aggressive turn first.
+///
+/// "Bet" is how often the range bets, raises or shoves on that turn.
+pub fn format_turn_report(turns: &[(Card, RangeStrategy)]) -> String {
+    let mut rows: Vec<(f64, TurnRow)> = turns
+        .iter()
+        .map(|(turn, range_strategy)| {
+            let mut actions = range_strategy.aggregate();
+            let bet: f64 = actions
+                .iter()
+                .filter(|a| matches!(a.action, Action::Bet(_) | Action::Raise(_) | Action::AllIn))
+                .map(|a| a.frequency)
+                .sum();
+            actions.sort_by(|a, b| descending_nan_last(a.frequency, b.frequency));
+            let row = TurnRow {
+                turn: turn.to_string(),
+                bet: format!("{:.1}%", bet * 100.0),
+                strategy: format_action_mix(&actions),
+            };
+            (bet, row)
+        })
+        .collect();
+    rows.sort_by(|a, b| descending_nan_last(a.0, b.0));
+
+    Table::new(rows.into_iter().map(|(_, row)| row)).to_string()
+}
+
+/// "Bet 50% pot 80%, Check 20%", skipping actions taken under 0.5% of the time
+fn format_action_mix(actions: &[ActionStrategy]) -> String {
+    actions
+        .iter()
+        .filter(|a| a.frequency >= 0.005)
+        .map(|a| format!("{} {:.0}%", a.action.display_name(), a.frequency * 100.0))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How `format_analysis` lays out a solve
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalysisFormat {
+    /// Pretty JSON of the strategy (of every combo's strategy for a range)
+    pub json: bool,
+    /// Only the strategy table, without the input summary
+    pub quiet: bool,
+    /// Row order of the strategy table
+    pub order: ActionOrder,
+    /// Decimal places in the strategy table (see `format_strategy_table_with`)
+    pub precision: Option<usize>,
+    /// Whose range a range solve is, for its summary
+    pub perspective: Perspective,
+    /// Add the `format_size_comparison` table
+    pub size_comparison: bool,
+    /// Add the `format_explanation` of a single-hand solve
+    pub explain: bool,
+    /// Add the `format_category_report` of a range solve
+    pub categories: bool,
+}
+
+/// An `analyze` result as the `analyze` subcommand prints it: an input
+/// summary, the strategy table and the extra sections `format` asks for,
+/// with `blocker_report` last if given
+pub fn format_analysis(
+    analysis: &AnalyzeOutput,
+    format: &AnalysisFormat,
+    blocker_report: Option<&BlockerReport>,
+) -> String {
+    if let Some(range) = &analysis.meta.range {
+        return format_range_analysis(analysis, range, format);
+    }
+    let strategy = &analysis.strategy;
+    let table = format_strategy_table_with(strategy, format.order, format.precision);
+    if format.json {
+        return serde_json::to_string_pretty(strategy).expect("strategies serialize");
+    }
+    if format.quiet {
+        if !format.size_comparison {
+            return table;
+        }
+        return format!("{}\n{}", table, format_size_comparison(strategy));
+    }
+
+    let game_state = &analysis.meta.game_state;
+    let mut out = String::new();
+    writeln!(out, "Input Summary:").unwrap();
+    writeln!(out, "  Hero: {}", game_state.hero_hand.notation()).unwrap();
+    writeln!(out, "  Board: {}", format_board(&game_state.board)).unwrap();
+    if !game_state.dead_cards.is_empty() {
+        writeln!(out, "  Dead: {}", format_board(&game_state.dead_cards)).unwrap();
+    }
+    writeln!(out, "{}", format_stakes(game_state)).unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "Strategy computed in {} iterations (convergence: {})",
+        strategy.iterations, strategy.convergence
+    )
+    .unwrap();
+    // Mixed actions should be worth the same; a wide gap means the solve
+    // needs more iterations
+    if strategy.is_mixed() {
+        writeln!(
+            out,
+            "Indifference spread: {:.2} {} between mixed actions",
+            strategy.indifference_spread(),
+            strategy.ev_unit
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+
+    writeln!(out, "{}", table).unwrap();
+    write!(out, "{}", format_strategy_summary(strategy)).unwrap();
+    let defense = format_defense_summary(strategy);
+    if !defense.is_empty() {
+        write!(out, "\n{}", defense).unwrap();
+    }
+    if format.size_comparison {
+        write!(out, "\n\n{}", format_size_comparison(strategy)).unwrap();
+    }
+    if format.explain {
+        write!(out, "\n\n{}", format_explanation(strategy)).unwrap();
+    }
+    if let Some(report) = blocker_report {
+        write!(out, "\n\n{}", format_blocker_report(report)).unwrap();
+    }
+    out
+}
+
+/// `format_analysis` of a range solve
+fn format_range_analysis(
+    analysis: &AnalyzeOutput,
+    range: &RangeAnalysis,
+    format: &AnalysisFormat,
+) -> String {
+    if format.json {
+        return serde_json::to_string_pretty(&range.strategy).expect("strategies serialize");
+    }
+
+    let mut out = String::new();
+    if !format.quiet {
+        let game_state = &analysis.meta.game_state;
+        let acting = match format.perspective {
+            Perspective::Hero => "Hero",
+            Perspective::Villain => "Villain",
+        };
+        writeln!(out, "Input Summary:").unwrap();
+        writeln!(
+            out,
+            "  {} Range: {} ({} combos)",
+            acting,
+            range.notation,
+            range.strategy.combos.len()
+        )
+        .unwrap();
+        writeln!(out, "  Board: {}", format_board(&game_state.board)).unwrap();
+        writeln!(out, "{}", format_stakes(game_state)).unwrap();
+        writeln!(out).unwrap();
+    }
+
+    write!(
+        out,
+        "{}",
+        format_strategy_table_with(&analysis.strategy, format.order, format.precision)
+    )
+    .unwrap();
+    if format.categories {
+        let board = &analysis.meta.game_state.board;
+        write!(
+            out,
+            "\n\n{}",
+            format_category_report(&range.strategy, board)
+        )
+        .unwrap();
+    }
+    if let Some(turns) = &range.turns {
+        write!(out, "\n\n{}", format_turn_report(turns)).unwrap();
+    }
+    out
+}
+
+/// Hero's runout equity as the `equity` subcommand prints it, followed by
+/// `format_runout_equity` when the output has runouts
+pub fn format_equity(output: &EquityOutput) -> String {
+    let mut out = format!(
+        "Hero: {}  Board: {}\n{}\nEquity: {}",
+        output.hero.notation(),
+        format_board(&output.board),
+        output.overall,
+        format_percent(output.overall.equity(), 1)
+    );
+    if let Some(runouts) = &output.runouts {
+        write!(
+            out,
+            "\n\n{}",
+            format_runout_equity(runouts, output.overall.equity())
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Cards separated by spaces, or "(none)"
+fn format_board(cards: &[Card]) -> String {
+    if cards.is_empty() {
+        return "(none)".to_string();
+    }
+    cards
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "  Pot: 10.0 BB, Stack: 100.0 BB, To Call: 0.0 BB"
+fn format_stakes(state: &GameState) -> String {
+    format!(
+        "  Pot: {:.1} BB, Stack: {:.1} BB, To Call: {:.1} BB",
+        state.pot_size, state.effective_stack, state.to_call
+    )
+}
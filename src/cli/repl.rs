@@ -0,0 +1,199 @@
+//! Interactive session for iterating on a single spot
+//!
+//! Users adjust one input at a time (`set pot 12`, `set villain 22+`) and
+//! re-solve without restarting the process. The last solved `GameState` and
+//! `Strategy` are kept between commands. With a seed set, re-solving a spot
+//! already solved in the session is answered from a `SolveCache`; without
+//! one every solve draws a fresh sample.
+
+use crate::cli::args::AnalyzeRequest;
+use crate::cli::output;
+use crate::models::{GameState, Strategy};
+use crate::solver::{MccfrConfig, SolveCache, SolverKind};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+const HELP: &str = "Commands:
+  set <field> <value>  Update an input (hero, board, villain, pot, stack, to_call, position, iterations, seed)
+  solve                Solve the current spot
+  show                 Print the current inputs and last strategy
+  help                 Show this message
+  quit | exit          Leave the session";
+
+/// Result of handling a single REPL line
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplResponse {
+    /// Text to print back to the user (may be empty)
+    Output(String),
+    /// The user asked to leave the session
+    Quit,
+}
+
+/// State kept across REPL commands
+#[derive(Debug, Default)]
+pub struct Session {
+    request: AnalyzeRequest,
+    last_state: Option<GameState>,
+    last_strategy: Option<Strategy>,
+    seed: Option<u64>,
+    cache: SolveCache,
+}
+
+impl Session {
+    /// Create a session with default inputs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current (unsolved) inputs
+    pub fn request(&self) -> &AnalyzeRequest {
+        &self.request
+    }
+
+    /// Game state of the most recent successful solve
+    pub fn last_state(&self) -> Option<&GameState> {
+        self.last_state.as_ref()
+    }
+
+    /// Strategy of the most recent successful solve
+    pub fn last_strategy(&self) -> Option<&Strategy> {
+        self.last_strategy.as_ref()
+    }
+
+    /// Handle one line of input.
+    ///
+    /// Errors are returned as user-facing messages; the session stays usable.
+    pub fn handle(&mut self, line: &str) -> std::result::Result<ReplResponse, String> {
+        let mut parts = line.split_whitespace();
+        let Some(command) = parts.next() else {
+            return Ok(ReplResponse::Output(String::new()));
+        };
+
+        match command {
+            "set" => {
+                let field = parts.next().ok_or("Usage: set <field> <value>")?;
+                let value: Vec<&str> = parts.collect();
+                self.set(field, &value.join(""))?;
+                Ok(ReplResponse::Output(String::new()))
+            }
+            "solve" => self.solve().map(ReplResponse::Output),
+            "show" => Ok(ReplResponse::Output(self.show())),
+            "help" => Ok(ReplResponse::Output(HELP.to_string())),
+            "quit" | "exit" => Ok(ReplResponse::Quit),
+            other => Err(format!("Unknown command '{}' (try 'help')", other)),
+        }
+    }
+
+    fn set(&mut self, field: &str, value: &str) -> std::result::Result<(), String> {
+        let number = |v: &str| {
+            v.parse::<f64>()
+                .map_err(|_| format!("Invalid number '{}' for {}", v, field))
+        };
+
+        match field {
+            "hero" => self.request.hero = value.to_string(),
+            "board" => self.request.board = value.to_string(),
+            "villain" | "villain_range" => self.request.villain_range = value.to_string(),
+            "pot" => self.request.pot = number(value)?,
+            "stack" => self.request.stack = number(value)?,
+            "to_call" => self.request.to_call = number(value)?,
+            "position" => self.request.position = value.to_string(),
+            "iterations" => {
+                self.request.iterations = value
+                    .parse()
+                    .map_err(|_| format!("Invalid iteration count '{}'", value))?
+            }
+            "seed" => {
+                self.seed = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed '{}'", value))?,
+                )
+            }
+            other => return Err(format!("Unknown field '{}' (try 'help')", other)),
+        }
+        Ok(())
+    }
+
+    fn solve(&mut self) -> std::result::Result<String, String> {
+        let state = self.request.to_game_state()?;
+        let config = MccfrConfig::builder()
+            .iterations(self.request.iterations)
+            .seed(self.seed)
+            .build();
+        let strategy = self
+            .cache
+            .solve(state.clone(), SolverKind::Mccfr, config)
+            .map_err(|e| format!("Solver error: {}", e))?;
+
+        let rendered = format!(
+            "Strategy computed in {} iterations (convergence: {})\n{}",
+            strategy.iterations,
+            strategy.convergence,
+            output::format_strategy_table(&strategy)
+        );
+
+        self.last_state = Some(state);
+        self.last_strategy = Some(strategy);
+        Ok(rendered)
+    }
+
+    fn show(&self) -> String {
+        let r = &self.request;
+        let mut lines = vec![
+            format!("  Hero: {}", r.hero),
+            format!(
+                "  Board: {}",
+                if r.board.is_empty() {
+                    "(none)"
+                } else {
+                    &r.board
+                }
+            ),
+            format!("  Villain: {}", r.villain_range),
+            format!(
+                "  Pot: {:.1} BB, Stack: {:.1} BB, To Call: {:.1} BB",
+                r.pot, r.stack, r.to_call
+            ),
+            format!("  Position: {}, Iterations: {}", r.position, r.iterations),
+        ];
+        if let Some(seed) = self.seed {
+            lines.push(format!("  Seed: {}", seed));
+        }
+        if let Some(strategy) = &self.last_strategy {
+            lines.push(String::new());
+            lines.push(output::format_strategy_table(strategy));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Run the interactive loop on stdin/stdout until the user quits
+pub fn run() -> std::result::Result<(), ReadlineError> {
+    let mut editor = DefaultEditor::new()?;
+    let mut session = Session::new();
+
+    println!("fpe interactive mode. Type 'help' for commands.");
+    loop {
+        match editor.readline("fpe> ") {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                match session.handle(&line) {
+                    Ok(ReplResponse::Output(text)) => {
+                        if !text.is_empty() {
+                            println!("{}", text);
+                        }
+                    }
+                    Ok(ReplResponse::Quit) => break,
+                    Err(message) => eprintln!("{}", message),
+                }
+            }
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
@@ -43,6 +43,17 @@ pub fn check_duplicates(hero: &Hand, board: &[Card]) -> Result<()> {
     Ok(())
 }
 
+/// Check for cards repeated on the board
+pub fn check_board_duplicates(board: &[Card]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for card in board {
+        if !seen.insert(*card) {
+            return Err(ModelError::DuplicateCard(card.to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// Validate complete game state
 pub fn validate_game_state(state: &GameState) -> Result<()> {
     // Basic structural validation is done in GameState::new
@@ -39,7 +39,71 @@ pub enum ModelError {
     /// Range is empty after blocker removal
     #[error("Empty range after removing blockers")]
     EmptyRange,
+
+    /// A spot input (hand, board, range, position) could not be parsed
+    #[error("Invalid {field} '{input}': {reason}")]
+    InvalidInput {
+        /// Which input, e.g. "board"
+        field: &'static str,
+        /// The text as given
+        input: String,
+        /// What was wrong with it
+        reason: String,
+    },
+
+    /// A serialized strategy could not be loaded
+    #[error("Invalid strategy: {0}")]
+    InvalidStrategy(String),
+
+    /// The same combo appears in more than one range strategy being merged
+    #[error("Combo '{0}' appears in more than one range strategy")]
+    OverlappingCombo(String),
+
+    /// The solver could not produce a strategy for an otherwise valid spot
+    #[error("{0}")]
+    Solver(String),
 }
 
 /// Result type for model operations
 pub type Result<T> = std::result::Result<T, ModelError>;
+
+/// Errors reported by the `fpe` binary, each mapped to its own exit code.
+///
+/// | Code | Meaning |
+/// |------|---------|
+/// | 0    | Success |
+/// | 2    | Invalid arguments or spot (`Parse`; also used by clap itself) |
+/// | 3    | The solver failed (`Solver`) |
+/// | 4    | Terminal or file I/O failed (`Io`) |
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// Invalid arguments, cards, ranges or game state
+    #[error("{0}")]
+    Parse(String),
+
+    /// The solver could not produce a strategy
+    #[error("Solver error: {0}")]
+    Solver(String),
+
+    /// Reading input or writing output failed
+    #[error("Error: {0}")]
+    Io(String),
+}
+
+impl CliError {
+    /// Exit code for invalid input
+    pub const PARSE_EXIT_CODE: i32 = 2;
+    /// Exit code for solver failures
+    pub const SOLVER_EXIT_CODE: i32 = 3;
+    /// Exit code for I/O failures
+    pub const IO_EXIT_CODE: i32 = 4;
+
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Parse(_) => Self::PARSE_EXIT_CODE,
+            CliError::Solver(_) => Self::SOLVER_EXIT_CODE,
+            CliError::Io(_) => Self::IO_EXIT_CODE,
+        }
+    }
+}
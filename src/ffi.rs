@@ -0,0 +1,162 @@
+//! C API (`ffi` feature)
+//!
+//! Lets non-Rust callers (Python via ctypes, JS via an FFI binding, ...)
+//! solve a spot through a shared library. Spots go in as C strings in the
+//! same notation as the CLI and strategies come back as the JSON of a
+//! `Strategy`. Every entry point validates its pointers and catches panics,
+//! so nothing unwinds into the caller.
+//!
+//! The crate builds as an rlib only; build the shared library with
+//!
+//! ```text
+//! cargo rustc --release --features ffi --lib --crate-type cdylib
+//! ```
+
+use crate::cli::args::AnalyzeRequest;
+use crate::solver;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+
+/// Solve a single-hand spot and return the strategy as JSON.
+///
+/// `hero` is hole cards such as "AhKd", `board` community cards such as
+/// "Ts9s2h" (empty for preflop), `villain_range` Equilab notation and
+/// `position` "IP" or "OOP"; amounts are in big blinds. On invalid input or
+/// a failed solve the result is `{"error": "<message>"}` instead.
+///
+/// The returned string is never null and must be released with
+/// `fpe_free_string`.
+///
+/// # Safety
+///
+/// Each string argument must be null or point to a NUL-terminated string
+/// that stays valid for the duration of the call.
+// A C API takes the spot as plain arguments
+#[allow(clippy::too_many_arguments)]
+#[no_mangle]
+pub unsafe extern "C" fn fpe_solve(
+    hero: *const c_char,
+    board: *const c_char,
+    villain_range: *const c_char,
+    pot: f64,
+    stack: f64,
+    to_call: f64,
+    position: *const c_char,
+    iterations: u32,
+) -> *mut c_char {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let request = AnalyzeRequest {
+            hero: read_str(hero, "hero")?,
+            board: read_str(board, "board")?,
+            villain_range: read_str(villain_range, "villain_range")?,
+            pot,
+            stack,
+            to_call,
+            position: read_str(position, "position")?,
+            iterations,
+            ..AnalyzeRequest::default()
+        };
+        let game_state = request.to_game_state()?;
+        let strategy = solver::solve(game_state, iterations).map_err(|e| e.to_string())?;
+        serde_json::to_string(&strategy).map_err(|e| e.to_string())
+    }));
+    let json = match result {
+        Ok(Ok(json)) => json,
+        Ok(Err(message)) => error_json(&message),
+        Err(_) => error_json("internal error: the solver panicked"),
+    };
+    into_c_string(json)
+}
+
+/// Release a string returned by this API. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a pointer returned by an `fpe_` function that has
+/// not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn fpe_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Borrow `ptr` as UTF-8; `name` labels the argument in errors
+///
+/// # Safety
+///
+/// As for the string arguments of `fpe_solve`.
+unsafe fn read_str(ptr: *const c_char, name: &str) -> Result<String, String> {
+    if ptr.is_null() {
+        return Err(format!("Error: {} is null", name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| format!("Error: {} is not valid UTF-8", name))
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn into_c_string(json: String) -> *mut c_char {
+    // JSON escapes control characters, so it never contains a NUL
+    CString::new(json)
+        .expect("JSON has no interior NUL")
+        .into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Strategy;
+    use std::ptr;
+
+    /// Call `fpe_solve` and take ownership of the result
+    fn solve(hero: *const c_char, board: &CStr, pot: f64) -> String {
+        unsafe {
+            let result = fpe_solve(
+                hero,
+                board.as_ptr(),
+                c"QQ,JJ".as_ptr(),
+                pot,
+                100.0,
+                0.0,
+                c"IP".as_ptr(),
+                200,
+            );
+            assert!(!result.is_null());
+            let json = CStr::from_ptr(result).to_str().unwrap().to_string();
+            fpe_free_string(result);
+            json
+        }
+    }
+
+    #[test]
+    fn test_solve_returns_strategy_json() {
+        let json = solve(c"AhKh".as_ptr(), c"Qh Jh Th", 10.0);
+        let strategy: Strategy = serde_json::from_str(&json).unwrap();
+        assert!(strategy.is_valid());
+        assert_eq!(strategy.iterations, 200);
+    }
+
+    #[test]
+    fn test_invalid_input_returns_error_json() {
+        let error = |json: String| {
+            let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+            value["error"].as_str().unwrap().to_string()
+        };
+
+        assert!(error(solve(c"AhAh".as_ptr(), c"", 10.0)).contains("hero"));
+        assert!(error(solve(c"AhKh".as_ptr(), c"Qh Jh", 10.0)).contains("board"));
+        assert!(error(solve(c"AhKh".as_ptr(), c"", -1.0)).contains("game state"));
+        assert_eq!(error(solve(ptr::null(), c"", 10.0)), "Error: hero is null");
+
+        let invalid_utf8 = [0xff_u8, 0];
+        let hero = CStr::from_bytes_with_nul(&invalid_utf8).unwrap();
+        assert!(error(solve(hero.as_ptr(), c"", 10.0)).contains("UTF-8"));
+
+        unsafe { fpe_free_string(ptr::null_mut()) };
+    }
+}
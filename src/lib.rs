@@ -17,5 +17,29 @@ pub mod cli;
 /// Error types
 pub mod error;
 
+/// Parsers for hands, boards, ranges, positions and bet sizes
+pub mod parse;
+
+/// C API for calling the solver from other languages (`ffi` feature)
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Proptest generators for models (`test-util` feature)
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 // Re-export commonly used types
-pub use error::{ModelError, Result};
+pub use error::{CliError, ModelError, Result};
+pub use parse::{parse_bet_sizes, parse_board, parse_hand, parse_position, parse_range};
+
+/// Version of the JSON shape of serialized `Strategy` and `Equity` values.
+///
+/// Both carry it as a top-level `schema_version` field. A `Strategy` holds
+/// `actions` (each `{action, frequency, ev}`), the frequency-weighted
+/// `root_ev`, the `ev_unit` of both (`"BB"`, or `"payout"` for ICM solves),
+/// `iterations`, `convergence` and, when present, the solved `game_state`, the `provenance` of a
+/// seeded solve and the `convergence_history` as `[iteration, metric]`
+/// pairs; an `Equity` holds `win`, `tie` and `lose` probabilities.
+/// The version is bumped whenever fields are added, removed, renamed or
+/// change meaning.
+pub const JSON_SCHEMA_VERSION: u32 = 9;
@@ -1,8 +1,12 @@
-use clap::{Parser, Subcommand};
-use fpe::cli::{output, validation};
-use fpe::models::{GameState, Position};
-use fpe::solver;
-use std::str::FromStr;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use fpe::cli::analyze::{self, AnalyzeInput, Perspective};
+use fpe::cli::args::{self, AnalyzeRequest};
+use fpe::cli::equity::{self, EquityInput};
+use fpe::cli::output::{self, ActionOrder, AnalysisFormat};
+use fpe::cli::repl;
+use fpe::models::{presets, BetSize};
+use fpe::solver::{MccfrConfig, SolverKind};
+use fpe::CliError;
 
 /// Poker GTO Strategy Engine
 ///
@@ -11,185 +15,516 @@ use std::str::FromStr;
 #[command(name = "fpe")]
 #[command(about = "Poker GTO Strategy Engine", long_about = None)]
 #[command(version)]
+#[command(after_help = "Exit codes: 0 success, 2 invalid input, 3 solver error, 4 I/O error")]
 struct Cli {
+    /// Print the preset ranges usable as `@name` in range notation
+    #[arg(long)]
+    list_ranges: bool,
+
     #[command(subcommand)]
-    command: Commands,
+    command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Calculate GTO strategy for a decision point
-    Analyze {
+    Analyze(AnalyzeArgs),
+
+    /// Hero's equity against a range over every runout of a flop, turn or river
+    Equity {
         /// Hero's hole cards (e.g., "AhKd")
         #[arg(long)]
         hero: String,
 
-        /// Community cards (e.g., "Ts9s2h")
-        #[arg(long, default_value = "")]
+        /// Community cards: a flop, turn or river (e.g., "Ts9s2h")
+        #[arg(long)]
         board: String,
 
-        /// Opponent's range in Equilab notation
+        /// Opponent's range in Equilab notation; `@name` uses a preset (see --list-ranges)
         #[arg(long)]
         villain_range: String,
 
-        /// Pot size in big blinds
+        /// On a flop or turn, also show hero's equity after each possible next card
         #[arg(long)]
-        pot: f64,
+        by_runout: bool,
+    },
 
-        /// Effective stack size in big blinds
-        #[arg(long)]
-        stack: f64,
+    /// Start an interactive session for iterating on a spot
+    Interactive,
+}
 
-        /// Amount to call in big blinds
-        #[arg(long, default_value = "0")]
-        to_call: f64,
+/// Arguments of the `analyze` subcommand
+#[derive(Args)]
+struct AnalyzeArgs {
+    /// Hero's hole cards (e.g., "AhKd")
+    #[arg(long)]
+    hero: Option<String>,
 
-        /// Hero position: IP or OOP
-        #[arg(long, default_value = "IP")]
-        position: String,
+    /// Hero's range in Equilab notation, solved combo by combo (instead of --hero);
+    /// `AA:0.5` weighs a combo's share of the aggregate
+    #[arg(long)]
+    hero_range: Option<String>,
 
-        /// Solver iterations
-        #[arg(long, default_value = "10000")]
-        iterations: u32,
+    /// Community cards (e.g., "Ts9s2h")
+    #[arg(long, default_value = "")]
+    board: String,
 
-        /// Output as JSON
-        #[arg(long, default_value = "false")]
-        json: bool,
+    /// Known dead cards that are out of play (e.g., "2c7d")
+    #[arg(long, default_value = "")]
+    dead: String,
 
-        /// Show solver progress
-        #[arg(long, default_value = "false")]
-        verbose: bool,
-    },
+    /// Opponent's range in Equilab notation; `@name` uses a preset (see --list-ranges)
+    #[arg(long)]
+    villain_range: String,
+
+    /// Pot size in big blinds
+    #[arg(long)]
+    pot: f64,
+
+    /// Effective stack size in big blinds
+    #[arg(long)]
+    stack: Option<f64>,
+
+    /// Effective stack as a multiple of the pot (alternative to --stack)
+    #[arg(long)]
+    spr: Option<f64>,
+
+    /// Amount to call in big blinds
+    #[arg(long, default_value = "0")]
+    to_call: f64,
+
+    /// Hero position: IP or OOP
+    #[arg(long, default_value = "IP")]
+    position: String,
+
+    /// Solver iterations
+    #[arg(long, default_value = "10000")]
+    iterations: u32,
+
+    /// RNG seed; seeded runs are reproducible and record provenance in JSON output
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Output as JSON
+    #[arg(long, default_value = "false")]
+    json: bool,
+
+    /// Show solver details, such as the info set the spot maps to (on stderr)
+    #[arg(long, default_value = "false")]
+    verbose: bool,
+
+    /// Extra report for --hero-range solves
+    #[arg(long, value_enum)]
+    report: Option<Report>,
+
+    /// Print where solve time went (sampling, evaluation, regret updates)
+    #[arg(long, default_value = "false")]
+    profile: bool,
+
+    /// Decimal places for frequencies and EVs in the strategy table
+    /// (default: 1 for frequencies, 2 for EVs)
+    #[arg(long)]
+    precision: Option<usize>,
+
+    /// Row order of the strategy table
+    #[arg(long, value_enum, default_value = "frequency")]
+    sort: SortArg,
+
+    /// Print only the strategy table, without the input summary
+    #[arg(long, default_value = "false")]
+    quiet: bool,
+
+    /// Explain the dominant action in plain language
+    #[arg(long, default_value = "false")]
+    explain: bool,
+
+    /// Show which villain value and bluff combos hero's cards block
+    #[arg(long, default_value = "false")]
+    blockers: bool,
+
+    /// Solve with these hero bet sizes (e.g. "0.33pot,0.75pot,pot") and report each
+    /// size's EV when always taken next to how often the strategy uses it
+    #[arg(long)]
+    compare_sizes: Option<String>,
+
+    /// Algorithm: `mccfr` solves, `uniform` is an instant preview with equal frequencies
+    #[arg(long, value_enum, default_value = "mccfr")]
+    solver: SolverArg,
+
+    /// Player whose strategy to solve for; `villain` solves --villain-range in
+    /// villain's position against --hero or --hero-range
+    #[arg(long, value_enum, default_value = "hero")]
+    perspective: PerspectiveArg,
+}
+
+/// `--sort` values for `ActionOrder`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortArg {
+    /// Most frequent action first
+    Frequency,
+    /// Fold, check, call, bets and raises by size, all-in
+    Canonical,
+}
+
+impl From<SortArg> for ActionOrder {
+    fn from(arg: SortArg) -> Self {
+        match arg {
+            SortArg::Frequency => ActionOrder::Frequency,
+            SortArg::Canonical => ActionOrder::Canonical,
+        }
+    }
+}
+
+/// `--solver` values for `SolverKind`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SolverArg {
+    /// Equal frequencies over the available actions, without solving
+    Uniform,
+    /// Monte Carlo CFR (the real solver)
+    Mccfr,
+}
+
+impl From<SolverArg> for SolverKind {
+    fn from(arg: SolverArg) -> Self {
+        match arg {
+            SolverArg::Uniform => SolverKind::Uniform,
+            SolverArg::Mccfr => SolverKind::Mccfr,
+        }
+    }
+}
+
+/// `--perspective` values for `Perspective`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PerspectiveArg {
+    /// Hero acts with their hand or range
+    Hero,
+    /// Villain acts with their range
+    Villain,
+}
+
+impl From<PerspectiveArg> for Perspective {
+    fn from(arg: PerspectiveArg) -> Self {
+        match arg {
+            PerspectiveArg::Hero => Perspective::Hero,
+            PerspectiveArg::Villain => Perspective::Villain,
+        }
+    }
+}
+
+/// Additional views of a hero-range solve
+#[derive(Clone, Copy, ValueEnum)]
+enum Report {
+    /// Action frequencies and EV per hand category
+    Categories,
+    /// Bet frequency after each turn card (flop spots only; one solve per turn)
+    Turns,
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    match cli.command {
-        Commands::Analyze {
+    if let Err(e) = run(cli) {
+        eprintln!("{}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run(cli: Cli) -> Result<(), CliError> {
+    if cli.list_ranges {
+        for (name, notation) in presets::PRESETS {
+            println!("@{:<10} {}", name, notation);
+        }
+        return Ok(());
+    }
+    let Some(command) = cli.command else {
+        return Err(CliError::Parse(
+            "Error: A subcommand is required (see --help)".to_string(),
+        ));
+    };
+
+    match command {
+        Commands::Analyze(args) => run_analyze(&args),
+        Commands::Equity {
             hero,
             board,
             villain_range,
-            pot,
-            stack,
-            to_call,
-            position,
-            iterations,
-            json,
-            verbose: _, // Not used yet
-        } => {
-            // Parse inputs
-            let hero_hand = match validation::validate_hand(&hero) {
-                Ok(h) => h,
-                Err(e) => {
-                    eprintln!("Error parsing hero hand: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            // Parse board
-            let mut board_cards = Vec::new();
-            if !board.is_empty() {
-                let chars: Vec<char> = board.chars().collect();
-                if !chars.len().is_multiple_of(2) {
-                    eprintln!("Error: Board string length must be even");
-                    std::process::exit(1);
-                }
-                for chunk in chars.chunks(2) {
-                    let s: String = chunk.iter().collect();
-                    match validation::validate_card(&s) {
-                        Ok(c) => board_cards.push(c),
-                        Err(e) => {
-                            eprintln!("Error parsing board card '{}': {}", s, e);
-                            std::process::exit(1);
-                        }
-                    }
-                }
-            }
+            by_runout,
+        } => run_equity(&hero, &board, &villain_range, by_runout),
+        Commands::Interactive => repl::run().map_err(|e| CliError::Io(e.to_string())),
+    }
+}
 
-            // Validate duplicates
-            if let Err(e) = validation::check_duplicates(&hero_hand, &board_cards) {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
+/// Run the `analyze` subcommand: arguments to an `AnalyzeInput`, solve,
+/// then render
+fn run_analyze(args: &AnalyzeArgs) -> Result<(), CliError> {
+    let mut request = args.request()?;
+    if args.perspective == PerspectiveArg::Villain {
+        request = request.villain_perspective().map_err(CliError::Parse)?;
+    }
+    let input = args.to_input(&request)?;
+    // The solve's villain range has hero's blockers removed already
+    let blocker_report = args
+        .blockers
+        .then(|| request.blocker_report())
+        .transpose()
+        .map_err(CliError::Parse)?;
+    if args.verbose && input.hero_range.is_none() {
+        eprintln!("Root info set: {}", input.game_state.info_set_key());
+    }
+
+    let output = analyze::analyze(input).map_err(|e| CliError::Solver(e.to_string()))?;
+    if let Some(profile) = &output.meta.profile {
+        eprint!("{}", profile);
+    }
+    if let Some(subsample) = output.strategy.villain_subsample {
+        eprintln!(
+            "Subsampled villain range: kept {} of {} combos",
+            subsample.kept, subsample.total
+        );
+    }
+    println!(
+        "{}",
+        output::format_analysis(&output, &args.format(), blocker_report.as_ref())
+    );
+    Ok(())
+}
 
-            let position_enum = match Position::from_str(&position) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("Error parsing position: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            // Parse Range
-            let mut v_range = match validation::validate_range(&villain_range) {
-                Ok(r) => r,
-                Err(e) => {
-                    eprintln!("Error parsing villain range: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            // Remove blockers from range
-            let mut blockers = Vec::new();
-            blockers.extend(hero_hand.cards);
-            blockers.extend(&board_cards);
-            v_range.remove_blockers(&blockers);
-
-            // Create GameState
-            let game_state = match GameState::new(
-                hero_hand.clone(),
-                board_cards.clone(),
-                pot,
-                stack,
-                to_call,
-                position_enum,
-                v_range,
-            ) {
-                Ok(gs) => gs,
-                Err(e) => {
-                    eprintln!("Error creating game state: {}", e);
-                    std::process::exit(1);
-                }
-            };
-
-            // Solve
-            match solver::solve(game_state, iterations) {
-                Ok(strategy) => {
-                    if json {
-                        println!("{}", serde_json::to_string_pretty(&strategy).unwrap());
-                    } else {
-                        // Summary
-                        println!("Input Summary:");
-                        println!("  Hero: {}", hero_hand.notation());
-                        if !board_cards.is_empty() {
-                            let board_str: Vec<String> =
-                                board_cards.iter().map(|c| c.to_string()).collect();
-                            println!("  Board: {}", board_str.join(" "));
-                        } else {
-                            println!("  Board: (none)");
-                        }
-                        println!(
-                            "  Pot: {:.1} BB, Stack: {:.1} BB, To Call: {:.1} BB",
-                            pot, stack, to_call
-                        );
-
-                        println!();
-                        println!(
-                            "Strategy computed in {} iterations (convergence: {})",
-                            strategy.iterations, strategy.convergence
-                        );
-                        println!();
-
-                        // Table output
-                        println!("{}", output::format_strategy_table(&strategy));
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Solver error: {}", e);
-                    std::process::exit(1);
-                }
+impl AnalyzeArgs {
+    /// The raw request, with exactly one of `--hero` and `--hero-range` and
+    /// the stack resolved from `--stack` or `--spr`
+    fn request(&self) -> Result<AnalyzeRequest, CliError> {
+        let stack = args::resolve_stack(self.pot, self.stack, self.spr).map_err(CliError::Parse)?;
+        let (hero, hero_range) = match (&self.hero, &self.hero_range) {
+            (Some(hero), None) => (hero.clone(), String::new()),
+            (None, Some(hero_range)) => (String::new(), hero_range.clone()),
+            (Some(_), Some(_)) => {
+                return Err(CliError::Parse(
+                    "Error: Specify only one of --hero or --hero-range".to_string(),
+                ));
             }
+            (None, None) => {
+                return Err(CliError::Parse(
+                    "Error: One of --hero or --hero-range is required".to_string(),
+                ));
+            }
+        };
+
+        Ok(AnalyzeRequest {
+            hero,
+            hero_range,
+            board: self.board.clone(),
+            dead: self.dead.clone(),
+            villain_range: self.villain_range.clone(),
+            pot: self.pot,
+            stack,
+            to_call: self.to_call,
+            position: self.position.clone(),
+            iterations: self.iterations,
+        })
+    }
+
+    /// Reject flags that only apply to single-hand solves
+    fn check_range_flags(&self) -> Result<(), CliError> {
+        if self.solver != SolverArg::Mccfr {
+            return Err(CliError::Parse(
+                "Error: --hero-range requires --solver mccfr".to_string(),
+            ));
+        }
+        // A villain-perspective solve is a range solve even with --hero
+        let single_hand = match self.perspective {
+            PerspectiveArg::Hero => "--hero",
+            PerspectiveArg::Villain => "--hero and --perspective hero",
+        };
+        if self.profile {
+            return Err(CliError::Parse(format!(
+                "Error: --profile requires {}",
+                single_hand
+            )));
+        }
+        let flag = if self.explain {
+            "--explain"
+        } else if self.blockers {
+            "--blockers"
+        } else if self.compare_sizes.is_some() {
+            "--compare-sizes"
+        } else {
+            return Ok(());
+        };
+        Err(CliError::Parse(format!(
+            "Error: {} requires {}",
+            flag, single_hand
+        )))
+    }
+
+    /// Validated input for `request`, a range solve if it has a hero range
+    fn to_input(&self, request: &AnalyzeRequest) -> Result<AnalyzeInput, CliError> {
+        if !request.hero_range.is_empty() {
+            self.check_range_flags()?;
+        } else if self.report.is_some() {
+            return Err(CliError::Parse(
+                "Error: --report requires --hero-range".to_string(),
+            ));
+        }
+
+        let bet_sizes = self
+            .compare_sizes
+            .as_deref()
+            .map(fpe::parse_bet_sizes)
+            .transpose()
+            .map_err(|e| CliError::Parse(format!("Error: {}", e)))?;
+        if bet_sizes
+            .iter()
+            .flatten()
+            .any(|size| matches!(size, BetSize::Multiplier(_)))
+        {
+            return Err(CliError::Parse(
+                "Error: --compare-sizes takes bet sizes; multipliers like 3x only size raises"
+                    .to_string(),
+            ));
         }
+        if bet_sizes.is_some() && self.to_call > 0.0 {
+            return Err(CliError::Parse(
+                "Error: --compare-sizes compares bets, so it requires --to-call 0".to_string(),
+            ));
+        }
+
+        let config = MccfrConfig::builder()
+            .seed(self.seed)
+            .profile(self.profile)
+            .hero_bet_sizes(bet_sizes)
+            .build();
+        let mut input = AnalyzeInput::from_request(request, self.solver.into(), config)
+            .map_err(CliError::Parse)?;
+        input.turn_report = matches!(self.report, Some(Report::Turns));
+        Ok(input)
+    }
+
+    /// Output layout from `--json`, `--quiet`, `--sort`, `--precision` and
+    /// the extra report flags
+    fn format(&self) -> AnalysisFormat {
+        AnalysisFormat {
+            json: self.json,
+            quiet: self.quiet,
+            order: self.sort.into(),
+            precision: self.precision,
+            perspective: self.perspective.into(),
+            size_comparison: self.compare_sizes.is_some(),
+            explain: self.explain,
+            categories: matches!(self.report, Some(Report::Categories)),
+        }
+    }
+}
+
+/// Print hero's runout equity, and with `by_runout` the equity after each
+/// next card
+fn run_equity(
+    hero: &str,
+    board: &str,
+    villain_range: &str,
+    by_runout: bool,
+) -> Result<(), CliError> {
+    let input =
+        EquityInput::parse(hero, board, villain_range, by_runout).map_err(CliError::Parse)?;
+    println!("{}", output::format_equity(&equity::equity(&input)));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fpe::models::Position;
+
+    /// `analyze` arguments for a 10 BB pot against QQ+
+    fn analyze_args(extra: &[&str]) -> AnalyzeArgs {
+        let base = ["fpe", "analyze", "--villain-range", "QQ+", "--pot", "10"];
+        let cli = Cli::try_parse_from(base.iter().chain(extra)).unwrap();
+        match cli.command {
+            Some(Commands::Analyze(args)) => args,
+            _ => unreachable!("parsed an analyze command"),
+        }
+    }
+
+    /// Input for `extra` with 100 BB behind
+    fn to_input(extra: &[&str]) -> Result<AnalyzeInput, CliError> {
+        let args = analyze_args(&[&["--stack", "100"], extra].concat());
+        let request = args.request()?;
+        args.to_input(&request)
+    }
+
+    #[test]
+    fn test_args_map_to_input() {
+        let input = to_input(&[
+            "--hero",
+            "AhKd",
+            "--board",
+            "Ts9s2h",
+            "--position",
+            "OOP",
+            "--iterations",
+            "500",
+            "--seed",
+            "7",
+        ])
+        .unwrap();
+        assert_eq!(input.game_state.hero_hand, fpe::parse_hand("AhKd").unwrap());
+        assert_eq!(input.game_state.board.len(), 3);
+        assert_eq!(input.game_state.position, Position::OOP);
+        assert_eq!(input.game_state.effective_stack, 100.0);
+        assert_eq!(input.solver, SolverKind::Mccfr);
+        assert_eq!(input.config.iterations, 500);
+        assert_eq!(input.config.seed, Some(7));
+
+        let request = analyze_args(&["--hero", "AhKd", "--spr", "2.5"]).request();
+        assert_eq!(request.unwrap().stack, 25.0);
+    }
+
+    #[test]
+    fn test_board_and_dead_cards_chunk_into_pairs() {
+        let board = |board: &str| to_input(&["--hero", "AhKd", "--board", board]);
+        let packed = board("Ts9s2h").unwrap();
+        let spaced = board("Ts 9s 2h").unwrap();
+        assert_eq!(packed.game_state.board, spaced.game_state.board);
+        // A dangling half card is an error, not a dropped card
+        assert!(matches!(board("Ts9s2"), Err(CliError::Parse(_))));
+        assert!(matches!(board("Ts9s2h8"), Err(CliError::Parse(_))));
+
+        let dead = |dead: &str| to_input(&["--hero", "AhKd", "--board", "Ts9s2h", "--dead", dead]);
+        assert_eq!(dead("2c7d").unwrap().game_state.dead_cards.len(), 2);
+        assert!(matches!(dead("2c7"), Err(CliError::Parse(_))));
+    }
+
+    #[test]
+    fn test_conflicting_args_are_rejected() {
+        let rejected = |extra: &[&str]| matches!(to_input(extra), Err(CliError::Parse(_)));
+        assert!(rejected(&[]));
+        assert!(rejected(&["--hero", "AhKd", "--hero-range", "AKs"]));
+        assert!(rejected(&["--hero", "AhKd", "--report", "categories"]));
+        assert!(rejected(&["--hero", "AhKd", "--compare-sizes", "3x"]));
+        assert!(rejected(&[
+            "--hero",
+            "AhKd",
+            "--to-call",
+            "5",
+            "--compare-sizes",
+            "pot"
+        ]));
+
+        let range = analyze_args(&["--hero-range", "AKs", "--stack", "100", "--explain"]);
+        assert_eq!(range.request().unwrap().hero_range, "AKs");
+        assert!(matches!(range.check_range_flags(), Err(CliError::Parse(_))));
+
+        let villain = analyze_args(&[
+            "--hero",
+            "AhKd",
+            "--stack",
+            "100",
+            "--perspective",
+            "villain",
+            "--blockers",
+        ]);
+        let Err(CliError::Parse(message)) = villain.check_range_flags() else {
+            panic!("--blockers is a single-hand flag");
+        };
+        assert!(message.contains("--perspective hero"), "{}", message);
     }
 }
@@ -1,6 +1,9 @@
 //! Poker action types
 
+use crate::error::{ModelError, Result};
+use crate::models::game_state::Street;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 /// Bet sizing as fraction of pot or absolute amount
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -9,6 +12,79 @@ pub enum BetSize {
     PotFraction(f64),
     /// Fixed amount in big blinds
     Amount(f64),
+    /// Raise to a multiple of the bet being faced (e.g. 3.0 for "3x").
+    /// Only sizes raises; there is no bet to multiply when betting.
+    Multiplier(f64),
+    /// Whole remaining stack
+    AllIn,
+}
+
+impl FromStr for BetSize {
+    type Err = ModelError;
+
+    /// Parse a size token: "0.5pot", "pot", "10bb", "3x" or "allin"
+    fn from_str(s: &str) -> Result<Self> {
+        let token = s.trim().to_ascii_lowercase();
+        let invalid = || ModelError::InvalidGameState(format!("Invalid bet size '{}'", s));
+        let number = |n: &str| {
+            n.parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite() && *v > 0.0)
+                .ok_or_else(invalid)
+        };
+
+        match token.as_str() {
+            "pot" => Ok(BetSize::PotFraction(1.0)),
+            "allin" | "all-in" => Ok(BetSize::AllIn),
+            _ => {
+                if let Some(fraction) = token.strip_suffix("pot") {
+                    Ok(BetSize::PotFraction(number(fraction)?))
+                } else if let Some(amount) = token.strip_suffix("bb") {
+                    Ok(BetSize::Amount(number(amount)?))
+                } else if let Some(multiplier) = token.strip_suffix('x') {
+                    Ok(BetSize::Multiplier(number(multiplier)?))
+                } else {
+                    Err(invalid())
+                }
+            }
+        }
+    }
+}
+
+/// Bet sizes offered on each postflop street, e.g. small flop c-bets and
+/// large, polarized river bets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreetBetSizes {
+    /// Sizes on the flop
+    pub flop: Vec<BetSize>,
+    /// Sizes on the turn
+    pub turn: Vec<BetSize>,
+    /// Sizes on the river
+    pub river: Vec<BetSize>,
+}
+
+impl StreetBetSizes {
+    /// Sizes for `street`; `None` preflop, which has no sizes of its own
+    pub fn on(&self, street: Street) -> Option<&[BetSize]> {
+        match street {
+            Street::Preflop => None,
+            Street::Flop => Some(&self.flop),
+            Street::Turn => Some(&self.turn),
+            Street::River => Some(&self.river),
+        }
+    }
+}
+
+impl Default for StreetBetSizes {
+    /// A third of pot on the flop, two thirds on the turn, and three
+    /// quarters or an overbet on the river
+    fn default() -> Self {
+        Self {
+            flop: vec![BetSize::PotFraction(0.33)],
+            turn: vec![BetSize::PotFraction(0.66)],
+            river: vec![BetSize::PotFraction(0.75), BetSize::PotFraction(1.25)],
+        }
+    }
 }
 
 /// A possible action at a decision point
@@ -30,6 +106,12 @@ pub enum Action {
 
 impl Action {
     /// Returns the amount committed by this action given pot and stack
+    ///
+    /// # Panics
+    ///
+    /// On a bet sized as a multiplier, which has no bet to multiply. Parsing
+    /// and `GameState::validate_actions` reject it and the solver never
+    /// builds one.
     pub fn amount(&self, pot: f64, stack: f64, to_call: f64) -> f64 {
         match self {
             Action::Fold => 0.0,
@@ -38,15 +120,68 @@ impl Action {
             Action::Bet(size) => match size {
                 BetSize::PotFraction(fraction) => (pot * fraction).min(stack),
                 BetSize::Amount(amt) => amt.min(stack),
+                BetSize::Multiplier(_) => panic!("A multiplier only sizes a raise, not a bet"),
+                BetSize::AllIn => stack,
             },
-            Action::Raise(size) => match size {
-                BetSize::PotFraction(fraction) => (to_call + pot * fraction).min(stack),
-                BetSize::Amount(amt) => amt.min(stack),
-            },
+            Action::Raise(size) => {
+                let requested = match size {
+                    BetSize::PotFraction(fraction) => to_call + pot * fraction,
+                    BetSize::Amount(amt) => *amt,
+                    BetSize::Multiplier(multiplier) => to_call * multiplier,
+                    BetSize::AllIn => stack,
+                };
+                requested.max(Self::min_raise(to_call)).min(stack)
+            }
             Action::AllIn => stack,
         }
     }
 
+    /// Smallest legal raise: the call plus at least the last bet or raise
+    /// increment. Heads-up, the amount to call always equals that increment.
+    pub fn min_raise(to_call: f64) -> f64 {
+        2.0 * to_call
+    }
+
+    /// Returns `AllIn` for a bet or raise that commits the whole stack,
+    /// otherwise the action unchanged
+    pub fn normalize(&self, pot: f64, stack: f64, to_call: f64) -> Action {
+        match self {
+            Action::Bet(_) | Action::Raise(_) if self.amount(pot, stack, to_call) >= stack => {
+                Action::AllIn
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Key for the canonical action order: fold, check, call, bets by size,
+    /// raises by size, then all-in.
+    ///
+    /// Sizes compare within a kind, pot fractions before multipliers before
+    /// fixed amounts before all-in sizes, so a mixed list still sorts
+    /// deterministically.
+    pub fn sort_key(&self) -> (u8, u8, u64) {
+        let size_key = |size: &BetSize| match size {
+            BetSize::PotFraction(f) => (0, (f * 10_000.0).round() as u64),
+            BetSize::Multiplier(m) => (1, (m * 10_000.0).round() as u64),
+            BetSize::Amount(a) => (2, (a * 10_000.0).round() as u64),
+            BetSize::AllIn => (3, 0),
+        };
+        match self {
+            Action::Fold => (0, 0, 0),
+            Action::Check => (1, 0, 0),
+            Action::Call => (2, 0, 0),
+            Action::Bet(size) => {
+                let (kind, amount) = size_key(size);
+                (3, kind, amount)
+            }
+            Action::Raise(size) => {
+                let (kind, amount) = size_key(size);
+                (4, kind, amount)
+            }
+            Action::AllIn => (5, 0, 0),
+        }
+    }
+
     /// Returns display name for the action
     pub fn display_name(&self) -> String {
         match self {
@@ -56,16 +191,51 @@ impl Action {
             Action::Bet(size) => match size {
                 BetSize::PotFraction(f) => format!("Bet {:.0}% pot", f * 100.0),
                 BetSize::Amount(a) => format!("Bet {} BB", a),
+                BetSize::Multiplier(m) => format!("Bet {}x", m),
+                BetSize::AllIn => "All-In".to_string(),
             },
             Action::Raise(size) => match size {
                 BetSize::PotFraction(f) => format!("Raise {:.0}% pot", f * 100.0),
                 BetSize::Amount(a) => format!("Raise to {} BB", a),
+                BetSize::Multiplier(m) => format!("Raise {}x", m),
+                BetSize::AllIn => "All-In".to_string(),
             },
             Action::AllIn => "All-In".to_string(),
         }
     }
 }
 
+impl FromStr for Action {
+    type Err = ModelError;
+
+    /// Parse an action token: "fold", "check", "call", "allin",
+    /// "bet:<size>" or "raise:<size>" with a `BetSize` token as size;
+    /// multipliers such as "3x" only size raises
+    fn from_str(s: &str) -> Result<Self> {
+        let token = s.trim().to_ascii_lowercase();
+        match token.as_str() {
+            "fold" => Ok(Action::Fold),
+            "check" => Ok(Action::Check),
+            "call" => Ok(Action::Call),
+            "allin" | "all-in" => Ok(Action::AllIn),
+            _ => match token.split_once(':') {
+                Some(("bet", size)) => match size.parse()? {
+                    BetSize::Multiplier(_) => Err(ModelError::InvalidGameState(format!(
+                        "Invalid action '{}': a multiplier only sizes a raise",
+                        s
+                    ))),
+                    size => Ok(Action::Bet(size)),
+                },
+                Some(("raise", size)) => Ok(Action::Raise(size.parse()?)),
+                _ => Err(ModelError::InvalidGameState(format!(
+                    "Invalid action '{}'",
+                    s
+                ))),
+            },
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
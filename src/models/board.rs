@@ -0,0 +1,152 @@
+//! Community cards
+//!
+//! A `Board` always holds a street's worth of distinct cards: 0, 3, 4 or 5.
+//! It derefs to `[Card]`, so anything taking a `&[Card]` board (the
+//! evaluator, equity calculations) accepts a `&Board` unchanged.
+
+use crate::error::{ModelError, Result};
+use crate::models::card::Card;
+use crate::models::game_state::Street;
+use serde::{Deserialize, Serialize};
+use std::ops::Deref;
+
+/// Validated community cards, serialized as a plain list of cards
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Card>", into = "Vec<Card>")]
+pub struct Board {
+    cards: Vec<Card>,
+}
+
+impl Board {
+    /// Board from `cards`, which must be a whole street's worth (0, 3, 4 or
+    /// 5 cards) with no card repeated
+    pub fn new(cards: Vec<Card>) -> Result<Self> {
+        Street::from_board_size(cards.len())?;
+        for (i, card) in cards.iter().enumerate() {
+            if cards[..i].contains(card) {
+                return Err(ModelError::DuplicateCard(format!("{}", card)));
+            }
+        }
+        Ok(Self { cards })
+    }
+
+    /// The empty preflop board
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Street these cards make
+    pub fn street(&self) -> Street {
+        Street::from_board_size(self.cards.len()).expect("board size is checked on construction")
+    }
+
+    /// The cards, in the order they were dealt
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Add the turn or river card.
+    ///
+    /// Fails before the flop or after the river, or if `card` is already on
+    /// the board.
+    pub fn deal(&mut self, card: Card) -> Result<()> {
+        let next = match self.street() {
+            Street::Flop | Street::Turn => self.street().next().unwrap(),
+            street => {
+                return Err(ModelError::InvalidGameState(format!(
+                    "Cannot deal a single card on the {:?}",
+                    street
+                )))
+            }
+        };
+        if self.cards.contains(&card) {
+            return Err(ModelError::DuplicateCard(format!("{}", card)));
+        }
+        self.cards.push(card);
+        debug_assert_eq!(self.street(), next);
+        Ok(())
+    }
+}
+
+impl Deref for Board {
+    type Target = [Card];
+
+    fn deref(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl AsRef<[Card]> for Board {
+    fn as_ref(&self) -> &[Card] {
+        &self.cards
+    }
+}
+
+impl TryFrom<Vec<Card>> for Board {
+    type Error = ModelError;
+
+    fn try_from(cards: Vec<Card>) -> Result<Self> {
+        Self::new(cards)
+    }
+}
+
+impl From<Board> for Vec<Card> {
+    fn from(board: Board) -> Self {
+        board.cards
+    }
+}
+
+impl<'a> IntoIterator for &'a Board {
+    type Item = &'a Card;
+    type IntoIter = std::slice::Iter<'a, Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cards.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{cards, DRY_RIVER};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_invalid_sizes_are_rejected() {
+        for invalid in ["Ks", "Ks8d", "Ks8d4c2h7s9c"] {
+            assert!(matches!(
+                Board::new(cards(invalid)),
+                Err(ModelError::InvalidBoard { .. })
+            ));
+        }
+        assert!(matches!(
+            Board::new(cards("Ks8dKs")),
+            Err(ModelError::DuplicateCard(_))
+        ));
+
+        assert_eq!(Board::empty().street(), Street::Preflop);
+        assert_eq!(Board::new(cards("Ks8d4c")).unwrap().street(), Street::Flop);
+        assert_eq!(
+            Board::new(cards(DRY_RIVER)).unwrap().street(),
+            Street::River
+        );
+    }
+
+    #[test]
+    fn test_deal_and_serialize() {
+        let mut board = Board::new(cards("Ks8d4c")).unwrap();
+        assert!(board.deal(Card::from_str("Ks").unwrap()).is_err());
+        board.deal(Card::from_str("2h").unwrap()).unwrap();
+        board.deal(Card::from_str("7s").unwrap()).unwrap();
+        assert_eq!(board.street(), Street::River);
+        assert!(board.deal(Card::from_str("9c").unwrap()).is_err());
+        assert!(Board::empty().deal(Card::from_str("9c").unwrap()).is_err());
+
+        // Serialized as a plain card list, and validated on the way back in
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(json, serde_json::to_string(&cards(DRY_RIVER)).unwrap());
+        assert_eq!(serde_json::from_str::<Board>(&json).unwrap(), board);
+        let two_cards = serde_json::to_string(&cards("Ks8d")).unwrap();
+        assert!(serde_json::from_str::<Board>(&two_cards).is_err());
+    }
+}
@@ -126,11 +126,92 @@ pub struct Card {
     pub suit: Suit,
 }
 
+impl Rank {
+    /// All ranks from Two to Ace
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// Zero-based rank index (Two = 0 ... Ace = 12)
+    pub fn index(self) -> u8 {
+        self as u8 - 2
+    }
+
+    /// Rank for a zero-based index, `None` past 12; the inverse of `index`
+    pub fn from_index(index: u8) -> Option<Rank> {
+        Rank::ALL.get(index as usize).copied()
+    }
+
+    /// Number of ranks strictly between this one and `other`, in either
+    /// order, with the ace high: 0 for connectors and pairs,
+    /// `Nine.gap_to(Seven) == 1`
+    pub fn gap_to(self, other: Rank) -> u8 {
+        self.index().abs_diff(other.index()).saturating_sub(1)
+    }
+}
+
+impl Suit {
+    /// All suits in `pokers` index order
+    pub const ALL: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds];
+
+    /// Suit index used by the `pokers` crate (s = 0, h = 1, c = 2, d = 3)
+    fn pokers_index(self) -> u8 {
+        match self {
+            Suit::Spades => 0,
+            Suit::Hearts => 1,
+            Suit::Clubs => 2,
+            Suit::Diamonds => 3,
+        }
+    }
+}
+
 impl Card {
     /// Create a new card
     pub fn new(rank: Rank, suit: Suit) -> Self {
         Self { rank, suit }
     }
+
+    /// Returns the card in input notation (e.g., "Ah", "Td")
+    pub fn notation(&self) -> String {
+        format!("{}{}", char::from(self.rank), char::from(self.suit))
+    }
+
+    /// Index of this card in the `pokers` crate (0-51, `4 * rank + suit`)
+    pub fn to_pokers_index(&self) -> u8 {
+        4 * self.rank.index() + self.suit.pokers_index()
+    }
+
+    /// Card for a `pokers` index; errors on anything outside 0-51
+    pub fn from_pokers_index(index: u8) -> Result<Self> {
+        if index >= 52 {
+            return Err(ModelError::InvalidCard(format!("pokers index {}", index)));
+        }
+        let rank = Rank::from_index(index / 4).expect("index below 52");
+        let suit = Suit::ALL[(index % 4) as usize];
+        Ok(Card::new(rank, suit))
+    }
+
+    /// Single-bit mask as read by `pokers::Hand::from_bit_mask`
+    pub fn to_pokers_mask(&self) -> u64 {
+        1u64 << self.to_pokers_index()
+    }
+
+    /// Combined `pokers` bit mask of several cards
+    pub fn pokers_mask(cards: &[Card]) -> u64 {
+        cards.iter().fold(0, |mask, c| mask | c.to_pokers_mask())
+    }
 }
 
 impl FromStr for Card {
@@ -177,4 +258,55 @@ mod tests {
         assert!(Card::from_str("Xh").is_err());
         assert!(Card::from_str("Ax").is_err());
     }
+
+    #[test]
+    fn test_pokers_index_round_trip() {
+        for rank in Rank::ALL {
+            for suit in Suit::ALL {
+                let card = Card::new(rank, suit);
+                assert_eq!(
+                    Card::from_pokers_index(card.to_pokers_index()).unwrap(),
+                    card
+                );
+            }
+        }
+        assert!(Card::from_pokers_index(52).is_err());
+    }
+
+    #[test]
+    fn test_rank_index_round_trip_and_gaps() {
+        for (i, rank) in Rank::ALL.into_iter().enumerate() {
+            assert_eq!(rank.index() as usize, i);
+            assert_eq!(Rank::from_index(rank.index()), Some(rank));
+        }
+        assert_eq!(Rank::from_index(13), None);
+
+        assert_eq!(Rank::Nine.gap_to(Rank::Seven), 1);
+        assert_eq!(Rank::Seven.gap_to(Rank::Nine), 1);
+        assert_eq!(Rank::Ten.gap_to(Rank::Nine), 0);
+        assert_eq!(Rank::King.gap_to(Rank::King), 0);
+        assert_eq!(Rank::Ace.gap_to(Rank::Two), 11);
+    }
+
+    #[test]
+    fn test_pokers_index_matches_pokers() {
+        for s in ["2s", "2h", "2c", "2d", "Th", "Ks", "Ad"] {
+            let card = Card::from_str(s).unwrap();
+            assert_eq!(
+                card.to_pokers_index(),
+                pokers::hand_range::card_from_str(s).unwrap()
+            );
+        }
+
+        let cards: Vec<Card> = ["Ah", "Kh", "Qh", "Jh", "Th"]
+            .iter()
+            .map(|s| Card::from_str(s).unwrap())
+            .collect();
+        let mask = Card::pokers_mask(&cards);
+        assert_eq!(mask, pokers::get_card_mask("AhKhQhJhTh"));
+
+        // Royal flush must land in the straight flush category
+        let score = pokers::Hand::from_bit_mask(mask).evaluate();
+        assert_eq!(score & 0xF000, pokers::constants::STRAIGHT_FLUSH & 0xF000);
+    }
 }
@@ -1,7 +1,8 @@
 //! Game state representation
 
 use crate::error::{ModelError, Result};
-use crate::models::action::Action;
+use crate::models::action::{Action, BetSize};
+use crate::models::board::Board;
 use crate::models::card::Card;
 use crate::models::hand::Hand;
 use crate::models::range::Range;
@@ -17,6 +18,21 @@ pub enum Position {
     OOP,
 }
 
+impl Position {
+    /// The opposing position
+    pub fn other(self) -> Self {
+        match self {
+            Position::IP => Position::OOP,
+            Position::OOP => Position::IP,
+        }
+    }
+
+    /// True if this position acts first on a postflop street
+    pub fn acts_first(self) -> bool {
+        self == Position::OOP
+    }
+}
+
 impl FromStr for Position {
     type Err = ModelError;
 
@@ -33,7 +49,7 @@ impl FromStr for Position {
 }
 
 /// Current street in the hand
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Street {
     /// Preflop (no community cards)
     Preflop,
@@ -70,6 +86,16 @@ impl Street {
             }),
         }
     }
+
+    /// The street that follows this one, `None` after the river
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            Street::Preflop => Some(Street::Flop),
+            Street::Flop => Some(Street::Turn),
+            Street::Turn => Some(Street::River),
+            Street::River => None,
+        }
+    }
 }
 
 /// Complete game state at a decision point
@@ -78,8 +104,8 @@ pub struct GameState {
     /// Hero's hole cards
     pub hero_hand: Hand,
 
-    /// Community cards (0-5 cards)
-    pub board: Vec<Card>,
+    /// Community cards (0, 3, 4 or 5 cards)
+    pub board: Board,
 
     /// Current pot size in big blinds
     pub pot_size: f64,
@@ -99,12 +125,41 @@ pub struct GameState {
     /// Current street
     pub street: Street,
 
-    /// Available actions for hero at this decision point
-    pub available_actions: Vec<Action>,
+    /// Available actions for hero at this decision point; `None` leaves
+    /// them to the solver's default abstraction
+    pub available_actions: Option<Vec<Action>>,
+
+    /// Chips both players have put in on the current street
+    #[serde(default)]
+    pub street_investment: f64,
+
+    /// Raises made on the current street; the bet that opens it isn't one
+    #[serde(default)]
+    pub street_raises: u32,
+
+    /// Pot size when the current street was dealt
+    #[serde(default)]
+    pub starting_street_pot: f64,
+
+    /// Known cards out of play that are neither in hero's hand nor on the
+    /// board (e.g. folded face up); set with `set_dead_cards`
+    #[serde(default)]
+    pub dead_cards: Vec<Card>,
 }
 
 impl GameState {
-    /// Create a new game state with validation
+    /// Largest pot or effective stack accepted, in big blinds.
+    ///
+    /// Bet sizing and payoffs are plain `f64` arithmetic on these amounts;
+    /// the cap keeps them far from overflow so a typo like `1e308` is
+    /// rejected instead of turning EVs into `inf`.
+    pub const MAX_CHIPS: f64 = 10_000.0;
+
+    /// Create a new game state with validation.
+    ///
+    /// `pot_size` and `effective_stack` must be in `(0, MAX_CHIPS]` and
+    /// `to_call` in `[0, effective_stack]`; NaN and infinite amounts are
+    /// rejected.
     pub fn new(
         hero_hand: Hand,
         board: Vec<Card>,
@@ -115,38 +170,44 @@ impl GameState {
         villain_range: Range,
     ) -> Result<Self> {
         // Validate pot size
-        if pot_size <= 0.0 {
+        if pot_size.is_nan() || pot_size <= 0.0 {
             return Err(ModelError::InvalidGameState(
                 "Pot size must be greater than 0".to_string(),
             ));
         }
+        if pot_size > Self::MAX_CHIPS {
+            return Err(ModelError::InvalidGameState(format!(
+                "Pot size ({}) must be at most {} BB",
+                pot_size,
+                Self::MAX_CHIPS
+            )));
+        }
 
         // Validate effective stack
-        if effective_stack <= 0.0 {
+        if effective_stack.is_nan() || effective_stack <= 0.0 {
             return Err(ModelError::InvalidGameState(
                 "Effective stack must be greater than 0".to_string(),
             ));
         }
+        if effective_stack > Self::MAX_CHIPS {
+            return Err(ModelError::InvalidGameState(format!(
+                "Effective stack ({}) must be at most {} BB",
+                effective_stack,
+                Self::MAX_CHIPS
+            )));
+        }
 
         // Validate to_call
-        if to_call < 0.0 || to_call > effective_stack {
+        if !(0.0..=effective_stack).contains(&to_call) {
             return Err(ModelError::InvalidGameState(format!(
                 "to_call ({}) must be between 0 and effective_stack ({})",
                 to_call, effective_stack
             )));
         }
 
-        // Determine street from board size
-        let street = Street::from_board_size(board.len())?;
-
-        // Validate board size matches street
-        if board.len() != street.expected_cards() {
-            return Err(ModelError::InvalidBoard {
-                street: format!("{:?}", street),
-                expected: street.expected_cards(),
-                actual: board.len(),
-            });
-        }
+        // Validates the board size and its own duplicates
+        let board = Board::new(board)?;
+        let street = board.street();
 
         // Check for duplicate cards
         let mut all_cards = vec![hero_hand.cards[0], hero_hand.cards[1]];
@@ -159,6 +220,7 @@ impl GameState {
             }
         }
 
+        // A pending bet is taken to be the only money in on this street
         Ok(Self {
             hero_hand,
             board,
@@ -168,9 +230,286 @@ impl GameState {
             position,
             villain_range,
             street,
-            available_actions: Vec::new(), // Will be populated by solver
+            available_actions: None, // Will be populated by solver
+            street_investment: to_call,
+            street_raises: 0,
+            starting_street_pot: pot_size - to_call,
+            dead_cards: Vec::new(),
         })
     }
+
+    /// This state with `pot_size` replaced, validated as `new` would
+    pub fn with_pot(mut self, pot_size: f64) -> Result<Self> {
+        self.pot_size = pot_size;
+        self.revalidated()
+    }
+
+    /// This state with `effective_stack` replaced, validated as `new` would
+    pub fn with_stack(mut self, effective_stack: f64) -> Result<Self> {
+        self.effective_stack = effective_stack;
+        self.revalidated()
+    }
+
+    /// This state with `to_call` replaced, validated as `new` would
+    pub fn with_to_call(mut self, to_call: f64) -> Result<Self> {
+        self.to_call = to_call;
+        self.revalidated()
+    }
+
+    /// This state with hero in `position`
+    pub fn with_position(mut self, position: Position) -> Result<Self> {
+        self.position = position;
+        self.revalidated()
+    }
+
+    /// This state on `board`, with the street it implies
+    pub fn with_board(mut self, board: Vec<Card>) -> Result<Self> {
+        self.board = Board::new(board)?;
+        self.revalidated()
+    }
+
+    /// Text identifying this spot for caching solves: two states have the
+    /// same key exactly when every field a solve reads is equal, with
+    /// amounts compared exactly and the villain range as `Range::cache_key`.
+    pub fn cache_key(&self) -> String {
+        let cards = |cards: &[Card]| cards.iter().map(Card::to_string).collect::<String>();
+        let mut dead_cards = self.dead_cards.clone();
+        dead_cards.sort_by_key(Card::to_pokers_index);
+        let actions = match &self.available_actions {
+            Some(actions) => actions
+                .iter()
+                .map(Action::display_name)
+                .collect::<Vec<_>>()
+                .join(","),
+            None => "default".to_string(),
+        };
+        format!(
+            "{}|{}|{}|{:?}|{:?}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.hero_hand.combo_notation(),
+            cards(&self.board),
+            cards(&dead_cards),
+            self.position,
+            self.street,
+            self.pot_size,
+            self.effective_stack,
+            self.to_call,
+            self.street_investment,
+            self.street_raises,
+            self.starting_street_pot,
+            actions,
+            self.villain_range.cache_key()
+        )
+    }
+
+    /// Run the fields back through `new`, keeping the available actions and
+    /// dead cards. The street and per-street betting are derived afresh, as
+    /// for a newly built state.
+    fn revalidated(self) -> Result<Self> {
+        let mut state = GameState::new(
+            self.hero_hand,
+            self.board.into(),
+            self.pot_size,
+            self.effective_stack,
+            self.to_call,
+            self.position,
+            self.villain_range,
+        )?;
+        state.available_actions = self.available_actions;
+        state.set_dead_cards(self.dead_cards)?;
+        Ok(state)
+    }
+
+    /// Take `cards` out of the deck: they can't appear in runouts, and
+    /// villain combos holding them are removed.
+    pub fn set_dead_cards(&mut self, cards: Vec<Card>) -> Result<()> {
+        for (i, card) in cards.iter().enumerate() {
+            if self.hero_hand.cards.contains(card)
+                || self.board.contains(card)
+                || cards[..i].contains(card)
+            {
+                return Err(ModelError::DuplicateCard(format!("{}", card)));
+            }
+        }
+        self.villain_range.remove_blockers(&cards);
+        self.dead_cards = cards;
+        Ok(())
+    }
+
+    /// Check that no villain combo uses hero's hole cards, a board card or a
+    /// dead card.
+    ///
+    /// `new` does not look at the villain range, so a range that was never
+    /// passed through `Range::remove_blockers` can hold impossible combos.
+    pub fn validate_against_deck(&self) -> Result<()> {
+        let dead = self
+            .hero_hand
+            .cards
+            .iter()
+            .chain(&self.board)
+            .chain(&self.dead_cards);
+        for card in dead {
+            if self
+                .villain_range
+                .hands()
+                .any(|(hand, _)| hand.cards.contains(card))
+            {
+                return Err(ModelError::DuplicateCard(format!("{}", card)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the player to act has a legal action in
+    /// `available_actions`: fold, call and raise need a bet to face, check
+    /// and bet need none, and all-in needs chips behind.
+    ///
+    /// Unset actions pass, since solvers fill in the defaults. An empty list
+    /// only passes on the river, where no action left means a showdown;
+    /// earlier it is an error. So is a bet sized as a multiplier: there is
+    /// no bet to multiply.
+    pub fn validate_actions(&self) -> Result<()> {
+        let Some(available) = &self.available_actions else {
+            return Ok(());
+        };
+        if available.is_empty() {
+            if self.street == Street::River {
+                return Ok(());
+            }
+            return Err(ModelError::InvalidGameState(format!(
+                "No actions on the {:?} with the hand still to play; leave them unset for the defaults",
+                self.street
+            )));
+        }
+        if available
+            .iter()
+            .any(|action| matches!(action, Action::Bet(BetSize::Multiplier(_))))
+        {
+            return Err(ModelError::InvalidGameState(
+                "A multiplier only sizes a raise, not a bet".to_string(),
+            ));
+        }
+        let facing_bet = self.to_call > 0.0;
+        let legal = |action: &Action| match action {
+            Action::Fold | Action::Call | Action::Raise(_) => facing_bet,
+            Action::Check | Action::Bet(_) => !facing_bet,
+            Action::AllIn => self.effective_stack > 0.0,
+        };
+        if available.iter().any(legal) {
+            return Ok(());
+        }
+
+        let actions: Vec<String> = available.iter().map(|a| a.display_name()).collect();
+        Err(ModelError::InvalidGameState(format!(
+            "No legal action among [{}] with {} to call",
+            actions.join(", "),
+            self.to_call
+        )))
+    }
+
+    /// Share of the final pot hero must put in to call: `to_call / (pot + to_call)`.
+    ///
+    /// `pot_size` already includes the bet being faced. 0.0 with nothing to call.
+    pub fn pot_odds(&self) -> f64 {
+        if self.to_call > 0.0 {
+            self.to_call / (self.pot_size + self.to_call)
+        } else {
+            0.0
+        }
+    }
+
+    /// Minimum defense frequency against the bet hero faces, so that a pure
+    /// bluff does not profit: `1 - bet / (pot + bet)` with the pot before
+    /// the bet. 1.0 with nothing to call.
+    pub fn mdf(&self) -> f64 {
+        if self.to_call > 0.0 {
+            1.0 - self.to_call / self.pot_size
+        } else {
+            1.0
+        }
+    }
+
+    /// Minimum defense frequency villain needs against hero putting in
+    /// `amount` more to win the current pot
+    pub fn bet_mdf(&self, amount: f64) -> f64 {
+        1.0 - amount / (self.pot_size + amount)
+    }
+
+    /// Record `amount` chips going into the pot on the current street
+    pub fn commit(&mut self, amount: f64) {
+        self.pot_size += amount;
+        self.street_investment += amount;
+    }
+
+    /// Deal the next turn or river card once betting on this street is closed.
+    ///
+    /// The pot carries over as the new street's starting pot, and the
+    /// out-of-position player acts first.
+    pub fn deal_card(&mut self, card: Card) -> Result<()> {
+        let next_street = match self.street {
+            Street::Flop | Street::Turn => self.street.next().unwrap(),
+            street => {
+                return Err(ModelError::InvalidGameState(format!(
+                    "Cannot deal a single card on the {:?}",
+                    street
+                )))
+            }
+        };
+        if self.to_call > 0.0 {
+            return Err(ModelError::InvalidGameState(
+                "Cannot deal the next street with a bet pending".to_string(),
+            ));
+        }
+        if self.board.contains(&card)
+            || self.hero_hand.cards.contains(&card)
+            || self.dead_cards.contains(&card)
+        {
+            return Err(ModelError::DuplicateCard(format!("{}", card)));
+        }
+
+        self.board.deal(card)?;
+        self.street = next_street;
+        self.start_street();
+        Ok(())
+    }
+
+    /// Deal the flop once preflop betting is closed, as `deal_card` does
+    /// for the turn and river.
+    pub fn deal_flop(&mut self, flop: [Card; 3]) -> Result<()> {
+        if self.street != Street::Preflop {
+            return Err(ModelError::InvalidGameState(format!(
+                "Cannot deal a flop on the {:?}",
+                self.street
+            )));
+        }
+        if self.to_call > 0.0 {
+            return Err(ModelError::InvalidGameState(
+                "Cannot deal the next street with a bet pending".to_string(),
+            ));
+        }
+        for (i, card) in flop.iter().enumerate() {
+            if flop[..i].contains(card)
+                || self.hero_hand.cards.contains(card)
+                || self.dead_cards.contains(card)
+            {
+                return Err(ModelError::DuplicateCard(format!("{}", card)));
+            }
+        }
+
+        self.board = Board::new(flop.to_vec())?;
+        self.street = Street::Flop;
+        self.start_street();
+        Ok(())
+    }
+
+    /// Reset per-street betting after a deal: the pot carries over and the
+    /// out-of-position player acts first
+    fn start_street(&mut self) {
+        self.starting_street_pot = self.pot_size;
+        self.street_investment = 0.0;
+        self.street_raises = 0;
+        self.position = Position::OOP;
+        self.available_actions = None;
+    }
 }
 
 #[cfg(test)]
@@ -186,6 +525,43 @@ mod tests {
         assert_eq!(Street::from_board_size(4).unwrap(), Street::Turn);
         assert_eq!(Street::from_board_size(5).unwrap(), Street::River);
         assert!(Street::from_board_size(2).is_err());
+        assert_eq!(Street::Flop.next(), Some(Street::Turn));
+        assert_eq!(Street::River.next(), None);
+    }
+
+    #[test]
+    fn test_deal_card_starts_new_street() {
+        let board = ["Ks", "8d", "4c"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        let mut state = GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            board,
+            10.0,
+            100.0,
+            5.0,
+            Position::IP,
+            Range::new(),
+        )
+        .unwrap();
+        assert_eq!(state.starting_street_pot, 5.0);
+        assert_eq!(state.street_investment, 5.0);
+
+        // Still facing a bet
+        let turn = Card::from_str("2h").unwrap();
+        assert!(state.deal_card(turn).is_err());
+
+        state.commit(5.0);
+        state.to_call = 0.0;
+        assert_eq!(state.street_investment, 10.0);
+
+        state.deal_card(turn).unwrap();
+        assert_eq!(state.street, Street::Turn);
+        assert_eq!(state.starting_street_pot, 15.0);
+        assert_eq!(state.street_investment, 0.0);
+        assert_eq!(state.position, Position::OOP);
+        assert!(state.deal_card(turn).is_err());
     }
 
     #[test]
@@ -196,6 +572,15 @@ mod tests {
         assert!(Position::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_position_other() {
+        assert_eq!(Position::IP.other(), Position::OOP);
+        assert_eq!(Position::OOP.other(), Position::IP);
+        assert_eq!(Position::IP.other().other(), Position::IP);
+        assert!(Position::OOP.acts_first());
+        assert!(!Position::IP.acts_first());
+    }
+
     #[test]
     fn test_game_state_validation() {
         let hand = Hand::from_str("AhKd").unwrap();
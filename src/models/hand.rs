@@ -1,7 +1,7 @@
 //! Hand representation (2-card poker hands)
 
 use crate::error::{ModelError, Result};
-use crate::models::card::Card;
+use crate::models::card::{Card, Rank, Suit};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 
@@ -20,6 +20,52 @@ impl Hand {
         }
     }
 
+    /// One representative combo of a hand class like "AKs", "AKo" or "QQ",
+    /// for examples and quick tests.
+    ///
+    /// Suited classes are spades (`AsKs`); pairs and offsuit classes take
+    /// spades then hearts (`AsKh`). Non-pair classes need their `s`/`o`
+    /// suffix, since "AK" alone doesn't say which.
+    pub fn from_class(class: &str) -> Result<Hand> {
+        let invalid =
+            |reason: &str| ModelError::InvalidRange(class.to_string(), reason.to_string());
+
+        let chars: Vec<char> = class.chars().collect();
+        if chars.len() < 2 || chars.len() > 3 {
+            return Err(invalid("expected a hand class like 'AKs', 'AKo' or 'QQ'"));
+        }
+        let high = Rank::from_str(&chars[0].to_string()).map_err(|_| invalid("unknown rank"))?;
+        let low = Rank::from_str(&chars[1].to_string()).map_err(|_| invalid("unknown rank"))?;
+
+        let second_suit = match (high == low, chars.get(2)) {
+            (true, None) => Suit::Hearts,
+            (true, Some(_)) => return Err(invalid("pairs take no 's' or 'o' suffix")),
+            (false, Some('s' | 'S')) => Suit::Spades,
+            (false, Some('o' | 'O')) => Suit::Hearts,
+            (false, None) => return Err(invalid("ambiguous, add 's' or 'o'")),
+            (false, Some(_)) => return Err(invalid("suffix must be 's' or 'o'")),
+        };
+
+        Ok(Hand::new(Card::new(high, Suit::Spades), Card::new(low, second_suit)).canonical())
+    }
+
+    /// The same combo with its cards in canonical order: higher rank first,
+    /// and for pairs the lower suit first. `Range` stores combos this way.
+    pub fn canonical(&self) -> Hand {
+        let [c1, c2] = self.cards;
+        if c1.rank < c2.rank || (c1.rank == c2.rank && c1.suit > c2.suit) {
+            Hand::new(c2, c1)
+        } else {
+            Hand::new(c1, c2)
+        }
+    }
+
+    /// Identity of the combo regardless of card order: `AsAh` and `AhAs`
+    /// share a key. `Range` keys its combos on this.
+    pub fn key(&self) -> u64 {
+        Card::pokers_mask(&self.cards)
+    }
+
     /// Returns true if both cards have the same suit
     pub fn is_suited(&self) -> bool {
         self.cards[0].suit == self.cards[1].suit
@@ -30,6 +76,25 @@ impl Hand {
         self.cards[0].rank == self.cards[1].rank
     }
 
+    /// Returns true if `card` is one of the two hole cards
+    pub fn contains_card(&self, card: &Card) -> bool {
+        self.cards.contains(card)
+    }
+
+    /// Given one hole card, returns the other (`None` if `card` isn't held)
+    pub fn other_card(&self, card: &Card) -> Option<Card> {
+        match self.cards {
+            [a, b] if a == *card => Some(b),
+            [a, b] if b == *card => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the exact combo in input notation (e.g., "AhKd")
+    pub fn combo_notation(&self) -> String {
+        format!("{}{}", self.cards[0].notation(), self.cards[1].notation())
+    }
+
     /// Returns the hand in canonical notation (e.g., "AKs", "QQ", "T9o")
     pub fn notation(&self) -> String {
         let mut ranks = [self.cards[0].rank, self.cards[1].rank];
@@ -100,4 +165,49 @@ mod tests {
         assert_eq!(Hand::from_str("AhKh").unwrap().notation(), "AKs");
         assert_eq!(Hand::from_str("AhAd").unwrap().notation(), "AA");
     }
+
+    #[test]
+    fn test_combo_notation() {
+        let hand = Hand::from_str("AhKd").unwrap();
+        assert_eq!(hand.combo_notation(), "AhKd");
+        assert_eq!(Hand::from_str(&hand.combo_notation()).unwrap(), hand);
+    }
+
+    #[test]
+    fn test_contains_card() {
+        let hand = Hand::from_str("AhKd").unwrap();
+        assert!(hand.contains_card(&Card::from_str("Ah").unwrap()));
+        assert!(hand.contains_card(&Card::from_str("Kd").unwrap()));
+        assert!(!hand.contains_card(&Card::from_str("Ad").unwrap()));
+    }
+
+    #[test]
+    fn test_from_class() {
+        let suited = Hand::from_class("AKs").unwrap();
+        assert!(suited.is_suited());
+        assert_eq!(suited.notation(), "AKs");
+
+        let offsuit = Hand::from_class("AKo").unwrap();
+        assert!(!offsuit.is_suited());
+        assert_eq!(offsuit.notation(), "AKo");
+
+        // Rank order and case don't matter
+        assert_eq!(Hand::from_class("kqo").unwrap().notation(), "KQo");
+        assert_eq!(Hand::from_class("KAs").unwrap(), suited);
+        assert_eq!(Hand::from_class("QQ").unwrap().notation(), "QQ");
+
+        for bad in ["", "A", "AK", "AKx", "QQs", "ZZ", "AKs+"] {
+            assert!(Hand::from_class(bad).is_err(), "'{}' should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn test_other_card() {
+        let hand = Hand::from_str("AhKd").unwrap();
+        let ace = Card::from_str("Ah").unwrap();
+        let king = Card::from_str("Kd").unwrap();
+        assert_eq!(hand.other_card(&ace), Some(king));
+        assert_eq!(hand.other_card(&king), Some(ace));
+        assert_eq!(hand.other_card(&Card::from_str("Qc").unwrap()), None);
+    }
 }
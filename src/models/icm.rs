@@ -0,0 +1,156 @@
+//! Independent Chip Model for tournament payoffs
+//!
+//! In a tournament, chips aren't worth their face value: doubling a stack
+//! doesn't double its share of the prize pool, and busting on the bubble
+//! costs far more than the chips lost. ICM values each stack by its
+//! expected payout, using the Malmuth-Harville model: a player finishes
+//! first with probability proportional to their stack, and the remaining
+//! places are filled the same way among the players left.
+
+use crate::error::{ModelError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Stacks at the table and the payout structure of a tournament spot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IcmModel {
+    /// Every player's chips behind at the decision, in big blinds, not
+    /// counting the pot
+    pub stacks: Vec<f64>,
+    /// Prize for each finishing place, first place first
+    pub payouts: Vec<f64>,
+    /// Index of hero's stack in `stacks`
+    pub hero_seat: usize,
+    /// Index of villain's stack in `stacks`
+    pub villain_seat: usize,
+}
+
+impl IcmModel {
+    /// Most players ICM is computed for; the cost grows with
+    /// players^payouts
+    pub const MAX_PLAYERS: usize = 10;
+
+    /// Validated model; hero and villain must be two different seats and
+    /// stacks and payouts must be non-negative
+    pub fn new(
+        stacks: Vec<f64>,
+        payouts: Vec<f64>,
+        hero_seat: usize,
+        villain_seat: usize,
+    ) -> Result<Self> {
+        let invalid = |reason: String| ModelError::InvalidGameState(format!("ICM: {}", reason));
+        if stacks.len() < 2 || stacks.len() > Self::MAX_PLAYERS {
+            return Err(invalid(format!(
+                "expected 2 to {} stacks, got {}",
+                Self::MAX_PLAYERS,
+                stacks.len()
+            )));
+        }
+        if payouts.is_empty() {
+            return Err(invalid("no payouts".to_string()));
+        }
+        if stacks
+            .iter()
+            .chain(&payouts)
+            .any(|v| v.is_nan() || *v < 0.0)
+        {
+            return Err(invalid(
+                "stacks and payouts must be non-negative".to_string(),
+            ));
+        }
+        if hero_seat >= stacks.len() || villain_seat >= stacks.len() || hero_seat == villain_seat {
+            return Err(invalid(format!(
+                "hero seat {} and villain seat {} must be two different seats of {}",
+                hero_seat,
+                villain_seat,
+                stacks.len()
+            )));
+        }
+        Ok(Self {
+            stacks,
+            payouts,
+            hero_seat,
+            villain_seat,
+        })
+    }
+
+    /// Expected payout of `seat` once `seat` has `stack` chips and `other`
+    /// has `other_stack`, everyone else keeping theirs
+    pub fn equity_with(&self, seat: usize, stack: f64, other: usize, other_stack: f64) -> f64 {
+        let mut stacks = self.stacks.clone();
+        stacks[seat] = stack.max(0.0);
+        stacks[other] = other_stack.max(0.0);
+        icm_equities(&stacks, &self.payouts)[seat]
+    }
+}
+
+/// Expected payout of every stack under Malmuth-Harville.
+///
+/// Players with no chips have busted and get nothing; places below the
+/// last player with chips go unpaid.
+pub fn icm_equities(stacks: &[f64], payouts: &[f64]) -> Vec<f64> {
+    let mut equities = vec![0.0; stacks.len()];
+    let alive: Vec<usize> = (0..stacks.len()).filter(|&i| stacks[i] > 0.0).collect();
+    place(stacks, payouts, &alive, 1.0, &mut equities);
+    equities
+}
+
+/// Hand out `payouts[0]` among `remaining` players with probability
+/// proportional to stack, then recurse on the places below
+fn place(stacks: &[f64], payouts: &[f64], remaining: &[usize], prob: f64, equities: &mut [f64]) {
+    let Some((&prize, lower)) = payouts.split_first() else {
+        return;
+    };
+    let total: f64 = remaining.iter().map(|&i| stacks[i]).sum();
+    if total <= 0.0 {
+        return;
+    }
+    for (k, &i) in remaining.iter().enumerate() {
+        let p = prob * stacks[i] / total;
+        equities[i] += p * prize;
+        if !lower.is_empty() && remaining.len() > 1 {
+            let rest: Vec<usize> = remaining[..k]
+                .iter()
+                .chain(&remaining[k + 1..])
+                .copied()
+                .collect();
+            place(stacks, lower, &rest, p, equities);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icm_equities() {
+        // Winner takes all is chip-linear
+        let equities = icm_equities(&[30.0, 10.0], &[1.0]);
+        assert!((equities[0] - 0.75).abs() < 1e-12);
+
+        // Equal stacks split the prize pool evenly
+        let equities = icm_equities(&[10.0, 10.0, 10.0], &[0.5, 0.3, 0.2]);
+        for equity in &equities {
+            assert!((equity - 1.0 / 3.0).abs() < 1e-12);
+        }
+
+        // The chip leader's equity is less than its chip share
+        let equities = icm_equities(&[50.0, 25.0, 25.0], &[0.5, 0.3, 0.2]);
+        assert!(equities[0] < 0.5 && equities[0] > 1.0 / 3.0);
+        assert!((equities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        // A busted player gets nothing while places are left for the others
+        let equities = icm_equities(&[0.0, 20.0, 10.0], &[0.7, 0.3]);
+        assert_eq!(equities[0], 0.0);
+        assert!((equities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_model_validation() {
+        assert!(IcmModel::new(vec![10.0, 10.0], vec![1.0], 0, 1).is_ok());
+        assert!(IcmModel::new(vec![10.0], vec![1.0], 0, 0).is_err());
+        assert!(IcmModel::new(vec![10.0, 10.0], vec![], 0, 1).is_err());
+        assert!(IcmModel::new(vec![10.0, 10.0], vec![1.0], 1, 1).is_err());
+        assert!(IcmModel::new(vec![10.0, -1.0], vec![1.0], 0, 1).is_err());
+    }
+}
@@ -1,15 +1,22 @@
 //! Data models for the Poker GTO Strategy Engine
 
 pub mod action;
+pub mod board;
 pub mod card;
 pub mod game_state;
 pub mod hand;
+pub mod icm;
+pub mod preflop;
+pub mod presets;
 pub mod range;
 pub mod strategy;
 
-pub use action::{Action, BetSize};
+pub use action::{Action, BetSize, StreetBetSizes};
+pub use board::Board;
 pub use card::{Card, Rank, Suit};
 pub use game_state::{GameState, Position, Street};
 pub use hand::Hand;
-pub use range::Range;
-pub use strategy::{ActionStrategy, Strategy};
+pub use range::{Range, RangeShape, ShapeEstimate};
+pub use strategy::{
+    ActionStrategy, ComboStrategy, EvUnit, Provenance, RangeStrategy, Strategy, Subsample,
+};
@@ -0,0 +1,71 @@
+//! Preflop hand strength ranking
+//!
+//! The 169 hand classes ordered by all-in equity against a random hand,
+//! strongest first. It is a rough ordering, good enough to tell premium
+//! hands from marginal ones when describing the shape of a range.
+
+use crate::models::hand::Hand;
+
+/// Every hand class, strongest first
+pub const PREFLOP_ORDER: [&str; 169] = [
+    "AA", "KK", "QQ", "AKs", "JJ", "AQs", "KQs", "AJs", "KJs", "TT", "AKo", "ATs", "QJs", "KTs",
+    "QTs", "JTs", "99", "AQo", "A9s", "KQo", "88", "K9s", "T9s", "A8s", "Q9s", "J9s", "AJo", "A5s",
+    "77", "A7s", "KJo", "A4s", "A3s", "A6s", "QJo", "66", "K8s", "T8s", "A2s", "98s", "J8s", "ATo",
+    "Q8s", "K7s", "KTo", "55", "JTo", "87s", "QTo", "44", "33", "22", "K6s", "97s", "K5s", "76s",
+    "T7s", "K4s", "K3s", "K2s", "Q7s", "86s", "65s", "J7s", "54s", "Q6s", "75s", "96s", "Q5s",
+    "64s", "Q4s", "Q3s", "T9o", "T6s", "Q2s", "A9o", "53s", "85s", "J6s", "J9o", "K9o", "J5s",
+    "Q9o", "43s", "74s", "J4s", "J3s", "95s", "J2s", "63s", "A8o", "52s", "T5s", "84s", "T4s",
+    "T3s", "42s", "T2s", "98o", "T8o", "A5o", "A7o", "73s", "A4o", "32s", "94s", "93s", "J8o",
+    "A3o", "62s", "92s", "K8o", "A6o", "87o", "Q8o", "83s", "A2o", "82s", "97o", "72s", "76o",
+    "K7o", "65o", "T7o", "K6o", "86o", "54o", "K5o", "J7o", "75o", "Q7o", "K4o", "K3o", "96o",
+    "K2o", "64o", "Q6o", "53o", "85o", "T6o", "Q5o", "43o", "Q4o", "Q3o", "74o", "Q2o", "J6o",
+    "63o", "J5o", "95o", "52o", "J4o", "J3o", "42o", "J2o", "84o", "T5o", "T4o", "32o", "T3o",
+    "73o", "T2o", "62o", "94o", "93o", "92o", "83o", "82o", "72o",
+];
+
+/// Number of combos in a class: 6 for pairs, 4 suited, 12 offsuit
+fn class_combos(class: &str) -> usize {
+    match class.as_bytes() {
+        [a, b] if a == b => 6,
+        [_, _, b's'] => 4,
+        _ => 12,
+    }
+}
+
+/// Position of `hand`'s class in `PREFLOP_ORDER` (0 for AA)
+pub fn class_rank(hand: &Hand) -> usize {
+    let class = hand.notation();
+    PREFLOP_ORDER
+        .iter()
+        .position(|c| *c == class)
+        .expect("every hand belongs to one of the 169 classes")
+}
+
+/// Share of all 1326 combos ranked above `hand`'s class, and that share
+/// plus the class itself: AA is (0.0, 6/1326), the weakest class ends at 1.0
+pub fn strength_interval(hand: &Hand) -> (f64, f64) {
+    let rank = class_rank(hand);
+    let above: usize = PREFLOP_ORDER[..rank].iter().map(|c| class_combos(c)).sum();
+    let own = class_combos(PREFLOP_ORDER[rank]);
+    (above as f64 / 1326.0, (above + own) as f64 / 1326.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_order_covers_every_combo() {
+        let total: usize = PREFLOP_ORDER.iter().map(|c| class_combos(c)).sum();
+        assert_eq!(total, 1326);
+
+        assert_eq!(class_rank(&Hand::from_str("AhAd").unwrap()), 0);
+        assert_eq!(class_rank(&Hand::from_str("7h2d").unwrap()), 168);
+        assert!(
+            class_rank(&Hand::from_str("AhKh").unwrap())
+                < class_rank(&Hand::from_str("AhKd").unwrap())
+        );
+        assert_eq!(strength_interval(&Hand::from_str("7h2d").unwrap()).1, 1.0);
+    }
+}
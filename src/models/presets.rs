@@ -0,0 +1,36 @@
+//! Named preset ranges
+//!
+//! Rough 6-max, 100 BB ranges for common spots, for users who don't know
+//! Equilab notation. Any range can use one as an `@name` token, e.g.
+//! `--villain-range @BTN_open` or `@BB_defend,AQo`.
+
+/// Preset names with their Equilab notation, tightest first
+pub const PRESETS: &[(&str, &str)] = &[
+    ("UTG_open", "77+,ATs+,KTs+,QTs+,JTs,T9s,AJo+,KQo"),
+    ("MP_open", "66+,A9s+,A5s,KTs+,QTs+,JTs,T9s,98s,ATo+,KJo+"),
+    (
+        "CO_open",
+        "44+,A2s+,K8s+,Q9s+,J9s+,T8s+,97s+,87s,76s,A9o+,KTo+,QTo+,JTo",
+    ),
+    (
+        "BTN_open",
+        "22+,A2s+,K5s+,Q7s+,J7s+,T7s+,96s+,85s+,75s+,64s+,54s,A5o+,K9o+,Q9o+,J9o+,T9o",
+    ),
+    (
+        "SB_open",
+        "22+,A2s+,K6s+,Q8s+,J8s+,T8s+,97s+,86s+,76s,65s,A7o+,KTo+,QTo+,JTo",
+    ),
+    (
+        "BB_defend",
+        "99-22,A2s-AJs,K2s-KJs,Q5s+,J7s+,T7s+,96s+,85s+,75s+,64s+,53s+,A2o-AJo,K8o-KJo,Q9o+,J9o+,T8o+,98o",
+    ),
+    ("BTN_3bet", "QQ+,AKs,A5s,A4s,KQs,AKo"),
+];
+
+/// Notation of the preset called `name`, ignoring case
+pub fn preset(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+        .map(|(_, notation)| *notation)
+}
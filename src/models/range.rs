@@ -1,17 +1,45 @@
 //! Hand range representation
 
 use crate::error::{ModelError, Result};
-use crate::models::card::{Card, Rank, Suit};
+use crate::models::card::{Card, Rank};
 use crate::models::hand::Hand;
+use crate::models::preflop;
+use crate::models::presets;
 use pokers::HandRange;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Broad shape of a range by preflop strength (see `Range::shape`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RangeShape {
+    /// Strong hands and weak hands with a hole in the middle
+    Polarized,
+    /// A continuous block of hands from the top down (also "merged")
+    Linear,
+    /// Missing the strongest hands
+    Capped,
+}
+
+/// A range's shape with how clearly it has it, from 0.5 (borderline) to
+/// 1.0 (unmistakable)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeEstimate {
+    /// The shape
+    pub shape: RangeShape,
+    /// Confidence in `shape`, 0.5 to 1.0
+    pub confidence: f64,
+}
 
 /// A collection of possible hole card combinations with weights
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Range {
-    /// Map from hand combination to weight (0.0-1.0)
-    pub hands: HashMap<Hand, f64>,
+    /// Each combo, in canonical card order, with its weight (0.0-1.0),
+    /// keyed by `Hand::key` so either card order finds the same entry.
+    /// Ordered by key, so iteration, and with it seeded sampling, is the
+    /// same every run.
+    #[serde(with = "combo_keys")]
+    hands: BTreeMap<u64, (Hand, f64)>,
 }
 
 impl Default for Range {
@@ -24,13 +52,22 @@ impl Range {
     /// Create a new empty range
     pub fn new() -> Self {
         Self {
-            hands: HashMap::new(),
+            hands: BTreeMap::new(),
         }
     }
 
-    /// Parse range from Equilab-style notation
+    /// Parse range from Equilab-style notation.
+    ///
+    /// `@name` tokens expand to the named preset (see `models::presets`).
+    /// A token may end in `:weight` with a weight in [0, 1] (e.g.
+    /// `"AA:0.5,KK"`), the same as `@percent` (`"AA@50"`); weights are kept
+    /// to whole percent.
+    ///
+    /// A token that isn't a hand class (`AKs`, `QQ+`, `JJ-99`, `A9s-A6s`)
+    /// or a specific combo (`AhKd`) is an error naming the token and its
+    /// character position in `notation`, counting from 0.
     pub fn from_notation(notation: &str) -> Result<Self> {
-        let mut hands = HashMap::new();
+        let mut range = Range::new();
 
         if notation.trim().is_empty() {
             return Err(ModelError::InvalidRange(
@@ -39,53 +76,147 @@ impl Range {
             ));
         }
 
+        let invalid = |reason: String| ModelError::InvalidRange(notation.to_string(), reason);
+        let mut next_offset = 0;
+        let tokens = notation
+            .split(',')
+            .map(|token| {
+                let offset = next_offset;
+                next_offset += token.len() + 1;
+                let (token, percent) = match token.split_once(':') {
+                    Some((token, weight)) => {
+                        let weight: f64 = weight
+                            .trim()
+                            .parse()
+                            .ok()
+                            .filter(|w| (0.0..=1.0).contains(w))
+                            .ok_or_else(|| {
+                                invalid(format!(
+                                    "Invalid weight '{}' for '{}' (expected 0 to 1)",
+                                    weight.trim(),
+                                    token.trim()
+                                ))
+                            })?;
+                        (token, Some((weight * 100.0).round() as u8))
+                    }
+                    None => (token, None),
+                };
+                let expanded = match token.trim().strip_prefix('@') {
+                    Some(name) => presets::preset(name).ok_or_else(|| {
+                        invalid(format!("Unknown preset '@{}' (see --list-ranges)", name))
+                    })?,
+                    None => {
+                        // pokers drops tokens it can't read, so catch them here
+                        let leading = token.len() - token.trim_start().len();
+                        check_token(token.trim()).map_err(|reason| {
+                            invalid(format!(
+                                "'{}' at position {}: {}",
+                                token.trim(),
+                                notation[..offset + leading].chars().count(),
+                                reason
+                            ))
+                        })?;
+                        token
+                    }
+                };
+                Ok(match percent {
+                    // A weighted preset weighs every one of its tokens
+                    Some(percent) => expanded
+                        .split(',')
+                        .map(|t| format!("{}@{}", t.trim(), percent))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    None => expanded.to_string(),
+                })
+            })
+            .collect::<Result<Vec<String>>>()?;
+
         // Use pokers crate for parsing
-        let range = HandRange::from_string(notation.to_string());
+        let parsed = HandRange::from_string(tokens.join(","));
 
-        for combo in range.hands {
-            // Mapping from discovery
+        for combo in parsed.hands {
             let c1_u8 = combo.0;
             let c2_u8 = combo.1;
             let weight_u8 = combo.2;
 
-            let c1 = u8_to_card(c1_u8);
-            let c2 = u8_to_card(c2_u8);
+            let c1 = Card::from_pokers_index(c1_u8)?;
+            let c2 = Card::from_pokers_index(c2_u8)?;
 
-            // Hand construction from 2 cards
-            // Hand logic might reorder them? Hand::new() usually stores them.
-            // Hand::new checks nothing about order.
-            // Hand::from_str parses string.
-            // But Hand struct doesn't enforce order, so equality might fail if order differs?
-            // "AhAs" vs "AsAh".
-            // Hand struct: `pub cards: [Card; 2]`
-            // Derive PartialEq checks exact array equality.
-            // So Hand(Ah, As) != Hand(As, Ah).
+            let weight = weight_u8 as f64 / 100.0;
+
+            range.add(Hand::new(c1, c2), weight);
+        }
 
-            // We MUST ensure consistent ordering in Hand constructor if we want equality to work as set membership.
-            // Or Range::contains must check both permutations.
-            // OR Hand::new sorts them.
+        Ok(range)
+    }
 
-            // Checking Hand::new in src/models/hand.rs:
-            // pub fn new(card1: Card, card2: Card) -> Self { Self { cards: [card1, card2] } }
+    /// Range of the given combos, in either card order; a combo listed
+    /// twice keeps its last weight
+    pub fn from_combos<I: IntoIterator<Item = (Hand, f64)>>(combos: I) -> Self {
+        let mut range = Range::new();
+        for (hand, weight) in combos {
+            range.add(hand, weight);
+        }
+        range
+    }
 
-            // It preserves order.
-            // So we need to sort them to be canonical.
+    /// Insert `hand` at `weight`, replacing its weight if the range already
+    /// holds the combo in either card order
+    pub fn add(&mut self, hand: Hand, weight: f64) {
+        self.hands.insert(hand.key(), (hand.canonical(), weight));
+    }
 
-            let _hand = Hand::new(c1, c2); // We should sort here
-            let weight = weight_u8 as f64 / 100.0;
+    /// Weight of `hand` in either card order, `None` if not in the range
+    pub fn weight(&self, hand: &Hand) -> Option<f64> {
+        self.hands.get(&hand.key()).map(|(_, weight)| *weight)
+    }
 
-            // Try to insert canonical hand
-            let canonical = canonical_hand(c1, c2);
+    /// Every combo with its weight as text, in key order, so two ranges
+    /// have the same key exactly when they hold the same weights
+    pub fn cache_key(&self) -> String {
+        self.hands()
+            .map(|(hand, weight)| format!("{}:{}", hand.combo_notation(), weight))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
 
-            hands.insert(canonical, weight);
-        }
+    /// The preset range called `name` (see `models::presets`), ignoring case
+    pub fn from_preset(name: &str) -> Result<Self> {
+        let notation = presets::preset(name).ok_or_else(|| {
+            ModelError::InvalidRange(name.to_string(), "Unknown preset".to_string())
+        })?;
+        Self::from_notation(notation)
+    }
 
-        Ok(Self { hands })
+    /// Every combo in explicit notation (e.g. "AhKd,QsQc"), in a stable order.
+    ///
+    /// Parsing the result with `from_notation` gives back the same combos;
+    /// weights are not included.
+    pub fn to_notation(&self) -> String {
+        let mut combos: Vec<String> = self
+            .hands
+            .values()
+            .map(|(hand, _)| hand.combo_notation())
+            .collect();
+        combos.sort();
+        combos.join(",")
     }
 
-    /// Returns all hands in the range
+    /// Returns all hands in the range, ordered by `Hand::key`
     pub fn hands(&self) -> impl Iterator<Item = (&Hand, f64)> {
-        self.hands.iter().map(|(h, w)| (h, *w))
+        self.hands.values().map(|(h, w)| (h, *w))
+    }
+
+    /// Every combo with its share of the range's total weight.
+    ///
+    /// Shares sum to 1.0, and each combo counts on its own, so a pair class
+    /// (6 combos) outweighs a suited class (4 combos) at equal weights.
+    /// Yields nothing if the total weight is not positive.
+    pub fn weighted_iter(&self) -> impl Iterator<Item = (&Hand, f64)> {
+        let total: f64 = self.hands().map(|(_, weight)| weight).sum();
+        self.hands()
+            .filter(move |_| total > 0.0)
+            .map(move |(hand, weight)| (hand, weight / total))
     }
 
     /// Returns number of hand combinations
@@ -93,108 +224,271 @@ impl Range {
         self.hands.len()
     }
 
+    /// Number of hand combinations, same as `num_combos`
+    pub fn len(&self) -> usize {
+        self.hands.len()
+    }
+
+    /// Returns true if the range holds no combos
+    pub fn is_empty(&self) -> bool {
+        self.hands.is_empty()
+    }
+
+    /// Every combo not in this range and not holding a `dead` card, each at
+    /// weight 1.0 (e.g. everything villain doesn't 3bet)
+    pub fn complement(&self, dead: &[Card]) -> Range {
+        let cards: Vec<Card> = (0..52)
+            .filter_map(|i| Card::from_pokers_index(i).ok())
+            .filter(|c| !dead.contains(c))
+            .collect();
+        let mut complement = Range::new();
+        for (i, &c1) in cards.iter().enumerate() {
+            for &c2 in &cards[i + 1..] {
+                let hand = Hand::new(c1, c2);
+                if !self.contains(&hand) {
+                    complement.add(hand, 1.0);
+                }
+            }
+        }
+        complement
+    }
+
     /// Remove combos that conflict with known cards (blockers)
     pub fn remove_blockers(&mut self, cards: &[Card]) {
-        self.hands.retain(|hand, _| {
-            // retain if NEITHER card is in blockers
-            !cards.contains(&hand.cards[0]) && !cards.contains(&hand.cards[1])
-        });
+        // retain if NEITHER card is in blockers
+        self.retain(|hand, _| !cards.iter().any(|c| hand.contains_card(c)));
+    }
+
+    /// Keep only combos for which `f(hand, weight)` is true (mutates in place)
+    pub fn retain<F: Fn(&Hand, f64) -> bool>(&mut self, f: F) {
+        self.hands.retain(|_, (hand, weight)| f(hand, *weight));
+    }
+
+    /// Returns a copy holding only combos for which `f(hand, weight)` is true
+    pub fn filter<F: Fn(&Hand, f64) -> bool>(&self, f: F) -> Range {
+        let mut filtered = self.clone();
+        filtered.retain(f);
+        filtered
+    }
+
+    /// Shape of the range by the preflop strength of its combos (see
+    /// `models::preflop`), ignoring weights; `None` for an empty range.
+    ///
+    /// Each class covers the slice of all 1326 combos it ranks at. A range
+    /// whose best class is outside the top `CAPPED_ABOVE` is capped.
+    /// Otherwise it is polarized when the largest uncovered stretch between
+    /// its best and worst class is at least half of that span, and linear
+    /// when it isn't; confidence is how far past the cutoff it is.
+    pub fn shape(&self) -> Option<ShapeEstimate> {
+        let mut intervals: Vec<(f64, f64)> = self
+            .hands()
+            .filter(|(_, weight)| *weight > 0.0)
+            .map(|(hand, _)| preflop::strength_interval(hand))
+            .collect();
+        intervals.sort_by(|a, b| a.0.total_cmp(&b.0));
+        intervals.dedup();
+        let (&(top, _), rest) = intervals.split_first()?;
+
+        if top >= Self::CAPPED_ABOVE {
+            return Some(ShapeEstimate {
+                shape: RangeShape::Capped,
+                confidence: (top / (2.0 * Self::CAPPED_ABOVE)).clamp(0.5, 1.0),
+            });
+        }
+
+        let mut covered_to = intervals[0].1;
+        let mut largest_gap: f64 = 0.0;
+        for &(start, end) in rest {
+            largest_gap = largest_gap.max(start - covered_to);
+            covered_to = covered_to.max(end);
+        }
+        let gap_share = largest_gap / (covered_to - top);
+        Some(if gap_share >= 0.5 {
+            ShapeEstimate {
+                shape: RangeShape::Polarized,
+                confidence: gap_share,
+            }
+        } else {
+            ShapeEstimate {
+                shape: RangeShape::Linear,
+                confidence: 1.0 - gap_share,
+            }
+        })
+    }
+
+    /// Share of the strongest combos a range must reach into to not be
+    /// capped: 5%, the classes from AA down to QJs
+    pub const CAPPED_ABOVE: f64 = 0.05;
+
+    /// True if `shape` finds the range polarized
+    pub fn is_polarized(&self) -> bool {
+        self.shape()
+            .is_some_and(|s| s.shape == RangeShape::Polarized)
+    }
+
+    /// True if `shape` finds the range linear
+    pub fn is_linear(&self) -> bool {
+        self.shape().is_some_and(|s| s.shape == RangeShape::Linear)
     }
 
     /// Returns true if range contains the specified hand
     pub fn contains(&self, hand: &Hand) -> bool {
-        // Check canonical form
-        let canonical = canonical_hand(hand.cards[0], hand.cards[1]);
-        self.hands.contains_key(&canonical)
+        self.hands.contains_key(&hand.key())
     }
-}
 
-fn canonical_hand(c1: Card, c2: Card) -> Hand {
-    if c1.rank > c2.rank {
-        Hand::new(c1, c2)
-    } else if c1.rank < c2.rank {
-        Hand::new(c2, c1)
-    } else {
-        // Pairs: sort by suit?
-        // Suit order: Spades > Hearts > Clubs > Diamonds?
-        // Or just consistent order.
-        if c1.suit > c2.suit {
-            // Suit derive Ord? No, derived PartialOrd. Enum order: Hearts, Diamonds, Clubs, Spades (0,1,2,3 from macro? No manual).
-            // src/models/card.rs:
-            // Hearts, Diamonds, Clubs, Spades.
-            // Hearts=0, Diamonds=1, Clubs=2, Spades=3.
-            // So Spades > Hearts.
-            Hand::new(c2, c1) // Want higher first?
-        } else {
-            Hand::new(c1, c2)
+    /// Weight of each hand class in the standard 13x13 matrix.
+    ///
+    /// Rows and columns run from Ace (0) down to Two (12). Pairs sit on the
+    /// diagonal, suited hands above it (`[high][low]`) and offsuit hands
+    /// below it (`[low][high]`). Each cell is the average weight over every
+    /// combo of the class, counting missing combos as 0.0, so a class with
+    /// half its combos at full weight shows 0.5.
+    pub fn to_grid_weights(&self) -> [[f64; 13]; 13] {
+        let mut grid = [[0.0; 13]; 13];
+        for (hand, weight) in self {
+            let a = 12 - hand.cards[0].rank.index() as usize;
+            let b = 12 - hand.cards[1].rank.index() as usize;
+            let (high, low) = (a.min(b), a.max(b));
+            let (row, col, combos) = if high == low {
+                (high, low, 6.0)
+            } else if hand.is_suited() {
+                (high, low, 4.0)
+            } else {
+                (low, high, 12.0)
+            };
+            grid[row][col] += weight / combos;
+        }
+        grid
+    }
+
+    /// Scale every weight so the largest becomes 1.0 (mutates in place).
+    ///
+    /// Relative weights are preserved. Does nothing on an empty range or
+    /// when every weight is zero.
+    pub fn normalize_weights(&mut self) {
+        let max = self.hands().map(|(_, weight)| weight).fold(0.0, f64::max);
+        if max > 0.0 {
+            self.scale(1.0 / max);
+        }
+    }
+
+    /// Multiply every weight by `factor` (mutates in place).
+    ///
+    /// Weights are not clamped afterwards; call `clamp_weights` if the
+    /// result must stay within [0, 1].
+    pub fn scale(&mut self, factor: f64) {
+        for (_, weight) in self.hands.values_mut() {
+            *weight *= factor;
+        }
+    }
+
+    /// Clamp every weight into [0.0, 1.0] (mutates in place).
+    pub fn clamp_weights(&mut self) {
+        for (_, weight) in self.hands.values_mut() {
+            *weight = weight.clamp(0.0, 1.0);
         }
     }
 }
 
-// Need to update u8_to_card because previous discovery of masks was "0..52".
-// Mask logic: `shift = r + (3 - s) * 16`.
-// But combo.0 is likely just 0..51.
-// Discovery:
-// 2s2h -> Combo(1, 0, 100).
-// 2s is 1? 2h is 0?
-// Wait, 2s mask was 2^0? No, 2^1?
-// discover_masks failed to print readable output.
-// Let's re-verify u8 to Card mapping from `pokers` source or discovery.
-
-// pokers 0.7 source `card.rs`:
-// 0 = 2h, 1 = 2d, 2 = 2c, 3 = 2s?
-// Or 2c, 2d, 2h, 2s?
-// Let's assume standard: 2c, 2d, 2h, 2s (0,1,2,3).
-// Rank major? 2c..2s, 3c..3s... Ac..As?
-// Or Suit major? 2c..Ac, 2d..Ad...
-
-// Discovery output:
-// 2s2h -> Combo(1, 0, 100).
-// 2d2c -> Combo(3, 2, 100).
-// This implies:
-// 0 = 2h
-// 1 = 2s
-// 2 = 2c
-// 3 = 2d
-// That is WEIRD.
-// 2h (Hearts), 2s (Spades), 2c (Clubs), 2d (Diamonds).
-// Maybe order is Hearts, Spades, Clubs, Diamonds?
-// Or maybe it's Rank=2, Suits are mapped strangely.
-
-// Let's implement u8_to_card based on this hypothesis:
-// 0=2h, 1=2s, 2=2c, 3=2d.
-// 4=3h, 5=3s, 6=3c, 7=3d.
-// Pattern: Rank * 4 + SuitIndex.
-// SuitIndex: h=0, s=1, c=2, d=3.
-
-fn u8_to_card(val: u8) -> Card {
-    let r_val = val / 4;
-    let s_val = val % 4;
-
-    let rank = match r_val {
-        0 => Rank::Two,
-        1 => Rank::Three,
-        2 => Rank::Four,
-        3 => Rank::Five,
-        4 => Rank::Six,
-        5 => Rank::Seven,
-        6 => Rank::Eight,
-        7 => Rank::Nine,
-        8 => Rank::Ten,
-        9 => Rank::Jack,
-        10 => Rank::Queen,
-        11 => Rank::King,
-        12 => Rank::Ace,
-        _ => Rank::Ace,
+/// A hand class such as `QQ`, `AK` or `T9s` as (first rank, second rank,
+/// suitedness); ranks are case-insensitive as in pokers
+fn parse_class(class: &str) -> Option<(Rank, Rank, Option<char>)> {
+    let mut chars = class.chars().map(|c| c.to_ascii_uppercase());
+    let rank = |c: char| Rank::from_str(c.encode_utf8(&mut [0; 4])).ok();
+    let first = rank(chars.next()?)?;
+    let second = rank(chars.next()?)?;
+    let suitedness = match chars.next() {
+        None => None,
+        Some(c @ ('S' | 'O')) if first != second => Some(c),
+        Some(_) => return None,
     };
+    chars
+        .next()
+        .is_none()
+        .then_some((first, second, suitedness))
+}
 
-    let suit = match s_val {
-        0 => Suit::Hearts,
-        1 => Suit::Spades,
-        2 => Suit::Clubs,
-        3 => Suit::Diamonds,
-        _ => Suit::Hearts,
+/// Why `token`, trimmed and without a `:weight`, isn't something pokers
+/// reads, or `Ok` if it is
+fn check_token(token: &str) -> std::result::Result<(), &'static str> {
+    if token.is_empty() {
+        return Err("empty token");
+    }
+    let body = match token.rsplit_once('@') {
+        Some((body, percent)) => {
+            percent
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= 100)
+                .ok_or("weight after '@' must be a whole percent from 0 to 100")?;
+            body
+        }
+        None => token,
     };
 
-    Card::new(rank, suit)
+    if let Some((high, low)) = body.split_once('-') {
+        let ends = parse_class(high).zip(parse_class(low));
+        return match ends {
+            // Pairs run from high to low; other classes keep their first
+            // rank and may list the kickers either way round
+            Some((h, l)) if h.0 == h.1 && l.0 == l.1 && h.0 >= l.0 => Ok(()),
+            Some((h, l)) if h.0 != h.1 && l.0 != l.1 && h.0 == l.0 && h.2 == l.2 => Ok(()),
+            _ => Err("expected a dash range like JJ-99 or A9s-A6s"),
+        };
+    }
+    let class = body.strip_suffix('+').unwrap_or(body);
+    if parse_class(class).is_some()
+        || (class == body && body.is_ascii() && body.parse::<Hand>().is_ok())
+    {
+        return Ok(());
+    }
+    Err("expected a hand class like AKs or QQ+, or a combo like AhKd")
+}
+
+impl<'a> IntoIterator for &'a Range {
+    type Item = (&'a Hand, f64);
+    type IntoIter = std::iter::Map<
+        std::collections::btree_map::Values<'a, u64, (Hand, f64)>,
+        fn(&'a (Hand, f64)) -> (&'a Hand, f64),
+    >;
+
+    /// Iterate `(combo, weight)` pairs, as `Range::hands` does
+    fn into_iter(self) -> Self::IntoIter {
+        self.hands.values().map(|(hand, weight)| (hand, *weight))
+    }
+}
+
+/// Serialize range hands as `"AhKd": weight` so the map has string keys
+mod combo_keys {
+    use crate::models::hand::Hand;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(
+        hands: &BTreeMap<u64, (Hand, f64)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        // Sorted for stable output
+        let by_combo: BTreeMap<String, f64> = hands
+            .values()
+            .map(|(hand, weight)| (hand.combo_notation(), *weight))
+            .collect();
+        by_combo.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<u64, (Hand, f64)>, D::Error> {
+        let by_combo = BTreeMap::<String, f64>::deserialize(deserializer)?;
+        by_combo
+            .into_iter()
+            .map(|(combo, weight)| {
+                Hand::from_str(&combo)
+                    .map(|hand| (hand.key(), (hand.canonical(), weight)))
+                    .map_err(D::Error::custom)
+            })
+            .collect()
+    }
 }
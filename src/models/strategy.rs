@@ -1,8 +1,14 @@
 //! Strategy output representation
 
-use crate::models::action::Action;
+use crate::error::{ModelError, Result};
+use crate::models::action::{Action, BetSize, StreetBetSizes};
 use crate::models::game_state::GameState;
+use crate::models::hand::Hand;
+use crate::JSON_SCHEMA_VERSION;
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
 
 /// Strategy for a single action
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,10 +19,22 @@ pub struct ActionStrategy {
     /// Frequency to take this action (0.0-1.0)
     pub frequency: f64,
 
-    /// Expected value in big blinds
+    /// Expected value, in the strategy's `ev_unit`
     pub ev: f64,
 }
 
+/// Unit the EVs of a strategy are expressed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvUnit {
+    /// Big blinds, net of what hero had put in before the decision
+    #[default]
+    #[serde(rename = "BB")]
+    BigBlinds,
+    /// Change in prize equity of an ICM solve, in the units of its payouts
+    #[serde(rename = "payout")]
+    Payout,
+}
+
 /// GTO strategy output for a decision point
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Strategy {
@@ -32,38 +50,426 @@ pub struct Strategy {
 
     /// Convergence metric (Nash distance approximation)
     pub convergence: f64,
+
+    /// JSON shape version, see `JSON_SCHEMA_VERSION` (0 if absent on input)
+    #[serde(default)]
+    pub schema_version: u32,
+
+    /// How the strategy was produced, recorded for seeded solves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+
+    /// (iteration, convergence) at each convergence check, if the solve
+    /// recorded them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub convergence_history: Vec<(u32, f64)>,
+
+    /// Unit of `root_ev` and of every action's `ev`
+    #[serde(default)]
+    pub ev_unit: EvUnit,
+
+    /// Value of the decision for hero: action EVs weighted by frequency
+    #[serde(default)]
+    pub root_ev: f64,
+
+    /// How much of villain's range the solve trained against, if it was
+    /// cut down to `MccfrConfig::max_villain_combos`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub villain_subsample: Option<Subsample>,
+}
+
+/// Combos kept when a range was subsampled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subsample {
+    /// Combos the solve used
+    pub kept: usize,
+
+    /// Combos in the full range
+    pub total: usize,
+}
+
+/// Everything needed to reproduce a seeded solve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// RNG seed
+    pub seed: u64,
+
+    /// Iterations actually run, fewer than requested if stopped early
+    pub iterations: u32,
+
+    /// Solver variant (e.g. "mccfr")
+    pub solver: String,
+
+    /// Hero's bet sizes, if not the default abstraction
+    pub hero_bet_sizes: Option<Vec<BetSize>>,
+
+    /// Villain's bet sizes, if not the default abstraction
+    pub villain_bet_sizes: Option<Vec<BetSize>>,
+
+    /// Per-street sizes for players without their own, if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_bet_sizes: Option<StreetBetSizes>,
+
+    /// Version of this crate
+    pub version: String,
 }
 
 impl Strategy {
+    /// Frequency slack under which a strategy still counts as pure
+    pub const PURE_TOLERANCE: f64 = 0.05;
+
     /// Create a new strategy
     pub fn new(actions: Vec<ActionStrategy>, iterations: u32, convergence: f64) -> Self {
+        let root_ev = weighted_ev(&actions);
         Self {
             game_state: None,
             actions,
             iterations,
             convergence,
+            schema_version: JSON_SCHEMA_VERSION,
+            provenance: None,
+            convergence_history: Vec::new(),
+            ev_unit: EvUnit::default(),
+            root_ev,
+            villain_subsample: None,
         }
     }
 
-    /// Returns true if frequencies sum to 1.0 (within tolerance)
+    /// Load a strategy from its JSON form.
+    ///
+    /// Unlike plain deserialization, rejects a strategy without actions,
+    /// which no solve produces.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let strategy: Strategy =
+            serde_json::from_str(json).map_err(|e| ModelError::InvalidStrategy(e.to_string()))?;
+        if strategy.actions.is_empty() {
+            return Err(ModelError::InvalidStrategy("no actions".to_string()));
+        }
+        Ok(strategy)
+    }
+
+    /// Default tolerance of `is_valid`
+    pub const VALID_TOLERANCE: f64 = 0.001;
+
+    /// Returns true if frequencies sum to 1.0 (within `VALID_TOLERANCE`)
     pub fn is_valid(&self) -> bool {
+        self.is_valid_with_tol(Self::VALID_TOLERANCE)
+    }
+
+    /// Returns true if frequencies sum to within `tol` of 1.0
+    pub fn is_valid_with_tol(&self, tol: f64) -> bool {
+        let sum: f64 = self.actions.iter().map(|a| a.frequency).sum();
+        (sum - 1.0).abs() < tol
+    }
+
+    /// Scale frequencies so they sum to exactly 1.0, e.g. after dropping
+    /// actions. Does nothing if they sum to zero.
+    pub fn renormalize(&mut self) {
         let sum: f64 = self.actions.iter().map(|a| a.frequency).sum();
-        (sum - 1.0).abs() < 0.001
+        if sum > 0.0 {
+            for action in &mut self.actions {
+                action.frequency /= sum;
+            }
+            self.root_ev = weighted_ev(&self.actions);
+        }
+    }
+
+    /// Number of actions
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns true if the strategy has no actions
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Action at `index` in `actions`, `None` if out of bounds
+    pub fn action_at(&self, index: usize) -> Option<&ActionStrategy> {
+        self.actions.get(index)
     }
 
-    /// Returns action with highest EV
+    /// Returns action with highest EV.
+    ///
+    /// NaN EVs rank last; on ties the earliest action in `actions` wins.
     pub fn best_action(&self) -> Option<&ActionStrategy> {
         self.actions
             .iter()
-            .max_by(|a, b| a.ev.partial_cmp(&b.ev).unwrap())
+            .min_by(|a, b| descending_nan_last(a.ev, b.ev))
     }
 
-    /// Returns actions sorted by frequency (highest first)
+    /// EV slack within which actions count as equally good for
+    /// `recommended_action`
+    pub const RECOMMEND_EV_TOLERANCE: f64 = 0.05;
+
+    /// Single action to play when not mixing, e.g. for beginners.
+    ///
+    /// Among the actions within `RECOMMEND_EV_TOLERANCE` of the best EV, the
+    /// most frequent wins, then the first in canonical order. NaN EVs are
+    /// never within the tolerance unless every EV is NaN, in which case all
+    /// actions are candidates.
+    pub fn recommended_action(&self) -> Option<&ActionStrategy> {
+        let best = self.best_action()?.ev;
+        self.actions
+            .iter()
+            .filter(|a| best.is_nan() || a.ev >= best - Self::RECOMMEND_EV_TOLERANCE)
+            .min_by(|a, b| {
+                descending_nan_last(a.frequency, b.frequency)
+                    .then_with(|| a.action.sort_key().cmp(&b.action.sort_key()))
+            })
+    }
+
+    /// Returns actions sorted by frequency (highest first).
+    ///
+    /// NaN frequencies sort last; equal frequencies keep their order in
+    /// `actions`, so output is stable across runs.
     pub fn sorted_by_frequency(&self) -> Vec<&ActionStrategy> {
         let mut sorted: Vec<&ActionStrategy> = self.actions.iter().collect();
-        sorted.sort_by(|a, b| b.frequency.partial_cmp(&a.frequency).unwrap());
+        sorted.sort_by(|a, b| descending_nan_last(a.frequency, b.frequency));
+        sorted
+    }
+
+    /// Returns actions in canonical order (see `Action::sort_key`), so
+    /// tables read the same whatever order the actions were listed in.
+    pub fn sorted_canonically(&self) -> Vec<&ActionStrategy> {
+        let mut sorted: Vec<&ActionStrategy> = self.actions.iter().collect();
+        sorted.sort_by_key(|a| a.action.sort_key());
         sorted
     }
+
+    /// Returns action with highest frequency, with the same tie-breaking as
+    /// `best_action`
+    pub fn dominant_action(&self) -> Option<&ActionStrategy> {
+        self.actions
+            .iter()
+            .min_by(|a, b| descending_nan_last(a.frequency, b.frequency))
+    }
+
+    /// Returns true if one action is taken at least `1.0 - tol` of the time
+    pub fn is_pure(&self, tol: f64) -> bool {
+        self.dominant_action()
+            .is_some_and(|a| a.frequency >= 1.0 - tol)
+    }
+
+    /// Returns true if no action is pure within `Strategy::PURE_TOLERANCE`
+    pub fn is_mixed(&self) -> bool {
+        !self.actions.is_empty() && !self.is_pure(Self::PURE_TOLERANCE)
+    }
+
+    /// Frequency at or above which an action counts as played in
+    /// `indifference_spread`; rarer ones are averaging residue
+    pub const INDIFFERENCE_MIN_FREQUENCY: f64 = 0.05;
+
+    /// Gap between the highest and lowest EV among the actions played at
+    /// least `INDIFFERENCE_MIN_FREQUENCY` of the time, ignoring NaN EVs.
+    ///
+    /// At equilibrium a player only mixes between actions worth the same,
+    /// so a large spread at a mixed node means the solve hasn't converged.
+    /// 0.0 unless at least two actions are played.
+    pub fn indifference_spread(&self) -> f64 {
+        let evs: Vec<f64> = self
+            .actions
+            .iter()
+            .filter(|a| a.frequency >= Self::INDIFFERENCE_MIN_FREQUENCY && !a.ev.is_nan())
+            .map(|a| a.ev)
+            .collect();
+        if evs.len() < 2 {
+            return 0.0;
+        }
+        let max = evs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let min = evs.iter().cloned().fold(f64::INFINITY, f64::min);
+        max - min
+    }
+}
+
+impl fmt::Display for EvUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvUnit::BigBlinds => write!(f, "BB"),
+            EvUnit::Payout => write!(f, "payout"),
+        }
+    }
+}
+
+/// One-line summary for logs, most frequent action first, e.g.
+/// "Bet 75% pot: 62% | Check: 38% | EV +1.10 BB | conv 0.002 | 5000 it".
+/// `cli::output` renders the full table.
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.actions.is_empty() {
+            write!(f, "(no actions)")?;
+        }
+        for (i, a) in self.sorted_by_frequency().iter().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+            write!(
+                f,
+                "{}: {:.0}%",
+                a.action.display_name(),
+                a.frequency * 100.0
+            )?;
+        }
+        write!(
+            f,
+            " | EV {:+.2} {} | conv {:.3} | {} it",
+            self.root_ev, self.ev_unit, self.convergence, self.iterations
+        )
+    }
+}
+
+/// Action EVs weighted by frequency
+pub(crate) fn weighted_ev(actions: &[ActionStrategy]) -> f64 {
+    actions.iter().map(|a| a.frequency * a.ev).sum()
+}
+
+/// Total order putting larger values first and NaN after every number
+pub(crate) fn descending_nan_last(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+/// Strategy of one hero combo within a range solve
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComboStrategy {
+    /// Hero's hole cards
+    pub hand: Hand,
+
+    /// Weight of the combo in hero's range (0.0-1.0)
+    pub weight: f64,
+
+    /// Solved strategy for this combo
+    pub strategy: Strategy,
+}
+
+/// Per-combo strategies for a hero range solved against a villain range
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RangeStrategy {
+    /// One entry per unblocked hero combo
+    pub combos: Vec<ComboStrategy>,
+}
+
+impl RangeStrategy {
+    /// Split combos into groups keyed by `key`, in key order
+    pub fn group_by<K, F>(&self, key: F) -> BTreeMap<K, RangeStrategy>
+    where
+        K: Ord,
+        F: Fn(&Hand) -> K,
+    {
+        let mut groups: BTreeMap<K, RangeStrategy> = BTreeMap::new();
+        for combo in &self.combos {
+            groups
+                .entry(key(&combo.hand))
+                .or_default()
+                .combos
+                .push(combo.clone());
+        }
+        groups
+    }
+
+    /// Combine this strategy with others solved for disjoint parts of the
+    /// same hero range, e.g. chunks solved separately.
+    ///
+    /// Combos keep their order, this strategy's first. `aggregate` on the
+    /// result weighs every chunk's combos together. Fails if a combo appears
+    /// more than once.
+    pub fn merge(&self, others: &[RangeStrategy]) -> Result<RangeStrategy> {
+        let mut seen = HashSet::new();
+        let mut merged = RangeStrategy::default();
+        for combo in std::iter::once(self)
+            .chain(others)
+            .flat_map(|part| &part.combos)
+        {
+            if !seen.insert(&combo.hand) {
+                return Err(ModelError::OverlappingCombo(combo.hand.combo_notation()));
+            }
+            merged.combos.push(combo.clone());
+        }
+        Ok(merged)
+    }
+
+    /// Frequencies by combo in the shape range viewers read:
+    ///
+    /// ```json
+    /// {
+    ///   "combos": { "AhKh": { "Check": 0.25, "Bet 50% pot": 0.75 } },
+    ///   "aggregate": { "Check": 0.25, "Bet 50% pot": 0.75 }
+    /// }
+    /// ```
+    ///
+    /// Combos are keyed by their cards (see `Hand::combo_notation`) and
+    /// actions by `Action::display_name`; `aggregate` is `aggregate()`'s
+    /// frequencies. EVs and weights are left out.
+    pub fn to_json(&self) -> String {
+        let frequencies = |actions: &[ActionStrategy]| -> BTreeMap<String, f64> {
+            actions
+                .iter()
+                .map(|a| (a.action.display_name(), a.frequency))
+                .collect()
+        };
+        let combos: BTreeMap<String, BTreeMap<String, f64>> = self
+            .combos
+            .iter()
+            .map(|combo| {
+                (
+                    combo.hand.combo_notation(),
+                    frequencies(&combo.strategy.actions),
+                )
+            })
+            .collect();
+        let json = serde_json::json!({
+            "combos": combos,
+            "aggregate": frequencies(&self.aggregate()),
+        });
+        serde_json::to_string_pretty(&json).expect("maps with string keys serialize")
+    }
+
+    /// Action frequencies and EVs averaged over combos, weighted by range weight
+    pub fn aggregate(&self) -> Vec<ActionStrategy> {
+        let Some(first) = self.combos.first() else {
+            return Vec::new();
+        };
+
+        let mut totals: Vec<ActionStrategy> = first
+            .strategy
+            .actions
+            .iter()
+            .map(|a| ActionStrategy {
+                action: a.action.clone(),
+                frequency: 0.0,
+                ev: 0.0,
+            })
+            .collect();
+        let mut total_weight = 0.0;
+
+        for combo in &self.combos {
+            for (total, action) in totals.iter_mut().zip(&combo.strategy.actions) {
+                total.frequency += combo.weight * action.frequency;
+                total.ev += combo.weight * action.ev;
+            }
+            total_weight += combo.weight;
+        }
+
+        if total_weight > 0.0 {
+            for total in &mut totals {
+                total.frequency /= total_weight;
+                total.ev /= total_weight;
+            }
+        }
+        totals
+    }
+
+    /// Convergence of the least converged combo's solve, 0 with no combos
+    pub fn convergence(&self) -> f64 {
+        self.combos
+            .iter()
+            .map(|combo| combo.strategy.convergence)
+            .fold(0.0, f64::max)
+    }
 }
 
 #[cfg(test)]
@@ -110,6 +516,54 @@ mod tests {
         assert_eq!(best.ev, 2.5);
     }
 
+    #[test]
+    fn test_recommended_action() {
+        let actions = vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.7,
+                ev: 1.49,
+            },
+            ActionStrategy {
+                action: Action::Bet(BetSize::PotFraction(0.5)),
+                frequency: 0.3,
+                ev: 1.5,
+            },
+            ActionStrategy {
+                action: Action::Fold,
+                frequency: 0.0,
+                ev: f64::NAN,
+            },
+        ];
+        let strategy = Strategy::new(actions, 10000, 0.001);
+        assert!(matches!(
+            strategy.best_action().unwrap().action,
+            Action::Bet(_)
+        ));
+        // Near-equal EVs go to the action played more often
+        assert_eq!(strategy.recommended_action().unwrap().action, Action::Check);
+
+        // Equal frequencies fall back to canonical order
+        let tied = Strategy::new(
+            vec![
+                ActionStrategy {
+                    action: Action::Call,
+                    frequency: 0.5,
+                    ev: 1.0,
+                },
+                ActionStrategy {
+                    action: Action::Fold,
+                    frequency: 0.5,
+                    ev: 1.0,
+                },
+            ],
+            10000,
+            0.001,
+        );
+        assert_eq!(tied.recommended_action().unwrap().action, Action::Fold);
+        assert!(Strategy::new(vec![], 0, 0.0).recommended_action().is_none());
+    }
+
     #[test]
     fn test_sorted_by_frequency() {
         let actions = vec![
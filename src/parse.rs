@@ -0,0 +1,116 @@
+//! Parsers for the inputs that describe a spot
+//!
+//! One entry point per input, each returning a `ModelError::InvalidInput`
+//! naming the input and what was wrong with it, so library users and the
+//! CLI report bad input the same way.
+
+use crate::error::{ModelError, Result};
+use crate::models::{BetSize, Board, Card, Hand, Position, Range};
+use std::str::FromStr;
+
+/// `ModelError::InvalidInput` for `field`
+fn invalid(field: &'static str, input: &str, reason: impl ToString) -> ModelError {
+    ModelError::InvalidInput {
+        field,
+        input: input.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Hole cards such as "AhKd"
+pub fn parse_hand(input: &str) -> Result<Hand> {
+    let cards = parse_cards(input, "hand")?;
+    match cards[..] {
+        [first, second] if first == second => Err(invalid(
+            "hand",
+            input,
+            format!("{} appears twice", first.notation()),
+        )),
+        [first, second] => Ok(Hand::new(first, second)),
+        _ => Err(invalid(
+            "hand",
+            input,
+            format!("expected 2 cards (e.g. 'AhKd'), got {}", cards.len()),
+        )),
+    }
+}
+
+/// Community cards such as "Ts9s2h", optionally separated by spaces or
+/// commas; empty for preflop
+pub fn parse_board(input: &str) -> Result<Board> {
+    let cards = parse_cards(input, "board")?;
+    let count = cards.len();
+    Board::new(cards).map_err(|e| match e {
+        ModelError::InvalidBoard { .. } => invalid(
+            "board",
+            input,
+            format!("expected 0, 3, 4 or 5 cards, got {}", count),
+        ),
+        ModelError::DuplicateCard(card) => {
+            invalid("board", input, format!("{} appears twice", card))
+        }
+        other => other,
+    })
+}
+
+/// A range in Equilab notation such as "QQ+,AKs,AQo:0.5" (see
+/// `Range::from_notation`)
+pub fn parse_range(input: &str) -> Result<Range> {
+    if input.trim().is_empty() {
+        return Err(invalid("range", input, "empty range"));
+    }
+    Range::from_notation(input).map_err(|e| match e {
+        ModelError::InvalidRange(_, reason) => invalid("range", input, reason),
+        other => other,
+    })
+}
+
+/// Comma-separated bet sizes such as "0.33pot,0.75pot,pot,allin" (see
+/// `BetSize::from_str`)
+pub fn parse_bet_sizes(input: &str) -> Result<Vec<BetSize>> {
+    if input.trim().is_empty() {
+        return Err(invalid("bet sizes", input, "no sizes given"));
+    }
+    input
+        .split(',')
+        .map(|token| {
+            BetSize::from_str(token).map_err(|_| {
+                invalid(
+                    "bet sizes",
+                    input,
+                    format!(
+                        "'{}' is not a size like '0.5pot', 'pot', '10bb', '3x' or 'allin'",
+                        token.trim()
+                    ),
+                )
+            })
+        })
+        .collect()
+}
+
+/// "IP" or "OOP", in any case
+pub fn parse_position(input: &str) -> Result<Position> {
+    Position::from_str(input.trim()).map_err(|_| invalid("position", input, "expected IP or OOP"))
+}
+
+/// Two-character cards, run together or separated by spaces or commas
+fn parse_cards(input: &str, field: &'static str) -> Result<Vec<Card>> {
+    let compact: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+    if !compact.is_ascii() || !compact.len().is_multiple_of(2) {
+        return Err(invalid(
+            field,
+            input,
+            "cards are two characters each, like 'Ah' or 'Td'",
+        ));
+    }
+    (0..compact.len())
+        .step_by(2)
+        .map(|i| {
+            let card = &compact[i..i + 2];
+            Card::from_str(card).map_err(|e| invalid(field, input, e))
+        })
+        .collect()
+}
@@ -0,0 +1,188 @@
+//! Best response against a fixed opponent policy
+//!
+//! Walks the same betting tree as the MCCFR solver, but instead of regret
+//! matching hero takes the highest-EV action at every node. Villain plays
+//! the given `StrategyTree`, which does not depend on villain's cards, so
+//! hero's belief about villain's hand never changes along the way and
+//! showdowns can use hero's equity against the whole range.
+
+use crate::error::ModelError;
+use crate::models::{
+    game_state::GameState,
+    strategy::{descending_nan_last, weighted_ev, ActionStrategy, EvUnit, Strategy},
+};
+use crate::solver::{
+    equity::Equity,
+    eval_cache::SharedEvalCache,
+    mccfr::{apply_action, node_actions, showdown_equity, terminal_utility, Traversal},
+};
+use crate::JSON_SCHEMA_VERSION;
+
+/// A fixed policy over the betting tree.
+///
+/// Each node mirrors a decision point: `frequencies` apply to the actions
+/// available there (in solver order) when villain is to act, and `children`
+/// holds the subtree after each of those actions. Missing or mismatched
+/// frequencies mean villain plays uniformly at that node.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyTree {
+    /// Villain's action frequencies at this node
+    pub frequencies: Vec<f64>,
+    /// Subtree after each action, in action order
+    pub children: Vec<StrategyTree>,
+}
+
+impl StrategyTree {
+    /// Create a node with the given frequencies and subtrees
+    pub fn new(frequencies: Vec<f64>, children: Vec<StrategyTree>) -> Self {
+        Self {
+            frequencies,
+            children,
+        }
+    }
+
+    /// Subtree after the action at `index`, if specified
+    pub fn child(&self, index: usize) -> Option<&StrategyTree> {
+        self.children.get(index)
+    }
+
+    /// Frequencies for a node with `num_actions` actions
+    fn frequencies_for(&self, num_actions: usize) -> Vec<f64> {
+        let total: f64 = self.frequencies.iter().sum();
+        if self.frequencies.len() == num_actions && total > 0.0 {
+            self.frequencies.iter().map(|f| f / total).collect()
+        } else {
+            vec![1.0 / num_actions as f64; num_actions]
+        }
+    }
+}
+
+/// Hero's maximally exploitative strategy against `villain_policy`.
+///
+/// The returned strategy is pure at the root: the best action has frequency
+/// 1.0 (the earliest action on EV ties) and every action carries its EV in
+/// net chips against the villain range.
+///
+/// Fails with `EmptyRange` if every villain combo is blocked by hero, the
+/// board or the dead cards.
+pub fn best_response(state: &GameState, villain_policy: &StrategyTree) -> crate::Result<Strategy> {
+    let equity = range_equity(state).ok_or(ModelError::EmptyRange)?;
+
+    let mut root = state.clone();
+    let root_actions = node_actions(&root, None, true);
+    root.available_actions = Some(root_actions.clone());
+
+    // Only `equity` is read at showdown, so hero's hand stands in for villain's
+    let t = Traversal::new(
+        state.position,
+        state.position,
+        &state.hero_hand,
+        &state.hero_hand,
+        equity,
+    );
+    let (_, action_values) = node_value(&root, Some(villain_policy), &t, 0.0);
+
+    let best = action_values
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| descending_nan_last(**a, **b))
+        .map(|(i, _)| i);
+    let actions: Vec<ActionStrategy> = root_actions
+        .iter()
+        .zip(action_values)
+        .enumerate()
+        .map(|(i, (action, ev))| ActionStrategy {
+            action: action.clone(),
+            frequency: if Some(i) == best { 1.0 } else { 0.0 },
+            ev,
+        })
+        .collect();
+
+    Ok(Strategy {
+        game_state: Some(root),
+        iterations: 0,
+        convergence: 0.0,
+        schema_version: JSON_SCHEMA_VERSION,
+        provenance: None,
+        convergence_history: Vec::new(),
+        ev_unit: EvUnit::BigBlinds,
+        root_ev: weighted_ev(&actions),
+        actions,
+        villain_subsample: None,
+    })
+}
+
+/// Value of `state` for hero, and the value of each action at it
+fn node_value(
+    state: &GameState,
+    policy: Option<&StrategyTree>,
+    t: &Traversal,
+    invested: f64,
+) -> (f64, Vec<f64>) {
+    let actions = node_actions(state, None, false);
+    let is_hero = state.position == t.hero_position;
+
+    let action_values: Vec<f64> = actions
+        .iter()
+        .enumerate()
+        .map(|(i, action)| {
+            let (next, is_terminal) = apply_action(state, action);
+            let next_invested = if is_hero {
+                invested + (next.pot_size - state.pot_size)
+            } else {
+                invested
+            };
+            if is_terminal {
+                terminal_utility(state, action, &next, t, next_invested)
+            } else {
+                let child = policy.and_then(|p| p.child(i));
+                node_value(&next, child, t, next_invested).0
+            }
+        })
+        .collect();
+
+    let value = if is_hero {
+        action_values
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max)
+    } else {
+        let frequencies = policy
+            .map(|p| p.frequencies_for(actions.len()))
+            .unwrap_or_else(|| vec![1.0 / actions.len() as f64; actions.len()]);
+        frequencies
+            .iter()
+            .zip(&action_values)
+            .map(|(f, v)| f * v)
+            .sum()
+    };
+    (value, action_values)
+}
+
+/// Hero's weighted showdown equity against the unblocked villain range
+fn range_equity(state: &GameState) -> Option<Equity> {
+    let eval_cache = SharedEvalCache::new();
+    let mut dead = state.board.to_vec();
+    dead.extend_from_slice(&state.hero_hand.cards);
+    dead.extend_from_slice(&state.dead_cards);
+
+    let (mut win, mut tie, mut lose, mut total) = (0.0, 0.0, 0.0, 0.0);
+    for (villain_hand, weight) in state.villain_range.weighted_iter() {
+        if weight <= 0.0 || villain_hand.cards.iter().any(|c| dead.contains(c)) {
+            continue;
+        }
+        let equity = showdown_equity(
+            &state.hero_hand,
+            villain_hand,
+            &state.board,
+            &state.dead_cards,
+            &eval_cache,
+        );
+        win += weight * equity.win;
+        tie += weight * equity.tie;
+        lose += weight * equity.lose;
+        total += weight;
+    }
+
+    (total > 0.0).then(|| Equity::new(win / total, tie / total, lose / total))
+}
@@ -0,0 +1,85 @@
+//! Blocker effects of hero's hole cards
+//!
+//! Holding a card removes every villain combo that uses it. `blocker_report`
+//! shows which of those combos were value hands and which were bluffs, e.g.
+//! why holding the ace of the flush suit matters on a three-flush board.
+
+use crate::models::{card::Card, hand::Hand, range::Range};
+use crate::solver::evaluator::{hand_category, HandCategory};
+
+/// Villain value and bluff combos with and without hero's blockers.
+///
+/// Value hands are top pair or better; bluffs are draws and air. Weaker
+/// pairs count as neither. Combo counts are weighted by range weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockerReport {
+    /// Value combos on the board before removing hero's cards
+    pub value_combos: f64,
+    /// Bluff combos on the board before removing hero's cards
+    pub bluff_combos: f64,
+    /// Value combos hero's cards remove, strongest first
+    pub blocked_value: Vec<(Hand, HandCategory)>,
+    /// Bluff combos hero's cards remove, strongest first
+    pub blocked_bluffs: Vec<(Hand, HandCategory)>,
+    /// Weighted count of `blocked_value`
+    pub blocked_value_combos: f64,
+    /// Weighted count of `blocked_bluffs`
+    pub blocked_bluff_combos: f64,
+}
+
+impl BlockerReport {
+    /// Value combos left once hero's cards are removed
+    pub fn value_combos_after(&self) -> f64 {
+        self.value_combos - self.blocked_value_combos
+    }
+
+    /// Bluff combos left once hero's cards are removed
+    pub fn bluff_combos_after(&self) -> f64 {
+        self.bluff_combos - self.blocked_bluff_combos
+    }
+}
+
+impl Range {
+    /// How `hero`'s cards change this (villain) range's value and bluff
+    /// combos on `board`.
+    ///
+    /// Combos using a board card are impossible either way and ignored.
+    pub fn blocker_report(&self, hero: &Hand, board: &[Card]) -> BlockerReport {
+        let mut report = BlockerReport {
+            value_combos: 0.0,
+            bluff_combos: 0.0,
+            blocked_value: Vec::new(),
+            blocked_bluffs: Vec::new(),
+            blocked_value_combos: 0.0,
+            blocked_bluff_combos: 0.0,
+        };
+
+        let mut combos: Vec<(&Hand, f64)> = self
+            .hands()
+            .filter(|(hand, weight)| *weight > 0.0 && !hand.cards.iter().any(|c| board.contains(c)))
+            .collect();
+        combos.sort_by_key(|(hand, _)| hand.cards.map(|c| c.to_pokers_index()));
+
+        for (hand, weight) in combos {
+            let category = hand_category(hand, board);
+            let blocked = hand.cards.iter().any(|c| hero.contains_card(c));
+            if category <= HandCategory::TopPair {
+                report.value_combos += weight;
+                if blocked {
+                    report.blocked_value.push((hand.clone(), category));
+                    report.blocked_value_combos += weight;
+                }
+            } else if category >= HandCategory::Draw {
+                report.bluff_combos += weight;
+                if blocked {
+                    report.blocked_bluffs.push((hand.clone(), category));
+                    report.blocked_bluff_combos += weight;
+                }
+            }
+        }
+
+        report.blocked_value.sort_by_key(|(_, category)| *category);
+        report.blocked_bluffs.sort_by_key(|(_, category)| *category);
+        report
+    }
+}
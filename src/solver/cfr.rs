@@ -3,9 +3,21 @@
 use crate::error::Result;
 use crate::models::action::{Action, BetSize};
 use crate::models::game_state::GameState;
-use crate::models::strategy::Strategy;
+use crate::models::strategy::{ActionStrategy, Strategy};
 
-use crate::solver::mccfr::solve_mccfr;
+use crate::solver::eval_cache::SharedEvalCache;
+use crate::solver::mccfr::{node_actions, solve_profiled, MccfrConfig, SolveProfile};
+
+/// Algorithm used to produce a strategy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SolverKind {
+    /// Equal frequencies over the available actions, without solving.
+    /// A fast preview for wiring up output; not a GTO strategy.
+    Uniform,
+    /// Monte Carlo CFR (the real solver)
+    #[default]
+    Mccfr,
+}
 
 /// GTO Solver engine
 pub struct Solver {
@@ -24,20 +36,68 @@ impl Solver {
 
     /// Execute the solver and return the strategy
     pub fn solve(&self) -> Result<Strategy> {
-        // 1. Determine available actions (if not already set in game_state)
-        let mut state = self.game_state.clone();
-        if state.available_actions.is_empty() {
-            state.available_actions = determine_available_actions(&state);
-        }
+        let config = MccfrConfig {
+            iterations: self.iterations,
+            ..Default::default()
+        };
+        solve_with_kind(self.game_state.clone(), SolverKind::Mccfr, config)
+    }
+}
 
-        // 2. Run MCCFR
-        let strategy = solve_mccfr(&state, self.iterations);
+/// Solve `game_state` with the chosen algorithm.
+///
+/// Available actions default to `determine_available_actions_with` the
+/// configured hero bet sizes when the state leaves them unset, and a state whose
+/// actions are all illegal is rejected (see `GameState::validate_actions`).
+/// `config` only applies to `SolverKind::Mccfr`; a uniform strategy runs no
+/// iterations and reports 0 EV for every action.
+pub fn solve_with_kind(
+    game_state: GameState,
+    kind: SolverKind,
+    config: MccfrConfig,
+) -> Result<Strategy> {
+    solve_with_kind_profiled(game_state, kind, config).map(|(strategy, _)| strategy)
+}
+
+/// `solve_with_kind`, also returning a timing breakdown when an MCCFR solve
+/// has `config.profile` set
+pub fn solve_with_kind_profiled(
+    game_state: GameState,
+    kind: SolverKind,
+    config: MccfrConfig,
+) -> Result<(Strategy, Option<SolveProfile>)> {
+    game_state.validate_actions()?;
+    let mut state = game_state;
+    if state.available_actions.is_none() {
+        state.available_actions = Some(determine_available_actions_with(
+            &state,
+            config.hero_sizes_on(state.street),
+        ));
+    }
 
-        Ok(strategy)
+    match kind {
+        SolverKind::Uniform => {
+            let available = state.available_actions.as_deref().unwrap_or_default();
+            let frequency = 1.0 / available.len() as f64;
+            let actions = available
+                .iter()
+                .map(|action| ActionStrategy {
+                    action: action.clone(),
+                    frequency,
+                    ev: 0.0,
+                })
+                .collect();
+            let mut strategy = Strategy::new(actions, 0, 0.0);
+            strategy.game_state = Some(state);
+            strategy.provenance = config.provenance("uniform", 0);
+            Ok((strategy, None))
+        }
+        SolverKind::Mccfr => solve_profiled(state, config, &SharedEvalCache::new()),
     }
 }
 
 /// Determine valid actions for the current game state
+#[must_use]
 pub fn determine_available_actions(state: &GameState) -> Vec<Action> {
     let mut actions = Vec::new();
 
@@ -62,7 +122,34 @@ pub fn determine_available_actions(state: &GameState) -> Vec<Action> {
         }
     }
 
-    actions
+    // Sizes that would put the whole stack in collapse into a single all-in
+    let mut normalized: Vec<Action> = Vec::with_capacity(actions.len());
+    for action in actions {
+        let action = action.normalize(state.pot_size, state.effective_stack, state.to_call);
+        if !normalized.contains(&action) {
+            normalized.push(action);
+        }
+    }
+
+    normalized
+}
+
+/// Actions at the root of a solve, as the solver offers them at every
+/// node: one bet or raise per size in `bet_sizes` (plus check, or fold and
+/// call), and `determine_available_actions` facing a bet without sizes
+#[must_use]
+pub fn determine_available_actions_with(
+    state: &GameState,
+    bet_sizes: Option<&[BetSize]>,
+) -> Vec<Action> {
+    match bet_sizes {
+        Some(sizes) => {
+            let mut open = state.clone();
+            open.available_actions = None;
+            node_actions(&open, Some(sizes), true)
+        }
+        None => determine_available_actions(state),
+    }
 }
 
 /// Helper to run solver in one step
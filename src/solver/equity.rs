@@ -1,10 +1,13 @@
 //! Equity calculation
 
-use crate::models::card::Card;
+use crate::models::card::{Card, Rank, Suit};
 use crate::models::hand::Hand;
 use crate::models::range::Range;
 use crate::solver::evaluator::evaluate_hand;
+use crate::JSON_SCHEMA_VERSION;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 /// Equity calculation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,66 +18,317 @@ pub struct Equity {
     pub tie: f64,
     /// Probability of losing (0.0-1.0)
     pub lose: f64,
+    /// JSON shape version, see `JSON_SCHEMA_VERSION` (0 if absent on input)
+    #[serde(default)]
+    pub schema_version: u32,
 }
 
 impl Equity {
     /// Create new equity result
     pub fn new(win: f64, tie: f64, lose: f64) -> Self {
-        Self { win, tie, lose }
+        Self {
+            win,
+            tie,
+            lose,
+            schema_version: JSON_SCHEMA_VERSION,
+        }
+    }
+
+    /// Share of the pot won on average: wins plus half of ties
+    pub fn equity(&self) -> f64 {
+        self.win + self.tie / 2.0
+    }
+
+    /// Returns true if win, tie and lose sum to 1.0 (within tolerance)
+    pub fn is_valid(&self) -> bool {
+        (self.win + self.tie + self.lose - 1.0).abs() < 0.001
     }
 }
 
+impl fmt::Display for Equity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Win {:.1}% / Tie {:.1}% / Lose {:.1}%",
+            self.win * 100.0,
+            self.tie * 100.0,
+            self.lose * 100.0
+        )
+    }
+}
+
+/// Villain ranges with at least this many combos are scored in parallel
+pub const PARALLEL_MIN_COMBOS: usize = 64;
+
 /// Calculate equity of hero hand vs villain range on board
+#[must_use]
 pub fn calculate_equity(hero_hand: &Hand, villain_range: &Range, board: &[Card]) -> Equity {
-    let mut wins = 0.0;
-    let mut ties = 0.0;
-    let mut losses = 0.0;
-    let mut total_weight = 0.0;
-
     let hero_score = evaluate_hand(hero_hand, board);
 
-    for (villain_hand, weight) in villain_range.hands() {
+    tally_range(villain_range, |villain_hand| {
         if shares_cards(hero_hand, villain_hand) || shares_board(villain_hand, board) {
-            continue;
+            return Tally::default();
         }
+        Tally::showdown(hero_score, evaluate_hand(villain_hand, board))
+    })
+}
 
-        let villain_score = evaluate_hand(villain_hand, board);
+/// Calculate equity of hero hand vs villain range averaged over every way the
+/// board can run out to the river.
+///
+/// Draws are credited with the river (and turn) cards that complete them
+/// instead of being scored on current made-hand strength. Boards with fewer
+/// than three cards are evaluated as given.
+#[must_use]
+pub fn calculate_equity_runout(hero_hand: &Hand, villain_range: &Range, board: &[Card]) -> Equity {
+    calculate_equity_runout_with_dead(hero_hand, villain_range, board, &[])
+}
 
-        if hero_score > villain_score {
-            wins += weight;
-        } else if hero_score < villain_score {
-            losses += weight;
-        } else {
-            ties += weight;
+/// `calculate_equity_runout` with `dead` cards removed from the deck: they
+/// never come on the turn or river, and villain combos holding them are
+/// skipped.
+#[must_use]
+pub fn calculate_equity_runout_with_dead(
+    hero_hand: &Hand,
+    villain_range: &Range,
+    board: &[Card],
+    dead: &[Card],
+) -> Equity {
+    runout_equity(hero_hand, villain_range, board, dead, None)
+}
+
+/// Hero's runout equity after each possible next card on a flop or turn
+/// board, showing which turns (or rivers) help or hurt.
+///
+/// Cards held by hero, on the board or `dead` are never dealt; villain
+/// combos holding the dealt card are skipped for that card. Cards come in
+/// rank then suit order, Two of spades first. Empty for any other board.
+#[must_use]
+pub fn equity_by_next_card(
+    hero_hand: &Hand,
+    villain_range: &Range,
+    board: &[Card],
+    dead: &[Card],
+) -> Vec<(Card, Equity)> {
+    if board.len() != 3 && board.len() != 4 {
+        return Vec::new();
+    }
+    Rank::ALL
+        .iter()
+        .flat_map(|&rank| Suit::ALL.iter().map(move |&suit| Card::new(rank, suit)))
+        .filter(|c| !board.contains(c) && !dead.contains(c) && !hero_hand.contains_card(c))
+        .map(|card| {
+            let mut next = board.to_vec();
+            next.push(card);
+            let equity = calculate_equity_runout_with_dead(hero_hand, villain_range, &next, dead);
+            (card, equity)
+        })
+        .collect()
+}
+
+/// `calculate_equity_runout_with_dead`, scoring villain combos in parallel
+/// if `parallel` is `Some(true)`, one by one if `Some(false)`, and by range
+/// size (see `PARALLEL_MIN_COMBOS`) if `None`
+fn runout_equity(
+    hero_hand: &Hand,
+    villain_range: &Range,
+    board: &[Card],
+    dead: &[Card],
+    parallel: Option<bool>,
+) -> Equity {
+    let mut known_dead = hero_hand.cards.to_vec();
+    known_dead.extend_from_slice(dead);
+
+    // Hero's score on each runout is shared by every villain combo
+    let boards: Vec<(Vec<Card>, u64)> = runouts(board, &known_dead)
+        .into_iter()
+        .map(|full_board| {
+            let hero_score = evaluate_hand(hero_hand, &full_board);
+            (full_board, hero_score)
+        })
+        .collect();
+
+    let score_combo = |villain_hand: &Hand| {
+        let mut tally = Tally::default();
+        if shares_cards(hero_hand, villain_hand) || shares_board(villain_hand, dead) {
+            return tally;
+        }
+        for (full_board, hero_score) in &boards {
+            if shares_board(villain_hand, full_board) {
+                continue;
+            }
+            tally.add(Tally::showdown(
+                *hero_score,
+                evaluate_hand(villain_hand, full_board),
+            ));
         }
-        total_weight += weight;
+        tally
+    };
+    match parallel {
+        Some(parallel) => tally_combos(villain_range, score_combo, parallel),
+        None => tally_range(villain_range, score_combo),
     }
+}
+
+/// Hero's wins, ties and losses against one villain combo
+#[derive(Debug, Clone, Copy, Default)]
+struct Tally {
+    wins: f64,
+    ties: f64,
+    losses: f64,
+}
+
+impl Tally {
+    /// One showdown between the two scores
+    fn showdown(hero_score: u64, villain_score: u64) -> Self {
+        let mut tally = Self::default();
+        match hero_score.cmp(&villain_score) {
+            std::cmp::Ordering::Greater => tally.wins = 1.0,
+            std::cmp::Ordering::Less => tally.losses = 1.0,
+            std::cmp::Ordering::Equal => tally.ties = 1.0,
+        }
+        tally
+    }
+
+    fn add(&mut self, other: Tally) {
+        self.wins += other.wins;
+        self.ties += other.ties;
+        self.losses += other.losses;
+    }
+}
 
+/// `tally_combos`, in parallel for ranges of `PARALLEL_MIN_COMBOS` or more
+fn tally_range<F>(villain_range: &Range, score_combo: F) -> Equity
+where
+    F: Fn(&Hand) -> Tally + Sync,
+{
+    let parallel = villain_range.len() >= PARALLEL_MIN_COMBOS;
+    tally_combos(villain_range, score_combo, parallel)
+}
+
+/// Weighted equity over every villain combo scored by `score_combo`.
+///
+/// Combos are scored in parallel or one by one, but always summed in the
+/// same order, so both give bit-identical results.
+fn tally_combos<F>(villain_range: &Range, score_combo: F, parallel: bool) -> Equity
+where
+    F: Fn(&Hand) -> Tally + Sync,
+{
+    let combos: Vec<(&Hand, f64)> = villain_range.weighted_iter().collect();
+    let tallies: Vec<Tally> = if parallel {
+        combos
+            .par_iter()
+            .map(|(hand, _)| score_combo(hand))
+            .collect()
+    } else {
+        combos.iter().map(|(hand, _)| score_combo(hand)).collect()
+    };
+
+    let mut total = Tally::default();
+    for ((_, weight), tally) in combos.iter().zip(tallies) {
+        total.wins += weight * tally.wins;
+        total.ties += weight * tally.ties;
+        total.losses += weight * tally.losses;
+    }
+    let total_weight = total.wins + total.ties + total.losses;
     if total_weight == 0.0 {
         return Equity::new(0.0, 0.0, 0.0);
     }
 
     Equity::new(
-        wins / total_weight,
-        ties / total_weight,
-        losses / total_weight,
+        total.wins / total_weight,
+        total.ties / total_weight,
+        total.losses / total_weight,
     )
 }
 
-fn shares_cards(h1: &Hand, h2: &Hand) -> bool {
-    h1.cards[0] == h2.cards[0]
-        || h1.cards[0] == h2.cards[1]
-        || h1.cards[1] == h2.cards[0]
-        || h1.cards[1] == h2.cards[1]
+/// Equity of one hand against another over every runout of `board`.
+///
+/// Same as `calculate_equity_runout` with a single-combo range, without
+/// building the range.
+pub fn calculate_hand_equity_runout(
+    hero_hand: &Hand,
+    villain_hand: &Hand,
+    board: &[Card],
+) -> Equity {
+    calculate_hand_equity_runout_with(hero_hand, villain_hand, board, &[], evaluate_hand)
 }
 
-fn shares_board(h: &Hand, board: &[Card]) -> bool {
-    for card in board {
-        if h.cards[0] == *card || h.cards[1] == *card {
-            return true;
+/// `calculate_hand_equity_runout` with `dead` cards kept out of the runouts
+/// and a custom scoring function, e.g. a lookup into a `SharedEvalCache`.
+pub fn calculate_hand_equity_runout_with<F>(
+    hero_hand: &Hand,
+    villain_hand: &Hand,
+    board: &[Card],
+    dead: &[Card],
+    evaluate: F,
+) -> Equity
+where
+    F: Fn(&Hand, &[Card]) -> u64,
+{
+    let mut known_dead = hero_hand.cards.to_vec();
+    known_dead.extend(villain_hand.cards);
+    known_dead.extend_from_slice(dead);
+
+    let mut wins = 0.0;
+    let mut ties = 0.0;
+    let mut total = 0.0;
+
+    for full_board in runouts(board, &known_dead) {
+        let hero_score = evaluate(hero_hand, &full_board);
+        let villain_score = evaluate(villain_hand, &full_board);
+
+        if hero_score > villain_score {
+            wins += 1.0;
+        } else if hero_score == villain_score {
+            ties += 1.0;
+        }
+        total += 1.0;
+    }
+
+    Equity::new(wins / total, ties / total, (total - wins - ties) / total)
+}
+
+/// Every completion of `board` to five cards avoiding `dead` cards.
+///
+/// Complete boards and boards before the flop are returned unchanged.
+fn runouts(board: &[Card], dead: &[Card]) -> Vec<Vec<Card>> {
+    if board.len() < 3 || board.len() >= 5 {
+        return vec![board.to_vec()];
+    }
+
+    let deck: Vec<Card> = Rank::ALL
+        .iter()
+        .flat_map(|&rank| Suit::ALL.iter().map(move |&suit| Card::new(rank, suit)))
+        .filter(|c| !board.contains(c) && !dead.contains(c))
+        .collect();
+
+    let mut boards = Vec::new();
+    if board.len() == 4 {
+        for &river in &deck {
+            let mut full = board.to_vec();
+            full.push(river);
+            boards.push(full);
+        }
+    } else {
+        for (i, &turn) in deck.iter().enumerate() {
+            for &river in &deck[i + 1..] {
+                let mut full = board.to_vec();
+                full.push(turn);
+                full.push(river);
+                boards.push(full);
+            }
         }
     }
-    false
+    boards
+}
+
+fn shares_cards(h1: &Hand, h2: &Hand) -> bool {
+    h2.cards.iter().any(|c| h1.contains_card(c))
+}
+
+fn shares_board(h: &Hand, board: &[Card]) -> bool {
+    board.iter().any(|c| h.contains_card(c))
 }
 
 #[cfg(test)]
@@ -82,6 +336,15 @@ mod tests {
     use super::*;
     use std::str::FromStr;
 
+    #[test]
+    fn test_equity_display_and_share() {
+        let equity = Equity::new(0.7, 0.1, 0.2);
+        assert_eq!(equity.to_string(), "Win 70.0% / Tie 10.0% / Lose 20.0%");
+        assert!((equity.equity() - 0.75).abs() < 1e-12);
+        assert!(equity.is_valid());
+        assert!(!Equity::new(0.5, 0.0, 0.0).is_valid());
+    }
+
     #[test]
     fn test_equity_calculation() {
         let hero = Hand::from_str("AhAs").unwrap();
@@ -93,8 +356,8 @@ mod tests {
 
         // Villain range: KhKd (Set), 2c3c (Miss)
         let mut range = Range::new();
-        range.hands.insert(Hand::from_str("KhKd").unwrap(), 1.0); // Set of Ks (beats AA)
-        range.hands.insert(Hand::from_str("2c3c").unwrap(), 1.0); // High Card (loses to AA)
+        range.add(Hand::from_str("KhKd").unwrap(), 1.0); // Set of Ks (beats AA)
+        range.add(Hand::from_str("2c3c").unwrap(), 1.0); // High Card (loses to AA)
 
         let equity = calculate_equity(&hero, &range, &board);
 
@@ -102,4 +365,65 @@ mod tests {
         assert!((equity.win - 0.5).abs() < 0.001);
         assert!((equity.lose - 0.5).abs() < 0.001);
     }
+
+    #[test]
+    fn test_equity_runout_credits_draws() {
+        let hero = Hand::from_str("Ah4h").unwrap();
+        let board = vec![
+            Card::from_str("Qh").unwrap(),
+            Card::from_str("7h").unwrap(),
+            Card::from_str("2c").unwrap(),
+            Card::from_str("3s").unwrap(),
+        ];
+
+        let mut range = Range::new();
+        range.add(Hand::from_str("QcQd").unwrap(), 1.0); // Set
+
+        // Drawing dead on the turn itself
+        assert_eq!(calculate_equity(&hero, &range, &board).win, 0.0);
+
+        // 7 clean hearts (2h/3h fill villain up) plus three fives for the wheel
+        let equity = calculate_equity_runout(&hero, &range, &board);
+        assert!((equity.win - 10.0 / 44.0).abs() < 1e-9);
+        assert_eq!(equity.tie, 0.0);
+
+        let villain = Hand::from_str("QcQd").unwrap();
+        let direct = calculate_hand_equity_runout(&hero, &villain, &board);
+        assert!((direct.win - equity.win).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_parallel_equity_matches_sequential() {
+        let hero = Hand::from_str("AhKh").unwrap();
+        let board: Vec<Card> = ["Qh", "7h", "2c"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        let range = Range::from_notation("22+,A2s+,KTs+,ATo+,KQo,QJs,JTs@50,T9s").unwrap();
+        assert!(range.len() >= PARALLEL_MIN_COMBOS);
+
+        let sequential = runout_equity(&hero, &range, &board, &[], Some(false));
+        let parallel = runout_equity(&hero, &range, &board, &[], Some(true));
+        assert_eq!(parallel.win, sequential.win);
+        assert_eq!(parallel.tie, sequential.tie);
+        assert_eq!(parallel.lose, sequential.lose);
+        assert!(sequential.is_valid());
+
+        // The size-based choice gives the same numbers
+        let auto = calculate_equity_runout(&hero, &range, &board);
+        assert_eq!(auto.win, sequential.win);
+    }
+
+    #[test]
+    fn test_equity_runout_on_river_is_showdown() {
+        let hero = Hand::from_str("AhAs").unwrap();
+        let villain = Hand::from_str("KhKd").unwrap();
+        let board: Vec<Card> = ["Ks", "Qh", "Jd", "2c", "3c"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+
+        let equity = calculate_hand_equity_runout(&hero, &villain, &board);
+        assert_eq!(equity.lose, 1.0);
+    }
 }
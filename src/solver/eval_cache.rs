@@ -0,0 +1,92 @@
+//! Hand score cache shared across solver threads
+//!
+//! Showdown scores depend only on the hand and the board, not on who holds
+//! the hand, so a range-vs-range solve can evaluate every villain combo once
+//! and reuse the score for every hero combo.
+
+use crate::models::card::Card;
+use crate::models::hand::Hand;
+use crate::solver::evaluator::evaluate_mask;
+use rustc_hash::FxHashMap;
+use std::sync::RwLock;
+
+/// Thread-safe cache of hand scores keyed by the combined card mask
+#[derive(Debug, Default)]
+pub struct SharedEvalCache {
+    scores: RwLock<FxHashMap<u64, u64>>,
+}
+
+impl SharedEvalCache {
+    /// Create an empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Score of `hand` on `board`, evaluated on first use and cached
+    pub fn evaluate(&self, hand: &Hand, board: &[Card]) -> u64 {
+        let mask = Card::pokers_mask(&hand.cards) | Card::pokers_mask(board);
+
+        if let Some(&score) = self.scores.read().unwrap().get(&mask) {
+            return score;
+        }
+
+        let score = evaluate_mask(mask);
+        self.scores.write().unwrap().insert(mask, score);
+        score
+    }
+
+    /// Number of cached scores
+    pub fn len(&self) -> usize {
+        self.scores.read().unwrap().len()
+    }
+
+    /// Returns true if nothing has been cached yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::evaluator::evaluate_hand;
+    use crate::test_util::{cards, DRY_RIVER};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_cache_matches_direct_evaluation() {
+        let board: Vec<Card> = ["Qh", "Jh", "Th", "2s", "3d"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        let cache = SharedEvalCache::new();
+
+        for hand in ["AhKh", "7c4d", "QsQd", "2c2d", "Kh9h"] {
+            let hand = Hand::from_str(hand).unwrap();
+            let direct = evaluate_hand(&hand, &board);
+            assert_eq!(cache.evaluate(&hand, &board), direct);
+            // Second lookup is served from the cache
+            assert_eq!(cache.evaluate(&hand, &board), direct);
+        }
+        assert_eq!(cache.len(), 5);
+    }
+
+    #[test]
+    fn test_cache_is_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedEvalCache>();
+
+        let board = cards(DRY_RIVER);
+        let hand = Hand::from_str("AhAd").unwrap();
+        let cache = SharedEvalCache::new();
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| cache.evaluate(&hand, &board));
+            }
+        });
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.evaluate(&hand, &board), evaluate_hand(&hand, &board));
+    }
+}
@@ -3,91 +3,285 @@
 use crate::models::card::{Card, Rank, Suit};
 use crate::models::hand::Hand;
 use pokers::Hand as PHand;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-/// Evaluate hand strength (LOWER IS BETTER)
-pub fn evaluate_hand(hand: &Hand, board: &[Card]) -> u64 {
-    let mut mask = 0u64;
+/// Slow reference evaluator that `evaluate_hand` is tested against
+/// (`test-util` feature)
+#[cfg(any(test, feature = "test-util"))]
+pub mod naive;
 
-    // Add hole cards
-    mask |= get_card_mask(&hand.cards[0]);
-    mask |= get_card_mask(&hand.cards[1]);
+/// Evaluate hand strength (HIGHER IS BETTER)
+///
+/// Works for any board from preflop to river. With fewer than five cards in
+/// total the score is the best hand those cards make: high card, pairs,
+/// trips or quads, never a straight or flush. Scores are comparable between
+/// hands evaluated on the same board.
+///
+/// A hole card repeated on the board is a caller bug; it panics in debug
+/// builds and is reported on stderr in release builds.
+#[must_use]
+pub fn evaluate_hand(hand: &Hand, board: &[Card]) -> u64 {
+    let mut mask = Card::pokers_mask(&hand.cards);
+    mask |= Card::pokers_mask(board);
 
-    // Add board cards
-    for card in board {
-        mask |= get_card_mask(card);
+    let expected = hand.cards.len() + board.len();
+    if mask.count_ones() as usize != expected {
+        unexpected_evaluation(format!(
+            "mask {:#x} for {} on {:?} has {} cards, expected {}",
+            mask,
+            hand.notation(),
+            board.iter().map(|c| c.notation()).collect::<Vec<_>>(),
+            mask.count_ones(),
+            expected
+        ));
     }
 
-    // Create hand from mask
+    evaluate_mask(mask)
+}
+
+/// Lowest and one past the highest score `pokers` gives a real hand:
+/// categories run from 1 (high card) to 9 (straight flush) in the top 4 bits
+const SCORE_RANGE: std::ops::Range<u64> = (1 << 12)..(10 << 12);
+
+/// Evaluate a combined `pokers` card mask (HIGHER IS BETTER)
+///
+/// An empty mask scores 0, below every real hand. A score outside the
+/// categories `pokers` documents means the mask layout is wrong; it panics
+/// in debug builds and is reported on stderr in release builds.
+pub fn evaluate_mask(mask: u64) -> u64 {
+    if mask == 0 {
+        return 0;
+    }
+    // pokers scores are higher-is-better; the top 4 bits hold the category
     let p_hand = PHand::from_bit_mask(mask);
+    let score = p_hand.evaluate() as u64;
+    if !SCORE_RANGE.contains(&score) {
+        unexpected_evaluation(format!("score {:#x} for mask {:#x}", score, mask));
+    }
+    score
+}
 
-    // Evaluate (Lower is better in pokers 0.7?)
-    // In previous test: SF Score: 32925, Quads Score: 10829
-    // SF > Quads. 32925 > 10829. So Higher is Better.
-    // Wait, earlier output: "SF Score: 32925, Quads Score: 10829".
-    // 32925 is roughly 32k.
-    // Standard poker hand rank: 1 = Royal Flush? No, 1 = 7-5-4-3-2?
-    // Usually 1 is best or 7462 is best.
-
-    // Let's re-read the failing test output from previous turn (attempt 1 of this phase).
-    // test_evaluation_order: QQ (4897) should beat AK (5285).
-    // QQ is pair. AK is high card.
-    // Pair > High Card.
-    // If higher is better, 4897 < 5285, so AK > QQ. This is WRONG.
-    // If lower is better, 4897 < 5285, so QQ > AK. This matches "Lower is Better".
-
-    // BUT test_board_strength: Flush should beat AA.
-    // Flush (32925) vs Quads (10829) in previous discovery.
-    // Flush < Quads.
-    // If Lower is Better, Quads > Flush (10k < 32k). Correct.
-
-    // So "Lower is Better" seems consistent with:
-    // Quads (10k) > Flush (32k) -> 10k < 32k. Correct.
-    // Pair (4897) > High Card (5285) -> 4897 < 5285. Correct.
-
-    // Conclusion: pokers crate uses "Lower is Better" (1 = best?).
-    // We need to invert the score for our internal "Higher is Better" logic if we want to keep `calculate_equity` simple (hero > villain).
-    // Or update `calculate_equity` to verify smaller is better.
-
-    // Let's invert it here so the rest of the system sees "Higher is Better".
-    // u16::MAX - score.
-
-    let score = p_hand.evaluate();
-    (u16::MAX as u64) - (score as u64)
+/// Fail loudly in debug builds; in release builds warn and carry on, since
+/// one bad evaluation shouldn't abort a long solve
+fn unexpected_evaluation(detail: String) {
+    if cfg!(debug_assertions) {
+        panic!("Unexpected hand evaluation: {}", detail);
+    }
+    eprintln!("Warning: unexpected hand evaluation: {}", detail);
 }
 
-fn get_card_mask(card: &Card) -> u64 {
-    let r = match card.rank {
-        Rank::Two => 0,
-        Rank::Three => 1,
-        Rank::Four => 2,
-        Rank::Five => 3,
-        Rank::Six => 4,
-        Rank::Seven => 5,
-        Rank::Eight => 6,
-        Rank::Nine => 7,
-        Rank::Ten => 8,
-        Rank::Jack => 9,
-        Rank::Queen => 10,
-        Rank::King => 11,
-        Rank::Ace => 12,
+/// Drawing potential of a hand on a flop or turn
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawInfo {
+    /// Four cards to a flush, at least one of them a hole card
+    pub flush_draw: bool,
+    /// Two or more ranks complete a straight (open-ender or double gutter)
+    pub oesd: bool,
+    /// Exactly one rank completes a straight
+    pub gutshot: bool,
+    /// Unseen cards that complete a flush or straight for hero
+    pub outs: u8,
+}
+
+/// Compute hero's flush and straight draws on a flop or turn.
+///
+/// Only draws that use at least one hole card are counted, and a draw to a
+/// hand hero already holds (e.g. a flush draw while holding a flush) is not
+/// a draw. Outs are counted over the cards not in hero's hand or on the
+/// board; a card completing both a flush and a straight counts once.
+/// Preflop and river boards have no draws.
+#[must_use]
+pub fn draw_info(hand: &Hand, board: &[Card]) -> DrawInfo {
+    if board.len() != 3 && board.len() != 4 {
+        return DrawInfo::default();
+    }
+
+    let mut known: Vec<Card> = hand.cards.to_vec();
+    known.extend_from_slice(board);
+
+    let hero_ranks = rank_mask(&known);
+    let board_ranks = rank_mask(board);
+    let has_straight = is_straight(hero_ranks);
+    let has_flush = Suit::ALL
+        .iter()
+        .any(|&suit| known.iter().filter(|c| c.suit == suit).count() >= 5);
+
+    // Suits with four cards where hero contributes at least one
+    let flush_suits: Vec<Suit> = if has_flush {
+        Vec::new()
+    } else {
+        Suit::ALL
+            .iter()
+            .copied()
+            .filter(|&suit| {
+                known.iter().filter(|c| c.suit == suit).count() == 4
+                    && hand.cards.iter().any(|c| c.suit == suit)
+            })
+            .collect()
     };
 
-    let s = match card.suit {
-        Suit::Spades => 0,
-        Suit::Hearts => 1,
-        Suit::Clubs => 2,
-        Suit::Diamonds => 3,
+    // Ranks that would give hero a straight the board alone doesn't make
+    let straight_ranks: Vec<Rank> = if has_straight {
+        Vec::new()
+    } else {
+        Rank::ALL
+            .iter()
+            .copied()
+            .filter(|&rank| {
+                let bit = 1u16 << rank.index();
+                is_straight(hero_ranks | bit) && !is_straight(board_ranks | bit)
+            })
+            .collect()
     };
 
-    // Formula derived from discovery: shift = rank + (3 - suit) * 16
-    let shift = r + (3 - s) * 16;
-    1u64 << shift
+    let outs = Rank::ALL
+        .iter()
+        .flat_map(|&rank| Suit::ALL.iter().map(move |&suit| Card::new(rank, suit)))
+        .filter(|card| !known.contains(card))
+        .filter(|card| flush_suits.contains(&card.suit) || straight_ranks.contains(&card.rank))
+        .count();
+
+    DrawInfo {
+        flush_draw: !flush_suits.is_empty(),
+        oesd: straight_ranks.len() >= 2,
+        gutshot: straight_ranks.len() == 1,
+        outs: outs as u8,
+    }
+}
+
+/// Made-hand class of a holding on a board, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum HandCategory {
+    /// Straight flush
+    StraightFlush,
+    /// Four of a kind
+    Quads,
+    /// Full house
+    FullHouse,
+    /// Flush
+    Flush,
+    /// Straight
+    Straight,
+    /// Three of a kind using a pocket pair
+    Set,
+    /// Three of a kind using one hole card
+    Trips,
+    /// Two pair
+    TwoPair,
+    /// Pocket pair above every board card
+    Overpair,
+    /// Pair with the highest board card
+    TopPair,
+    /// Pair with the second highest board rank, or a pocket pair between the
+    /// top two board ranks
+    MiddlePair,
+    /// Any lower pair
+    WeakPair,
+    /// No pair, but a flush or straight draw
+    Draw,
+    /// Nothing
+    Air,
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HandCategory::StraightFlush => "straight flush",
+            HandCategory::Quads => "quads",
+            HandCategory::FullHouse => "full house",
+            HandCategory::Flush => "flush",
+            HandCategory::Straight => "straight",
+            HandCategory::Set => "set",
+            HandCategory::Trips => "trips",
+            HandCategory::TwoPair => "two pair",
+            HandCategory::Overpair => "overpair",
+            HandCategory::TopPair => "top pair",
+            HandCategory::MiddlePair => "middle pair",
+            HandCategory::WeakPair => "weak pair",
+            HandCategory::Draw => "draw",
+            HandCategory::Air => "air",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Classify hero's holding on the board.
+///
+/// Made hands come from the full seven-card score, so a hand the board makes
+/// on its own counts as hero's. Pairs are placed relative to the distinct
+/// board ranks; with no board a pocket pair is an overpair.
+#[must_use]
+pub fn hand_category(hand: &Hand, board: &[Card]) -> HandCategory {
+    let category = evaluate_hand(hand, board) >> 12;
+    match category {
+        9 => HandCategory::StraightFlush,
+        8 => HandCategory::Quads,
+        7 => HandCategory::FullHouse,
+        6 => HandCategory::Flush,
+        5 => HandCategory::Straight,
+        4 if hand.is_pair() => HandCategory::Set,
+        4 => HandCategory::Trips,
+        3 => HandCategory::TwoPair,
+        _ => pair_category(hand, board).unwrap_or_else(|| {
+            let draws = draw_info(hand, board);
+            if draws.flush_draw || draws.oesd || draws.gutshot {
+                HandCategory::Draw
+            } else {
+                HandCategory::Air
+            }
+        }),
+    }
+}
+
+/// Pair class when hero's hole cards make a pair, `None` otherwise
+fn pair_category(hand: &Hand, board: &[Card]) -> Option<HandCategory> {
+    let mut board_ranks: Vec<Rank> = board.iter().map(|c| c.rank).collect();
+    board_ranks.sort_by(|a, b| b.cmp(a));
+    board_ranks.dedup();
+
+    let pair_rank = if hand.is_pair() {
+        let rank = hand.cards[0].rank;
+        return Some(match board_ranks.as_slice() {
+            [] => HandCategory::Overpair,
+            [top, ..] if rank > *top => HandCategory::Overpair,
+            [_, second, ..] if rank > *second => HandCategory::MiddlePair,
+            _ => HandCategory::WeakPair,
+        });
+    } else {
+        hand.cards
+            .iter()
+            .map(|c| c.rank)
+            .filter(|r| board_ranks.contains(r))
+            .max()?
+    };
+
+    Some(match board_ranks.iter().position(|&r| r == pair_rank) {
+        Some(0) => HandCategory::TopPair,
+        Some(1) => HandCategory::MiddlePair,
+        _ => HandCategory::WeakPair,
+    })
+}
+
+/// Bit per rank present (bit 0 = Two ... bit 12 = Ace)
+fn rank_mask(cards: &[Card]) -> u16 {
+    cards
+        .iter()
+        .fold(0u16, |mask, c| mask | (1u16 << c.rank.index()))
+}
+
+/// True if the rank mask contains five consecutive ranks (wheel included)
+fn is_straight(mask: u16) -> bool {
+    // Duplicate the ace below the two so A-2-3-4-5 is a normal run
+    let extended = (mask << 1) | ((mask >> 12) & 1);
+    (0..=9).any(|low| (extended >> low) & 0b11111 == 0b11111)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::card::Card;
+    use crate::test_util::{cards, DRY_RIVER};
     use std::str::FromStr;
 
     #[test]
@@ -103,7 +297,6 @@ mod tests {
         let s1 = evaluate_hand(&h1, &board);
         let s2 = evaluate_hand(&h2, &board);
 
-        // We inverted score, so Higher should be Better now.
         assert!(s2 > s1, "QQ ({}) should beat AK ({})", s2, s1);
     }
 
@@ -125,4 +318,156 @@ mod tests {
 
         assert!(s1 > s2, "Flush ({}) should beat AA ({})", s1, s2);
     }
+
+    #[test]
+    fn test_hand_categories_rank_in_order() {
+        // Strongest first, each hand on its own river board
+        let hands = [
+            ("AhKh", "QhJhTh2c3d"), // straight flush
+            ("9c9d", "9h9s2c3d4h"), // quads
+            ("KcKd", "Kh2s2c7d8h"), // full house
+            ("Ah2h", "9h7h4h3cJd"), // flush
+            ("9c8d", "7h6s5c2dKh"), // straight
+            ("QcQd", "Qh7s4c2d9h"), // trips
+            ("Ac7d", "Ah7s4c2d9h"), // two pair
+            ("AcKd", "Ah8s4c2d9h"), // pair
+            ("AcKd", "Qh8s4c2d9h"), // high card
+        ];
+        let scores: Vec<u64> = hands
+            .iter()
+            .map(|(hand, board)| evaluate_hand(&Hand::from_str(hand).unwrap(), &cards(board)))
+            .collect();
+        for (i, pair) in scores.windows(2).enumerate() {
+            assert!(
+                pair[0] > pair[1],
+                "{:?} should beat {:?}",
+                hands[i],
+                hands[i + 1]
+            );
+        }
+
+        // Kickers break ties within a category
+        let board = cards("Ah8s4c2d9h");
+        let ace_king = evaluate_hand(&Hand::from_str("AcKd").unwrap(), &board);
+        let ace_queen = evaluate_hand(&Hand::from_str("AdQc").unwrap(), &board);
+        assert!(ace_king > ace_queen);
+        let same = evaluate_hand(&Hand::from_str("AsKc").unwrap(), &board);
+        assert_eq!(ace_king, same);
+    }
+
+    #[test]
+    fn test_evaluate_short_boards() {
+        let score =
+            |h: &str, board: &str| evaluate_hand(&Hand::from_str(h).unwrap(), &cards(board));
+        // Categories: 1 high card, 2 pair, 3 two pair, 4 trips, 8 quads, 9 straight flush
+        let category = |h: &str, board: &str| score(h, board) >> 12;
+
+        // Preflop: two cards
+        assert!(score("AhAd", "") > score("KhKd", ""));
+        assert!(score("KhKd", "") > score("AhKd", ""));
+        assert!(score("AhKd", "") > score("AhQd", ""));
+        assert_eq!(category("AhAd", ""), 2);
+        assert_eq!(category("7h2d", ""), 1);
+
+        // Three cards
+        assert_eq!(category("AhAd", "Ac"), 4);
+        assert!(score("AhKd", "Kc") > score("AhQd", "Kc"));
+
+        // Four cards: no straight or flush possible
+        assert_eq!(category("AhAd", "KcKs"), 3);
+        assert_eq!(category("AhKh", "QhJh"), 1);
+        assert_eq!(category("AhAd", "AcAs"), 8);
+
+        // Five cards: full evaluation
+        assert_eq!(category("AhKh", "QhJhTh"), 9);
+        assert!(score("AhKh", "QhJhTh") > score("AhAd", "AcAs2d"));
+    }
+
+    #[test]
+    fn test_evaluate_empty_mask() {
+        assert_eq!(evaluate_mask(0), 0);
+    }
+
+    #[test]
+    fn test_mask_has_one_bit_per_card() {
+        let hand = Hand::from_str("AhKh").unwrap();
+        let board = cards("QhJhTh2s3d");
+        let mask = Card::pokers_mask(&hand.cards) | Card::pokers_mask(&board);
+        assert_eq!(mask.count_ones(), 7);
+
+        let score = evaluate_hand(&hand, &board);
+        assert!(SCORE_RANGE.contains(&score));
+        assert_eq!(score >> 12, 9);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Unexpected hand evaluation")]
+    fn test_hole_card_on_board_panics_in_debug() {
+        let _ = evaluate_hand(&Hand::from_str("AhKh").unwrap(), &cards("AhJhTh"));
+    }
+
+    #[test]
+    fn test_hand_category() {
+        let board = cards("Ks8d4c");
+        let category = |h: &str| hand_category(&Hand::from_str(h).unwrap(), &board);
+
+        assert_eq!(category("8h8s"), HandCategory::Set);
+        assert_eq!(category("Kh8c"), HandCategory::TwoPair);
+        assert_eq!(category("AhAd"), HandCategory::Overpair);
+        assert_eq!(category("KhQd"), HandCategory::TopPair);
+        assert_eq!(category("TdTs"), HandCategory::MiddlePair);
+        assert_eq!(category("8h7h"), HandCategory::MiddlePair);
+        assert_eq!(category("4h3h"), HandCategory::WeakPair);
+        assert_eq!(category("2h2d"), HandCategory::WeakPair);
+        assert_eq!(category("7h6h"), HandCategory::Draw);
+        assert_eq!(category("Jh9c"), HandCategory::Air);
+
+        let river = cards(DRY_RIVER);
+        assert_eq!(
+            hand_category(&Hand::from_str("6h5h").unwrap(), &river),
+            HandCategory::Straight
+        );
+        assert_eq!(
+            hand_category(&Hand::from_str("AhAd").unwrap(), &[]),
+            HandCategory::Overpair
+        );
+        assert_eq!(HandCategory::TopPair.to_string(), "top pair");
+    }
+
+    #[test]
+    fn test_draw_info_flush_draw() {
+        let hand = Hand::from_str("AhKh").unwrap();
+        let info = draw_info(&hand, &cards("7h2h9c"));
+
+        assert!(info.flush_draw);
+        assert!(!info.oesd);
+        assert!(!info.gutshot);
+        assert_eq!(info.outs, 9);
+    }
+
+    #[test]
+    fn test_draw_info_open_ender() {
+        let hand = Hand::from_str("9h8d").unwrap();
+        let info = draw_info(&hand, &cards("7c6s2d"));
+
+        assert!(!info.flush_draw);
+        assert!(info.oesd);
+        assert_eq!(info.outs, 8);
+    }
+
+    #[test]
+    fn test_draw_info_gutshot_and_made_hands() {
+        let gutshot = draw_info(&Hand::from_str("9h8d").unwrap(), &cards("6c5s2d"));
+        assert!(gutshot.gutshot);
+        assert_eq!(gutshot.outs, 4);
+
+        // Already holding a straight: nothing to draw to
+        let made = draw_info(&Hand::from_str("9h8d").unwrap(), &cards("7c6s5d"));
+        assert_eq!(made, DrawInfo::default());
+
+        // River boards have no draws
+        let river = draw_info(&Hand::from_str("AhKh").unwrap(), &cards("7h2h9c3s4d"));
+        assert_eq!(river, DrawInfo::default());
+    }
 }
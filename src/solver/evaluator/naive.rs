@@ -0,0 +1,132 @@
+//! Reference hand evaluator, independent of `pokers`
+//!
+//! Scores the best five cards out of up to seven by trying every five-card
+//! subset and comparing category, then ranks, the way the rules read. It
+//! is far too slow for solving, but easy to check by eye, so tests can pin
+//! `evaluate_hand` against it.
+
+use crate::models::card::Card;
+
+/// Score of the best five-card hand in `cards` (HIGHER IS BETTER).
+///
+/// Bits 20 and up hold the category, numbered as `pokers` does: 1 (high
+/// card) to 9 (straight flush). Below that, five 4-bit rank indexes (Two =
+/// 0) break ties, most significant first: the rank of the larger group
+/// before the smaller, higher ranks before lower, and only the top card of
+/// a straight. Fewer than five cards score 0.
+pub fn evaluate(cards: &[Card]) -> u64 {
+    let n = cards.len();
+    let mut best = 0;
+    for a in 0..n {
+        for b in a + 1..n {
+            for c in b + 1..n {
+                for d in c + 1..n {
+                    for e in d + 1..n {
+                        let five = [cards[a], cards[b], cards[c], cards[d], cards[e]];
+                        best = best.max(evaluate_five(&five));
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Category of a score from `evaluate`, 1 (high card) to 9 (straight flush)
+pub fn category(score: u64) -> u64 {
+    score >> 20
+}
+
+fn evaluate_five(cards: &[Card; 5]) -> u64 {
+    let mut counts = [0u8; 13];
+    for card in cards {
+        counts[card.rank.index() as usize] += 1;
+    }
+    // (count, rank) groups, biggest group first, then highest rank
+    let mut groups: Vec<(u8, u8)> = (0..13u8)
+        .filter(|&r| counts[r as usize] > 0)
+        .map(|r| (counts[r as usize], r))
+        .collect();
+    groups.sort_by(|x, y| y.cmp(x));
+
+    let flush = cards.iter().all(|c| c.suit == cards[0].suit);
+    let straight_high = match groups.len() {
+        5 if groups[0].1 - groups[4].1 == 4 => Some(groups[0].1),
+        // A-2-3-4-5 plays as a five-high straight
+        5 if groups[0].1 == 12 && groups[1].1 == 3 => Some(3),
+        _ => None,
+    };
+
+    let category = match (straight_high, flush, groups[0].0, groups.get(1)) {
+        (Some(_), true, ..) => 9,
+        (_, _, 4, _) => 8,
+        (_, _, 3, Some((2, _))) => 7,
+        (_, true, ..) => 6,
+        (Some(_), false, ..) => 5,
+        (_, _, 3, _) => 4,
+        (_, _, 2, Some((2, _))) => 3,
+        (_, _, 2, _) => 2,
+        _ => 1,
+    };
+
+    let ranks: Vec<u8> = match straight_high {
+        Some(high) => vec![high],
+        None => groups.iter().map(|&(_, r)| r).collect(),
+    };
+    let mut score = category << 20;
+    for (i, &rank) in ranks.iter().enumerate() {
+        score |= (rank as u64) << (16 - 4 * i);
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::evaluator::evaluate_mask;
+    use crate::test_util::cards;
+    use rand::{seq::SliceRandom, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    #[test]
+    fn test_naive_ranks_by_the_rules() {
+        let wheel = evaluate(&cards("Ah 2c 3d 4s 5h Kc Kd"));
+        let six_high = evaluate(&cards("6h 2c 3d 4s 5h Kc Qd"));
+        assert_eq!(category(wheel), 5);
+        assert!(six_high > wheel);
+
+        // Trips of the higher rank win a full house
+        assert!(evaluate(&cards("9s 9d 9c 4h 4c")) > evaluate(&cards("4s 4d 4c 9h 9c")));
+        // Kicker decides between equal pairs
+        assert!(evaluate(&cards("Ks Kd Ac 7h 2c")) > evaluate(&cards("Kh Kc Qc 7d 2d")));
+        assert_eq!(evaluate(&cards("Ks Kd Ac")), 0);
+    }
+
+    #[test]
+    fn test_naive_agrees_with_pokers() {
+        let mut deck: Vec<Card> = (0..52)
+            .map(|i| Card::from_pokers_index(i).unwrap())
+            .collect();
+        let mut rng = Xoshiro256PlusPlus::seed_from_u64(2429);
+
+        let mut previous: Option<(Vec<Card>, u64, u64)> = None;
+        for _ in 0..5000 {
+            deck.shuffle(&mut rng);
+            let hand = deck[..7].to_vec();
+            let naive = evaluate(&hand);
+            let pokers = evaluate_mask(Card::pokers_mask(&hand));
+            assert_eq!(category(naive), pokers >> 12, "category of {:?}", hand);
+
+            if let Some((other, other_naive, other_pokers)) = &previous {
+                assert_eq!(
+                    naive.cmp(other_naive),
+                    pokers.cmp(other_pokers),
+                    "{:?} vs {:?}",
+                    hand,
+                    other
+                );
+            }
+            previous = Some((hand, naive, pokers));
+        }
+    }
+}
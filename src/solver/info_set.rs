@@ -1,9 +1,17 @@
 //! Information set abstraction for MCCFR.
 //!
 //! This module defines the `InfoSetKey` struct and `SprBucket` enum used to
-//! group similar game states into information sets for strategy computation.
+//! group similar game states into information sets for strategy computation,
+//! and `StrengthBuckets` for keying villain decisions by hand strength.
 
-use crate::models::{game_state::GameState, game_state::Position, hand::Hand};
+use crate::models::{
+    card::Card,
+    game_state::{GameState, Position, Street},
+    hand::Hand,
+    range::Range,
+};
+use crate::solver::evaluator::evaluate_hand;
+use std::fmt;
 
 /// Discretized stack-to-pot ratio for info set grouping.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -34,6 +42,10 @@ impl SprBucket {
 }
 
 /// Unique identifier for an information set.
+///
+/// The pot and amount to call tell apart the decision points a player meets
+/// along one betting line, e.g. acting first versus facing a bet after
+/// checking.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct InfoSetKey {
     /// Hero's hole cards
@@ -42,6 +54,16 @@ pub struct InfoSetKey {
     pub spr_bucket: SprBucket,
     /// Hero's position
     pub position: Position,
+    /// Strength bucket standing in for the exact hand, if abstracted
+    pub strength_bucket: Option<u8>,
+    /// Street of the decision
+    pub street: Street,
+    /// Pot in hundredths of a big blind
+    pub pot_cents: u64,
+    /// Amount to call in hundredths of a big blind
+    pub to_call_cents: u64,
+    /// Board cards as a `pokers` mask, telling apart flops dealt mid-solve
+    pub board_mask: u64,
 }
 
 impl InfoSetKey {
@@ -57,6 +79,139 @@ impl InfoSetKey {
             hero_hand: state.hero_hand.clone(),
             spr_bucket: SprBucket::from_spr(spr),
             position: state.position,
+            strength_bucket: None,
+            street: state.street,
+            pot_cents: to_cents(state.pot_size),
+            to_call_cents: to_cents(state.to_call),
+            board_mask: Card::pokers_mask(&state.board),
         }
     }
+
+    /// Key for a player whose hand is abstracted to `bucket`.
+    ///
+    /// `hero_hand` keeps `state.hero_hand`, which is fixed for a solve, so
+    /// every hand in the same bucket shares the key.
+    pub fn from_bucket(state: &GameState, bucket: u8) -> Self {
+        Self {
+            strength_bucket: Some(bucket),
+            ..Self::from_game_state(state)
+        }
+    }
+}
+
+/// One line showing every field, e.g. for `--verbose` output:
+/// `AhKd River IP spr=Medium pot=10.00 to_call=0.00 bucket=- board=0x...`
+impl fmt::Display for InfoSetKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bucket = match self.strength_bucket {
+            Some(bucket) => bucket.to_string(),
+            None => "-".to_string(),
+        };
+        write!(
+            f,
+            "{} {:?} {:?} spr={:?} pot={:.2} to_call={:.2} bucket={} board={:#x}",
+            self.hero_hand.combo_notation(),
+            self.street,
+            self.position,
+            self.spr_bucket,
+            self.pot_cents as f64 / 100.0,
+            self.to_call_cents as f64 / 100.0,
+            bucket,
+            self.board_mask
+        )
+    }
+}
+
+impl GameState {
+    /// Key of the info set hero's decision here falls into, for seeing how
+    /// a spot is abstracted and which spots collide (see
+    /// `InfoSetKey::from_game_state`)
+    pub fn info_set_key(&self) -> InfoSetKey {
+        InfoSetKey::from_game_state(self)
+    }
+}
+
+/// Chip amount in hundredths of a big blind, so amounts can be hashed
+fn to_cents(amount: f64) -> u64 {
+    (amount.max(0.0) * 100.0).round() as u64
+}
+
+/// Groups hands by made-hand strength against a reference range.
+///
+/// A hand's strength is the weighted share of reference combos it beats on
+/// the current board, counting ties as half. Reference combos sharing a card
+/// with the hand or the board are ignored.
+#[derive(Debug, Clone)]
+pub struct StrengthBuckets {
+    buckets: u8,
+    board: Vec<Card>,
+    /// Reference combos with their weight and score on the board
+    reference: Vec<(Hand, f64, u64)>,
+}
+
+impl StrengthBuckets {
+    /// Buckets against `reference`, or against every possible combo if `None`.
+    pub fn new(board: &[Card], reference: Option<&Range>, buckets: u8) -> Self {
+        let combos: Vec<(Hand, f64)> = match reference {
+            Some(range) => range
+                .hands()
+                .filter(|(_, weight)| *weight > 0.0)
+                .map(|(hand, weight)| (hand.clone(), weight))
+                .collect(),
+            None => all_combos().into_iter().map(|hand| (hand, 1.0)).collect(),
+        };
+        let reference = combos
+            .into_iter()
+            .filter(|(hand, _)| !hand.cards.iter().any(|c| board.contains(c)))
+            .map(|(hand, weight)| {
+                let score = evaluate_hand(&hand, board);
+                (hand, weight, score)
+            })
+            .collect();
+
+        Self {
+            buckets: buckets.max(1),
+            board: board.to_vec(),
+            reference,
+        }
+    }
+
+    /// Number of buckets
+    pub fn count(&self) -> u8 {
+        self.buckets
+    }
+
+    /// Bucket of `hand`, from 0 (weakest) to `count() - 1` (strongest)
+    pub fn bucket(&self, hand: &Hand) -> u8 {
+        let score = evaluate_hand(hand, &self.board);
+        let (mut beaten, mut total) = (0.0, 0.0);
+        for (other, weight, other_score) in &self.reference {
+            if other.cards.iter().any(|c| hand.cards.contains(c)) {
+                continue;
+            }
+            beaten += match score.cmp(other_score) {
+                std::cmp::Ordering::Greater => *weight,
+                std::cmp::Ordering::Equal => weight / 2.0,
+                std::cmp::Ordering::Less => 0.0,
+            };
+            total += weight;
+        }
+
+        let strength = if total > 0.0 { beaten / total } else { 0.5 };
+        ((strength * self.buckets as f64) as u8).min(self.buckets - 1)
+    }
+}
+
+/// Every two-card combination of the deck
+fn all_combos() -> Vec<Hand> {
+    let cards: Vec<Card> = (0..52)
+        .filter_map(|i| Card::from_pokers_index(i).ok())
+        .collect();
+    let mut combos = Vec::with_capacity(1326);
+    for (i, &c1) in cards.iter().enumerate() {
+        for &c2 in &cards[i + 1..] {
+            combos.push(Hand::new(c1, c2));
+        }
+    }
+    combos
 }
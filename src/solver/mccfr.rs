@@ -2,20 +2,40 @@
 //!
 //! This module implements the core MCCFR algorithm using External Sampling.
 
+use crate::error::ModelError;
 use crate::models::{
-    action::{Action, BetSize},
-    game_state::{GameState, Position},
+    action::{Action, BetSize, StreetBetSizes},
+    card::Card,
+    game_state::{GameState, Position, Street},
     hand::Hand,
+    icm::IcmModel,
     range::Range,
-    strategy::{ActionStrategy, Strategy},
+    strategy::{weighted_ev, ActionStrategy, EvUnit, Provenance, Strategy, Subsample},
 };
-use crate::solver::{evaluator::evaluate_hand, info_set::InfoSetKey, regret::RegretTable};
-use rand::{Rng, SeedableRng};
+use crate::solver::{
+    cfr::determine_available_actions,
+    equity::{calculate_hand_equity_runout_with, Equity},
+    eval_cache::SharedEvalCache,
+    info_set::{InfoSetKey, StrengthBuckets},
+    progress::Snapshot,
+    regret::RegretTable,
+};
+use crate::JSON_SCHEMA_VERSION;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_xoshiro::Xoshiro256PlusPlus;
 use rustc_hash::FxHashMap;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Configuration for solver execution.
+///
+/// Other crates construct it with `MccfrConfig::builder()` or from
+/// `MccfrConfig::default()`, since new options may be added at any time.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct MccfrConfig {
     /// Number of MCCFR iterations
     pub iterations: u32,
@@ -25,6 +45,77 @@ pub struct MccfrConfig {
     pub convergence_threshold: f64,
     /// RNG seed for reproducibility
     pub seed: Option<u64>,
+    /// Optional schedule overriding `samples_per_iteration` by solve progress
+    pub sample_schedule: Option<SampleSchedule>,
+    /// Split tied pots in whole chips, odd chip to the out-of-position player
+    pub chip_accurate: bool,
+    /// Chips per big blind used when `chip_accurate` is set
+    pub big_blind_size: u32,
+    /// Key villain decisions by this many hand-strength buckets instead of
+    /// by exact combo
+    pub villain_buckets: Option<u8>,
+    /// Hero range that villain hand strength is measured against when
+    /// bucketing; every possible combo if unset
+    pub hero_range: Option<Range>,
+    /// Time sampling, showdown evaluation and regret updates
+    pub profile: bool,
+    /// Report the last regret-matched strategy at the root instead of the
+    /// average over iterations. Only the average approximates a Nash
+    /// equilibrium; the current strategy can oscillate, so this is meant
+    /// for debugging.
+    pub return_current_strategy: bool,
+    /// Bet sizes hero chooses from when not facing a bet; the default
+    /// check/half-pot abstraction if unset
+    pub hero_bet_sizes: Option<Vec<BetSize>>,
+    /// Bet sizes villain chooses from when not facing a bet, so the tree can
+    /// be asymmetric (e.g. villain always bets pot)
+    pub villain_bet_sizes: Option<Vec<BetSize>>,
+    /// Bet sizes by street for a player without `hero_bet_sizes` or
+    /// `villain_bet_sizes` of their own; preflop keeps the default
+    /// abstraction. `StreetBetSizes::default()` is a sensible starting set.
+    pub street_bet_sizes: Option<StreetBetSizes>,
+    /// Solve against a seeded random subset of at most this many villain
+    /// combos when the range is larger, trading accuracy for speed
+    pub max_villain_combos: Option<usize>,
+    /// Decisions allowed along any line before the hand is forced to
+    /// showdown, calling any pending bet; unbounded if unset
+    pub max_depth: Option<u32>,
+    /// Raises allowed on a street below the root; past it a player facing
+    /// a bet can only fold or call. The root always offers its own raises.
+    pub max_raises: u32,
+    /// Rake taken from the pot before it is awarded; none if unset
+    pub rake: Option<Rake>,
+    /// In a preflop spot, deal a random flop when preflop action closes
+    /// without a fold and keep playing on it instead of going to showdown.
+    /// Preflop stays one decision each (e.g. an open and a call), and flop
+    /// play uses the flop's bet sizes.
+    pub deal_flop: bool,
+    /// Regrets from a solve of a similar spot (e.g. a slightly different
+    /// pot or stack) to start from; info sets with matching keys reuse
+    /// them and the rest start fresh. See `solve_with_regrets`.
+    pub warm_start: Option<Arc<RegretTable>>,
+    /// Record the convergence metric at every check in
+    /// `Strategy::convergence_history`
+    pub record_convergence: bool,
+    /// Stop before `iterations` once this criterion is met, tested at every
+    /// convergence check; `iterations` is then only a cap
+    pub stop_when: Option<StopWhen>,
+    /// Score terminal payoffs by the change in prize equity under this
+    /// tournament model instead of in chips. EVs are then in payout units.
+    /// Rake is not applied to ICM payoffs.
+    pub icm: Option<IcmModel>,
+    /// Stop before the next iteration once this is set, e.g. from another
+    /// thread, returning the strategy so far with the iterations actually
+    /// run
+    pub stop: Option<Arc<AtomicBool>>,
+    /// Walk every villain combo, weighted, on each iteration instead of
+    /// sampling `samples_per_iteration` of them when the villain range has
+    /// fewer combos than this. Small ranges are cheap to enumerate and it
+    /// removes the sampling noise; 0 (the default) always samples.
+    pub enumerate_villain_below: usize,
+    /// How strategy changes between convergence checks are summarized,
+    /// for `convergence_threshold` and the reported convergence
+    pub convergence_metric: ConvergenceMetric,
 }
 
 impl Default for MccfrConfig {
@@ -34,6 +125,473 @@ impl Default for MccfrConfig {
             samples_per_iteration: 100,
             convergence_threshold: 0.001,
             seed: None,
+            sample_schedule: None,
+            chip_accurate: false,
+            big_blind_size: 100,
+            villain_buckets: None,
+            hero_range: None,
+            profile: false,
+            return_current_strategy: false,
+            hero_bet_sizes: None,
+            villain_bet_sizes: None,
+            street_bet_sizes: None,
+            max_villain_combos: None,
+            max_depth: None,
+            max_raises: 0,
+            rake: None,
+            deal_flop: false,
+            warm_start: None,
+            record_convergence: false,
+            stop_when: None,
+            icm: None,
+            stop: None,
+            enumerate_villain_below: 0,
+            convergence_metric: ConvergenceMetric::MaxChange,
+        }
+    }
+}
+
+impl MccfrConfig {
+    /// Builder starting from the default configuration
+    pub fn builder() -> MccfrConfigBuilder {
+        MccfrConfigBuilder::default()
+    }
+
+    /// Whether a node below the root at `state` may offer raises
+    pub(crate) fn can_raise(&self, state: &GameState) -> bool {
+        state.street_raises < self.max_raises
+    }
+
+    /// Chips per big blind if tied pots are split in whole chips
+    pub fn chips_per_bb(&self) -> Option<u32> {
+        (self.chip_accurate && self.big_blind_size > 0).then_some(self.big_blind_size)
+    }
+
+    /// Provenance of a solve with this configuration, if it is seeded
+    pub fn provenance(&self, solver: &str, iterations: u32) -> Option<Provenance> {
+        self.seed.map(|seed| Provenance {
+            seed,
+            iterations,
+            solver: solver.to_string(),
+            hero_bet_sizes: self.hero_bet_sizes.clone(),
+            villain_bet_sizes: self.villain_bet_sizes.clone(),
+            street_bet_sizes: self.street_bet_sizes.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        })
+    }
+
+    /// Hero's bet sizes on `street`: `hero_bet_sizes`, or else the street's
+    /// entry in `street_bet_sizes`
+    pub fn hero_sizes_on(&self, street: Street) -> Option<&[BetSize]> {
+        sizes_on(
+            self.hero_bet_sizes.as_deref(),
+            self.street_bet_sizes.as_ref(),
+            street,
+        )
+    }
+
+    /// Villain's bet sizes on `street`, as `hero_sizes_on` picks hero's
+    pub fn villain_sizes_on(&self, street: Street) -> Option<&[BetSize]> {
+        sizes_on(
+            self.villain_bet_sizes.as_deref(),
+            self.street_bet_sizes.as_ref(),
+            street,
+        )
+    }
+
+    /// Villain hands to sample on the given (0-based) iteration.
+    pub fn samples_at(&self, iteration: u32) -> usize {
+        match self.sample_schedule {
+            Some(schedule) => schedule.samples_at(iteration, self.iterations),
+            None => self.samples_per_iteration,
+        }
+    }
+}
+
+/// Builder for `MccfrConfig`, starting from the defaults.
+///
+/// `MccfrConfig` is `#[non_exhaustive]` so that options can be added
+/// without breaking other crates; outside this crate, build it here (or
+/// start from `MccfrConfig::default()` and assign fields).
+#[derive(Debug, Clone, Default)]
+pub struct MccfrConfigBuilder {
+    config: MccfrConfig,
+}
+
+impl MccfrConfigBuilder {
+    /// Number of MCCFR iterations
+    pub fn iterations(mut self, iterations: u32) -> Self {
+        self.config.iterations = iterations;
+        self
+    }
+
+    /// Villain hands sampled per iteration
+    pub fn samples_per_iteration(mut self, samples_per_iteration: usize) -> Self {
+        self.config.samples_per_iteration = samples_per_iteration;
+        self
+    }
+
+    /// Strategy change threshold for early stop
+    pub fn convergence_threshold(mut self, convergence_threshold: f64) -> Self {
+        self.config.convergence_threshold = convergence_threshold;
+        self
+    }
+
+    /// RNG seed for reproducibility; `None` (the default) clears it
+    pub fn seed(mut self, seed: impl Into<Option<u64>>) -> Self {
+        self.config.seed = seed.into();
+        self
+    }
+
+    /// Schedule overriding `samples_per_iteration` by solve progress; `None` (the default) clears it
+    pub fn sample_schedule(mut self, sample_schedule: impl Into<Option<SampleSchedule>>) -> Self {
+        self.config.sample_schedule = sample_schedule.into();
+        self
+    }
+
+    /// Split tied pots in whole chips
+    pub fn chip_accurate(mut self, chip_accurate: bool) -> Self {
+        self.config.chip_accurate = chip_accurate;
+        self
+    }
+
+    /// Chips per big blind used when `chip_accurate` is set
+    pub fn big_blind_size(mut self, big_blind_size: u32) -> Self {
+        self.config.big_blind_size = big_blind_size;
+        self
+    }
+
+    /// Key villain decisions by this many hand-strength buckets; `None` (the default) clears it
+    pub fn villain_buckets(mut self, villain_buckets: impl Into<Option<u8>>) -> Self {
+        self.config.villain_buckets = villain_buckets.into();
+        self
+    }
+
+    /// Hero range that villain hand strength is measured against; `None` (the default) clears it
+    pub fn hero_range(mut self, hero_range: impl Into<Option<Range>>) -> Self {
+        self.config.hero_range = hero_range.into();
+        self
+    }
+
+    /// Time sampling, showdown evaluation and regret updates
+    pub fn profile(mut self, profile: bool) -> Self {
+        self.config.profile = profile;
+        self
+    }
+
+    /// Report the last regret-matched strategy instead of the average
+    pub fn return_current_strategy(mut self, return_current_strategy: bool) -> Self {
+        self.config.return_current_strategy = return_current_strategy;
+        self
+    }
+
+    /// Bet sizes hero chooses from when not facing a bet; `None` (the default) clears it
+    pub fn hero_bet_sizes(mut self, hero_bet_sizes: impl Into<Option<Vec<BetSize>>>) -> Self {
+        self.config.hero_bet_sizes = hero_bet_sizes.into();
+        self
+    }
+
+    /// Bet sizes villain chooses from when not facing a bet; `None` (the default) clears it
+    pub fn villain_bet_sizes(mut self, villain_bet_sizes: impl Into<Option<Vec<BetSize>>>) -> Self {
+        self.config.villain_bet_sizes = villain_bet_sizes.into();
+        self
+    }
+
+    /// Bet sizes by street for players without their own; `None` (the default) clears it
+    pub fn street_bet_sizes(mut self, street_bet_sizes: impl Into<Option<StreetBetSizes>>) -> Self {
+        self.config.street_bet_sizes = street_bet_sizes.into();
+        self
+    }
+
+    /// Solve against at most this many villain combos; `None` (the default) clears it
+    pub fn max_villain_combos(mut self, max_villain_combos: impl Into<Option<usize>>) -> Self {
+        self.config.max_villain_combos = max_villain_combos.into();
+        self
+    }
+
+    /// Decisions allowed along any line before a forced showdown; `None` (the default) clears it
+    pub fn max_depth(mut self, max_depth: impl Into<Option<u32>>) -> Self {
+        self.config.max_depth = max_depth.into();
+        self
+    }
+
+    /// Raises allowed on a street below the root (default 0)
+    pub fn max_raises(mut self, max_raises: u32) -> Self {
+        self.config.max_raises = max_raises;
+        self
+    }
+
+    /// Rake taken from the pot before it is awarded; `None` (the default) clears it
+    pub fn rake(mut self, rake: impl Into<Option<Rake>>) -> Self {
+        self.config.rake = rake.into();
+        self
+    }
+
+    /// In a preflop spot, deal a flop and keep playing on it
+    pub fn deal_flop(mut self, deal_flop: bool) -> Self {
+        self.config.deal_flop = deal_flop;
+        self
+    }
+
+    /// Regrets from a solve of a similar spot to start from; `None` (the default) clears it
+    pub fn warm_start(mut self, warm_start: impl Into<Option<Arc<RegretTable>>>) -> Self {
+        self.config.warm_start = warm_start.into();
+        self
+    }
+
+    /// Record the convergence metric in `Strategy::convergence_history`
+    pub fn record_convergence(mut self, record_convergence: bool) -> Self {
+        self.config.record_convergence = record_convergence;
+        self
+    }
+
+    /// Stop before `iterations` once this criterion is met; `None` (the default) clears it
+    pub fn stop_when(mut self, stop_when: impl Into<Option<StopWhen>>) -> Self {
+        self.config.stop_when = stop_when.into();
+        self
+    }
+
+    /// Tournament model scoring terminal payoffs; `None` (the default) clears it
+    pub fn icm(mut self, icm: impl Into<Option<IcmModel>>) -> Self {
+        self.config.icm = icm.into();
+        self
+    }
+
+    /// Flag that stops the solve before its next iteration; `None` (the default) clears it
+    pub fn stop(mut self, stop: impl Into<Option<Arc<AtomicBool>>>) -> Self {
+        self.config.stop = stop.into();
+        self
+    }
+
+    /// Villain range size below which every combo is walked each iteration
+    pub fn enumerate_villain_below(mut self, enumerate_villain_below: usize) -> Self {
+        self.config.enumerate_villain_below = enumerate_villain_below;
+        self
+    }
+
+    /// How strategy changes are summarized into the convergence number
+    pub fn convergence_metric(mut self, convergence_metric: ConvergenceMetric) -> Self {
+        self.config.convergence_metric = convergence_metric;
+        self
+    }
+
+    /// The finished configuration
+    pub fn build(self) -> MccfrConfig {
+        self.config
+    }
+}
+
+/// Rake charged on every pot, as in cash games.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rake {
+    /// Share of the pot taken, in percent (5.0 is 5%)
+    pub percent: f64,
+    /// Most rake taken from a single pot, in big blinds
+    pub cap: f64,
+    /// Take no rake from pots won before the flop ("no flop, no drop")
+    pub no_flop_no_drop: bool,
+}
+
+impl Rake {
+    /// Rake taken from `pot` when the hand ends on `street`
+    pub fn taken(&self, pot: f64, street: Street) -> f64 {
+        if self.no_flop_no_drop && street == Street::Preflop {
+            0.0
+        } else {
+            (pot * self.percent / 100.0).clamp(0.0, self.cap.max(0.0))
+        }
+    }
+}
+
+/// How `ConvergenceTracker` summarizes the strategy change at every info
+/// set into one number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConvergenceMetric {
+    /// Largest change of any action frequency at any info set
+    #[default]
+    MaxChange,
+    /// Largest change with each info set's change scaled by its visits
+    /// relative to the most visited info set, so noise at rarely reached
+    /// nodes barely counts while the root keeps full weight
+    ReachWeighted,
+}
+
+/// When to end a solve early.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum StopWhen {
+    /// Stop once the estimated standard error of the root's most frequent
+    /// action's average frequency is below this value.
+    ///
+    /// The error is estimated by batch means: the stretch of iterations
+    /// between two checks is one batch, and the spread of the batches'
+    /// frequencies gives the error of their mean. At least
+    /// `StdErrEstimator::MIN_BATCHES` batches are needed.
+    StdErr(f64),
+}
+
+/// Running standard error of the root strategy, see `StopWhen::StdErr`
+#[derive(Debug, Clone)]
+pub struct StdErrEstimator {
+    key: InfoSetKey,
+    /// Root strategy sums at the previous check
+    last_sums: Vec<f64>,
+    /// Average root strategy within each batch
+    batches: Vec<Vec<f64>>,
+}
+
+impl StdErrEstimator {
+    /// Batches needed before a standard error is reported
+    pub const MIN_BATCHES: usize = 3;
+
+    /// Start measuring `key` from the sums already in `regret_table`
+    pub fn new(key: InfoSetKey, regret_table: &RegretTable) -> Self {
+        let last_sums = regret_table
+            .get_strategy_sum(&key)
+            .map(|sums| sums.to_vec())
+            .unwrap_or_default();
+        Self {
+            key,
+            last_sums,
+            batches: Vec::new(),
+        }
+    }
+
+    /// Close the batch ending now and return the standard error of the most
+    /// frequent action's frequency, `None` until there are enough batches
+    pub fn check(&mut self, regret_table: &RegretTable) -> Option<f64> {
+        let sums = regret_table.get_strategy_sum(&self.key)?;
+        if self.last_sums.len() != sums.len() {
+            self.last_sums = vec![0.0; sums.len()];
+        }
+        let batch: Vec<f64> = sums
+            .iter()
+            .zip(&self.last_sums)
+            .map(|(now, before)| now - before)
+            .collect();
+        self.last_sums = sums.to_vec();
+        let total: f64 = batch.iter().sum();
+        if total > 0.0 {
+            self.batches
+                .push(batch.iter().map(|weight| weight / total).collect());
+        }
+        if self.batches.len() < Self::MIN_BATCHES {
+            return None;
+        }
+
+        let average = regret_table.get_average_strategy(&self.key)?;
+        let top = (0..average.len()).max_by(|&a, &b| average[a].total_cmp(&average[b]))?;
+        let frequencies: Vec<f64> = self.batches.iter().map(|batch| batch[top]).collect();
+        let n = frequencies.len() as f64;
+        let mean = frequencies.iter().sum::<f64>() / n;
+        let variance = frequencies.iter().map(|f| (f - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        Some((variance / n).sqrt())
+    }
+}
+
+/// Number of villain samples drawn per iteration as the solve progresses.
+///
+/// Early iterations work on a noisy strategy, so spending fewer samples there
+/// and more near the end gives a more stable final strategy for the same
+/// total budget.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSchedule {
+    /// Same number of samples every iteration
+    Constant(usize),
+    /// Interpolate linearly from `start` on the first iteration to `end` on the last
+    Linear {
+        /// Samples on the first iteration
+        start: usize,
+        /// Samples on the last iteration
+        end: usize,
+    },
+}
+
+impl SampleSchedule {
+    /// Samples for `iteration` out of `total` iterations (always at least 1).
+    pub fn samples_at(&self, iteration: u32, total: u32) -> usize {
+        let samples = match *self {
+            SampleSchedule::Constant(n) => n,
+            SampleSchedule::Linear { start, end } => {
+                let progress = if total > 1 {
+                    iteration.min(total - 1) as f64 / (total - 1) as f64
+                } else {
+                    1.0
+                };
+                let interpolated = start as f64 + (end as f64 - start as f64) * progress;
+                interpolated.round() as usize
+            }
+        };
+        samples.max(1)
+    }
+}
+
+/// Where a solve spent its time.
+///
+/// The three phases do not add up to `total`; tree walking and convergence
+/// checks make up the rest.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SolveProfile {
+    /// Drawing villain hands from the range
+    pub sampling: Duration,
+    /// Computing showdown equity for newly sampled villain hands
+    pub evaluation: Duration,
+    /// Regret matching, regret updates and action value bookkeeping
+    pub regret_updates: Duration,
+    /// Wall time of the whole solve
+    pub total: Duration,
+}
+
+impl fmt::Display for SolveProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let share = |d: Duration| {
+            if self.total.is_zero() {
+                0.0
+            } else {
+                d.as_secs_f64() / self.total.as_secs_f64() * 100.0
+            }
+        };
+        writeln!(f, "Solve profile ({:.3}s total):", self.total.as_secs_f64())?;
+        for (name, d) in [
+            ("sampling", self.sampling),
+            ("evaluation", self.evaluation),
+            ("regret updates", self.regret_updates),
+        ] {
+            writeln!(
+                f,
+                "  {:<15} {:>8.3}s ({:.1}%)",
+                name,
+                d.as_secs_f64(),
+                share(d)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Running totals behind `SolveProfile`, shared with the tree walk
+#[derive(Default)]
+pub(crate) struct Timings {
+    sampling: Cell<Duration>,
+    evaluation: Cell<Duration>,
+    regret_updates: Cell<Duration>,
+}
+
+/// Run `f`, adding its duration to the phase `phase` picks when profiling
+fn timed<T>(
+    timings: Option<&Timings>,
+    phase: fn(&Timings) -> &Cell<Duration>,
+    f: impl FnOnce() -> T,
+) -> T {
+    match timings {
+        None => f(),
+        Some(timings) => {
+            let start = Instant::now();
+            let result = f();
+            let cell = phase(timings);
+            cell.set(cell.get() + start.elapsed());
+            result
         }
     }
 }
@@ -46,6 +604,8 @@ pub struct ConvergenceTracker {
     max_change: f64,
     /// Iterations since last check
     iterations_since_check: u32,
+    /// How changes are summarized
+    metric: ConvergenceMetric,
 }
 
 impl Default for ConvergenceTracker {
@@ -57,24 +617,45 @@ impl Default for ConvergenceTracker {
 impl ConvergenceTracker {
     /// Create a new convergence tracker.
     pub fn new() -> Self {
+        Self::with_metric(ConvergenceMetric::MaxChange)
+    }
+
+    /// Create a convergence tracker summarizing changes by `metric`.
+    pub fn with_metric(metric: ConvergenceMetric) -> Self {
         Self {
             prev_strategies: FxHashMap::default(),
             max_change: f64::MAX, // Start high (not converged)
             iterations_since_check: 0,
+            metric,
         }
     }
 
     /// Check convergence by comparing current average strategy to previous check.
-    /// Returns the maximum strategy change across all info sets.
+    /// Returns the maximum strategy change across all info sets, weighted
+    /// as the tracker's `ConvergenceMetric` says.
     pub fn check_convergence(&mut self, regret_table: &RegretTable) -> f64 {
         let mut max_diff = 0.0;
+        let most_visits = match self.metric {
+            ConvergenceMetric::MaxChange => 0,
+            ConvergenceMetric::ReachWeighted => regret_table
+                .keys()
+                .map(|key| regret_table.visits(key))
+                .max()
+                .unwrap_or(0),
+        };
 
         for key in regret_table.keys() {
             if let Some(current_strategy) = regret_table.get_average_strategy(key) {
                 if let Some(prev_strategy) = self.prev_strategies.get(key) {
+                    let weight = match self.metric {
+                        ConvergenceMetric::MaxChange => 1.0,
+                        ConvergenceMetric::ReachWeighted => {
+                            regret_table.visits(key) as f64 / most_visits.max(1) as f64
+                        }
+                    };
                     // Calculate diff
                     for (s1, s2) in current_strategy.iter().zip(prev_strategy.iter()) {
-                        let diff = (s1 - s2).abs();
+                        let diff = weight * (s1 - s2).abs();
                         if diff > max_diff {
                             max_diff = diff;
                         }
@@ -114,14 +695,14 @@ impl McSampler {
 
     /// Sample a single hand from the range based on weights.
     pub fn sample_hand(&mut self, range: &Range) -> Option<(Hand, f64)> {
-        if range.hands.is_empty() {
+        if range.is_empty() {
             return None;
         }
 
-        let total_weight: f64 = range.hands.values().sum();
+        let total_weight: f64 = range.hands().map(|(_, weight)| weight).sum();
         let mut r = self.rng.gen::<f64>() * total_weight;
 
-        for (hand, &weight) in &range.hands {
+        for (hand, weight) in range {
             r -= weight;
             if r <= 0.0 {
                 return Some((hand.clone(), weight));
@@ -129,90 +710,412 @@ impl McSampler {
         }
 
         // Fallback to any hand if rounding errors
-        range.hands.iter().next().map(|(h, &w)| (h.clone(), w))
+        range.hands().next().map(|(h, w)| (h.clone(), w))
+    }
+
+    /// Deal three random cards not held by either player, on the board or dead
+    pub fn sample_flop(&mut self, state: &GameState, villain_hand: &Hand) -> [Card; 3] {
+        let mut deck: Vec<Card> = (0..52)
+            .filter_map(|i| Card::from_pokers_index(i).ok())
+            .filter(|c| {
+                !state.board.contains(c)
+                    && !state.dead_cards.contains(c)
+                    && !state.hero_hand.contains_card(c)
+                    && !villain_hand.contains_card(c)
+            })
+            .collect();
+        let (flop, _) = deck.partial_shuffle(&mut self.rng, 3);
+        [flop[0], flop[1], flop[2]]
+    }
+
+    /// Pick `max_combos` combos of `range` uniformly at random.
+    ///
+    /// Every combo is equally likely to be kept, so the kept weights still
+    /// describe the range; they are scaled up so the total weight matches
+    /// the full range. The range yields its combos in a fixed order, so the
+    /// subset only depends on the sampler's seed.
+    pub fn subsample_range(&mut self, range: &Range, max_combos: usize) -> Range {
+        let mut combos: Vec<(&Hand, f64)> = range.hands().collect();
+        if combos.len() <= max_combos {
+            return range.clone();
+        }
+
+        let total: f64 = combos.iter().map(|(_, w)| w).sum();
+        let (kept, _) = combos.partial_shuffle(&mut self.rng, max_combos);
+        let kept_total: f64 = kept.iter().map(|(_, w)| w).sum();
+        let scale = if kept_total > 0.0 {
+            total / kept_total
+        } else {
+            1.0
+        };
+
+        let mut subset = Range::new();
+        for (hand, weight) in kept.iter() {
+            subset.add((*hand).clone(), weight * scale);
+        }
+        subset
+    }
+
+    /// Draw `n` distinct combos of `range`, each draw picking among the
+    /// combos left with probability proportional to weight.
+    ///
+    /// Kept combos keep their weights. Combos of zero weight are never
+    /// drawn, so fewer than `n` come back if fewer have positive weight.
+    /// The range yields its combos in a fixed order, so the draw only
+    /// depends on the sampler's seed.
+    pub fn sample_combos(&mut self, range: &Range, n: usize) -> Range {
+        let combos: Vec<(&Hand, f64)> = range.hands().filter(|(_, w)| *w > 0.0).collect();
+
+        // Efraimidis-Spirakis: the n largest keys u^(1/w) are a weighted
+        // draw without replacement
+        let mut keyed: Vec<(f64, &Hand, f64)> = combos
+            .into_iter()
+            .map(|(hand, weight)| (self.rng.gen::<f64>().powf(1.0 / weight), hand, weight))
+            .collect();
+        keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut sample = Range::new();
+        for (_, hand, weight) in keyed.into_iter().take(n) {
+            sample.add(hand.clone(), weight);
+        }
+        sample
     }
 }
 
+impl Range {
+    /// Reproducible weighted draw of `n` distinct combos (see
+    /// `McSampler::sample_combos`), e.g. to approximate a wide range cheaply
+    pub fn sample(&self, n: usize, seed: u64) -> Range {
+        McSampler::new(Some(seed)).sample_combos(self, n)
+    }
+}
+
+/// A player's own sizes if set, else the sizes for `street`
+fn sizes_on<'a>(
+    own: Option<&'a [BetSize]>,
+    by_street: Option<&'a StreetBetSizes>,
+    street: Street,
+) -> Option<&'a [BetSize]> {
+    own.or_else(|| by_street.and_then(|sizes| sizes.on(street)))
+}
+
+/// Actions at a node: the state's own list if set, or the default abstraction of
+/// `determine_available_actions` facing a bet and check/half-pot bet
+/// otherwise.
+///
+/// `bet_sizes` replaces the default bets or raises with one per size; sizes
+/// that would commit the whole stack collapse into a single all-in. Without
+/// `can_raise`, a player facing a bet can only fold or call.
+pub(crate) fn node_actions(
+    state: &GameState,
+    bet_sizes: Option<&[BetSize]>,
+    can_raise: bool,
+) -> Vec<Action> {
+    if let Some(actions) = &state.available_actions {
+        return actions.clone();
+    }
+    let facing_bet = state.to_call > 0.0;
+    if facing_bet && !can_raise {
+        return vec![Action::Fold, Action::Call];
+    }
+    let Some(sizes) = bet_sizes else {
+        return if facing_bet {
+            determine_available_actions(state)
+        } else {
+            vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]
+        };
+    };
+
+    let (mut actions, sized): (Vec<Action>, fn(BetSize) -> Action) = if facing_bet {
+        (vec![Action::Fold, Action::Call], Action::Raise)
+    } else {
+        (vec![Action::Check], Action::Bet)
+    };
+    if state.effective_stack > state.to_call {
+        // A multiplier sizes a raise off the bet faced; with no bet there is
+        // nothing to multiply, so it offers no bet
+        let sizes = sizes
+            .iter()
+            .filter(|size| facing_bet || !matches!(size, BetSize::Multiplier(_)));
+        for size in sizes {
+            let action =
+                sized(*size).normalize(state.pot_size, state.effective_stack, state.to_call);
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+    }
+    actions
+}
+
 /// Apply an action to a game state to get the next state.
-/// Returns None if the action results in a terminal state (Fold, Showdown).
-/// Returns (NewState, IsTerminal, PayoffForActor)
-/// Payoff is only relevant if IsTerminal is true.
-fn apply_action(state: &GameState, action: &Action) -> (Option<GameState>, bool, f64) {
+/// Returns (NextState, IsTerminal).
+/// Terminal states (fold, showdown) still carry the final pot so payoffs can
+/// be computed from them.
+pub(crate) fn apply_action(state: &GameState, action: &Action) -> (GameState, bool) {
     let mut next = state.clone();
-    next.available_actions.clear(); // Clear actions for the next state
+    next.available_actions = None; // Clear actions for the next state
 
     match action {
-        Action::Fold => (None, true, 0.0),
+        Action::Fold => (next, true),
         Action::Check => {
-            if state.position == Position::OOP {
-                next.position = Position::IP;
-                (Some(next), false, 0.0)
+            if state.position.acts_first() {
+                next.position = state.position.other();
+                (next, false)
             } else {
-                (None, true, 0.0)
+                (next, true)
             }
         }
         Action::Call => {
-            next.pot_size += state.to_call;
+            next.commit(state.to_call);
             next.effective_stack -= state.to_call;
             next.to_call = 0.0;
-            (None, true, 0.0)
+            (next, true)
         }
         Action::Bet(_) | Action::Raise(_) | Action::AllIn => {
             let amount = action.amount(state.pot_size, state.effective_stack, state.to_call);
-            next.pot_size += amount;
-            next.effective_stack -= amount;
-            next.to_call = amount;
-            next.position = if state.position == Position::IP {
-                Position::OOP
+            next.commit(amount);
+            // The opponent only owes the part beyond what it already put in,
+            // and can commit no more than this player had left after calling
+            next.to_call = amount - state.to_call;
+            next.effective_stack = state.effective_stack - state.to_call;
+            next.position = state.position.other();
+            if state.to_call > 0.0 {
+                next.street_raises += 1;
+            }
+            (next, false)
+        }
+    }
+}
+
+/// Inputs that stay fixed while walking the tree for one sampled deal.
+#[derive(Clone)]
+pub(crate) struct Traversal<'a> {
+    /// Player whose regrets are updated on this pass
+    pub(crate) traverser: Position,
+    /// Seat holding `hero_hand`
+    pub(crate) hero_position: Position,
+    pub(crate) hero_hand: &'a Hand,
+    pub(crate) villain_hand: &'a Hand,
+    /// Hero's showdown equity against `villain_hand`, over all runouts
+    pub(crate) equity: Equity,
+    /// Chips per big blind when tied pots are split in whole chips
+    pub(crate) chips_per_bb: Option<u32>,
+    /// Strength bucket keying villain's decisions, if abstracted
+    pub(crate) villain_bucket: Option<u8>,
+    /// Phase timings, when profiling
+    pub(crate) timings: Option<&'a Timings>,
+    /// Hero's bet sizes, if not the default abstraction
+    pub(crate) hero_bet_sizes: Option<&'a [BetSize]>,
+    /// Villain's bet sizes, if not the default abstraction
+    pub(crate) villain_bet_sizes: Option<&'a [BetSize]>,
+    /// Sizes by street for a player without sizes of their own
+    pub(crate) street_bet_sizes: Option<&'a StreetBetSizes>,
+    /// Depth at which nodes are forced to showdown
+    pub(crate) max_depth: Option<u32>,
+    /// Raises allowed on a street before facing a bet only folds or calls
+    pub(crate) max_raises: u32,
+    /// Rake taken from the pot before it is awarded
+    pub(crate) rake: Option<Rake>,
+    /// When set, closing preflop action deals a flop, whose showdowns are
+    /// scored through this cache
+    pub(crate) flop_eval_cache: Option<&'a SharedEvalCache>,
+    /// Tournament payoffs, if not chip-linear
+    pub(crate) icm: Option<&'a IcmPayoff<'a>>,
+    /// Share of the iteration this villain hand stands for: 1 when
+    /// sampled, its share of the range's weight when enumerated
+    pub(crate) weight: f64,
+}
+
+impl<'a> Traversal<'a> {
+    pub(crate) fn new(
+        traverser: Position,
+        hero_position: Position,
+        hero_hand: &'a Hand,
+        villain_hand: &'a Hand,
+        equity: Equity,
+    ) -> Self {
+        Self {
+            traverser,
+            hero_position,
+            hero_hand,
+            villain_hand,
+            equity,
+            chips_per_bb: None,
+            villain_bucket: None,
+            timings: None,
+            hero_bet_sizes: None,
+            villain_bet_sizes: None,
+            street_bet_sizes: None,
+            max_depth: None,
+            max_raises: 0,
+            rake: None,
+            flop_eval_cache: None,
+            icm: None,
+            weight: 1.0,
+        }
+    }
+
+    /// Traverser's utility for ending the hand `chips` up (net, relative to
+    /// the root)
+    fn payoff(&self, chips: f64) -> f64 {
+        match self.icm {
+            Some(icm) => icm.utility(self.traverser == self.hero_position, chips),
+            None => chips,
+        }
+    }
+
+    /// Pot left for the winner once rake is taken
+    fn awarded_pot(&self, state: &GameState) -> f64 {
+        let rake = self
+            .rake
+            .map_or(0.0, |rake| rake.taken(state.pot_size, state.street));
+        state.pot_size - rake
+    }
+
+    /// Actions for the player to act at `state`, using that player's sizes
+    /// or else the street's
+    pub(crate) fn actions(&self, state: &GameState) -> Vec<Action> {
+        let own = if state.position == self.hero_position {
+            self.hero_bet_sizes
+        } else {
+            self.villain_bet_sizes
+        };
+        node_actions(
+            state,
+            sizes_on(own, self.street_bet_sizes, state.street),
+            state.street_raises < self.max_raises,
+        )
+    }
+}
+
+/// Hero's showdown equity against one villain hand.
+///
+/// Showdowns before the river realize equity over the remaining cards rather
+/// than freezing current made-hand strength.
+pub(crate) fn showdown_equity(
+    hero_hand: &Hand,
+    villain_hand: &Hand,
+    board: &[Card],
+    dead: &[Card],
+    eval_cache: &SharedEvalCache,
+) -> Equity {
+    calculate_hand_equity_runout_with(hero_hand, villain_hand, board, dead, |h, b| {
+        eval_cache.evaluate(h, b)
+    })
+}
+
+/// Share of a tied pot going to the player at `position`.
+///
+/// Without `chips_per_bb` the pot is split exactly in half. With it, the pot
+/// is counted in whole chips and an odd chip goes to the out-of-position
+/// player, who is first to act after the flop.
+fn split_pot_share(pot: f64, position: Position, chips_per_bb: Option<u32>) -> f64 {
+    match chips_per_bb {
+        None => pot / 2.0,
+        Some(bb) => {
+            let chips = (pot * bb as f64).round() as u64;
+            let half = chips / 2;
+            let share = if position.acts_first() {
+                chips - half
             } else {
-                Position::IP
+                half
             };
-            (Some(next), false, 0.0)
+            share as f64 / bb as f64
+        }
+    }
+}
+
+/// Utility for the traverser once `action` by `state.position` ends the hand.
+///
+/// Utilities are net chips relative to the root decision: the traverser's
+/// share of the final pot minus what it put in since the root (`invested`,
+/// already including this action). Folding therefore loses exactly the chips
+/// invested so far instead of looking free.
+pub(crate) fn terminal_utility(
+    state: &GameState,
+    action: &Action,
+    next: &GameState,
+    t: &Traversal,
+    invested: f64,
+) -> f64 {
+    if matches!(action, Action::Fold) {
+        if state.position == t.traverser {
+            t.payoff(-invested)
+        } else {
+            t.payoff(t.awarded_pot(next) - invested)
         }
+    } else {
+        showdown_utility(next, t, invested)
     }
 }
 
 /// Recursive MCCFR traversal.
 /// Returns the utility for the *traverser*.
+///
+/// `state.position` is the player to act at this node, `depth` the number
+/// of decisions made since the root, and `invested` what the traverser has
+/// put into the pot since the root.
 fn traverse(
     state: &GameState,
-    traverser: Position,
-    hero_hand: &Hand,
-    villain_hand: &Hand,
+    t: &Traversal,
+    depth: u32,
+    invested: f64,
     regret_table: &mut RegretTable,
     sampler: &mut McSampler,
-) -> f64 {
+) -> crate::Result<f64> {
     // Determine whose turn it is
     let actor = state.position;
-    let is_traverser = actor == traverser;
+    let is_traverser = actor == t.traverser;
 
-    let actor_hand = if is_traverser {
-        hero_hand
+    // Past the depth limit the player to act calls and the hand goes to showdown
+    if t.max_depth.is_some_and(|max| depth >= max) {
+        let (next, _) = apply_action(state, &Action::Call);
+        let invested = if is_traverser {
+            invested + (next.pot_size - state.pot_size)
+        } else {
+            invested
+        };
+        return Ok(showdown_utility(&next, t, invested));
+    }
+
+    let actor_hand = if actor == t.hero_position {
+        t.hero_hand
     } else {
-        villain_hand
+        t.villain_hand
     };
 
     // Get available actions
-    let actions = if state.available_actions.is_empty() {
-        if state.to_call > 0.0 {
-            vec![Action::Fold, Action::Call]
-        } else {
-            vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]
-        }
-    } else {
-        state.available_actions.clone()
-    };
+    let actions = t.actions(state);
 
     if actions.is_empty() {
-        return evaluate_showdown(state, hero_hand, villain_hand, traverser);
+        return Ok(showdown_utility(state, t, invested));
     }
 
     // Get Strategy
-    let mut state_for_key = state.clone();
-    state_for_key.hero_hand = actor_hand.clone();
-    let key = InfoSetKey::from_game_state(&state_for_key);
+    let key = match t.villain_bucket {
+        Some(bucket) if actor != t.hero_position => InfoSetKey::from_bucket(state, bucket),
+        _ => {
+            let mut state_for_key = state.clone();
+            state_for_key.hero_hand = actor_hand.clone();
+            InfoSetKey::from_game_state(&state_for_key)
+        }
+    };
+
+    let strategy = timed(
+        t.timings,
+        |t| &t.regret_updates,
+        || regret_table.get_strategy(&key, actions.len()),
+    );
 
-    let strategy = regret_table.get_strategy(&key, actions.len());
+    // Chips the traverser has in after `next`
+    let invested_after = |next: &GameState| {
+        if is_traverser {
+            invested + (next.pot_size - state.pot_size)
+        } else {
+            invested
+        }
+    };
 
     if is_traverser {
         // Traverser: Iterate all actions
@@ -220,36 +1123,36 @@ fn traverse(
         let mut action_utils = vec![0.0; actions.len()];
 
         for (i, action) in actions.iter().enumerate() {
-            let (next_state_opt, is_terminal, payoff) = apply_action(state, action);
-
-            let util = if is_terminal {
-                if payoff != 0.0 {
-                    payoff // Fold payoff
-                } else {
-                    evaluate_showdown(state, hero_hand, villain_hand, traverser)
-                }
-            } else if let Some(next) = next_state_opt {
-                traverse(
-                    &next,
-                    traverser,
-                    hero_hand,
-                    villain_hand,
-                    regret_table,
-                    sampler,
-                )
-            } else {
-                0.0
+            let (next, is_terminal) = apply_action(state, action);
+            let step = Step {
+                state,
+                action,
+                next: &next,
+                is_terminal,
+                depth,
+                invested: invested_after(&next),
             };
+            let util = continue_after(&step, t, regret_table, sampler)?;
 
             action_utils[i] = util;
             node_util += strategy[i] * util;
         }
 
         // Update Regrets
-        let regrets: Vec<f64> = action_utils.iter().map(|u| u - node_util).collect();
-        regret_table.update_regrets(key, &regrets, 1.0);
+        timed(
+            t.timings,
+            |t| &t.regret_updates,
+            || {
+                let regrets: Vec<f64> = action_utils
+                    .iter()
+                    .map(|u| t.weight * (u - node_util))
+                    .collect();
+                regret_table.record_weighted_action_values(&key, &action_utils, t.weight);
+                regret_table.update_regrets(key, &regrets, t.weight);
+            },
+        );
 
-        node_util
+        Ok(node_util)
     } else {
         // Opponent: Sample one action
         let mut r = sampler.rng.gen::<f64>();
@@ -266,90 +1169,450 @@ fn traverse(
         }
 
         let action = &actions[chosen_idx];
-        let (next_state_opt, is_terminal, payoff) = apply_action(state, action);
+        let (next, is_terminal) = apply_action(state, action);
+        let step = Step {
+            state,
+            action,
+            next: &next,
+            is_terminal,
+            depth,
+            invested: invested_after(&next),
+        };
+        continue_after(&step, t, regret_table, sampler)
+    }
+}
 
-        if is_terminal {
-            if payoff != 0.0 {
-                if matches!(action, Action::Fold) {
-                    state.pot_size // Traverser wins pot
-                } else {
-                    evaluate_showdown(state, hero_hand, villain_hand, traverser)
-                }
-            } else {
-                evaluate_showdown(state, hero_hand, villain_hand, traverser)
-            }
-        } else if let Some(next) = next_state_opt {
+/// One action taken during a traversal: `action` at `state` led to `next`
+struct Step<'s> {
+    state: &'s GameState,
+    action: &'s Action,
+    next: &'s GameState,
+    /// Whether `action` ended the betting
+    is_terminal: bool,
+    /// Depth of `state` in the tree
+    depth: u32,
+    /// Chips the traverser has in after `next`
+    invested: f64,
+}
+
+/// Traverser's utility once `step` has been taken: the terminal payoff, or
+/// the value of the subtree below.
+///
+/// With `t.flop_eval_cache` set, a preflop hand that would go to showdown
+/// with chips behind continues on a sampled flop instead.
+fn continue_after(
+    step: &Step,
+    t: &Traversal,
+    regret_table: &mut RegretTable,
+    sampler: &mut McSampler,
+) -> crate::Result<f64> {
+    let Step {
+        state,
+        action,
+        next,
+        is_terminal,
+        depth,
+        invested,
+    } = *step;
+    if !is_terminal {
+        return traverse(next, t, depth + 1, invested, regret_table, sampler);
+    }
+
+    match t.flop_eval_cache {
+        Some(eval_cache)
+            if state.street == Street::Preflop
+                && !matches!(action, Action::Fold)
+                && next.effective_stack > 0.0 =>
+        {
+            let mut flop_state = next.clone();
+            flop_state.deal_flop(sampler.sample_flop(next, t.villain_hand))?;
+            let equity = timed(
+                t.timings,
+                |t| &t.evaluation,
+                || {
+                    showdown_equity(
+                        t.hero_hand,
+                        t.villain_hand,
+                        &flop_state.board,
+                        &flop_state.dead_cards,
+                        eval_cache,
+                    )
+                },
+            );
+            let flop_t = Traversal {
+                equity,
+                ..t.clone()
+            };
             traverse(
-                &next,
-                traverser,
-                hero_hand,
-                villain_hand,
+                &flop_state,
+                &flop_t,
+                depth + 1,
+                invested,
                 regret_table,
                 sampler,
             )
-        } else {
-            0.0
         }
+        _ => Ok(terminal_utility(state, action, next, t, invested)),
     }
 }
 
-fn evaluate_showdown(
-    state: &GameState,
-    hero_hand: &Hand,
-    villain_hand: &Hand,
-    traverser: Position,
-) -> f64 {
-    let t_score = evaluate_hand(hero_hand, &state.board);
-    let o_score = evaluate_hand(villain_hand, &state.board);
+/// Showdown utility for the traverser.
+///
+/// Showdowns before the river, such as a called flop all-in, pay out the
+/// expected share over the remaining runouts (see `showdown_equity`).
+/// Attribution depends only on which seat holds hero's hand, never on whose
+/// turn it was when the showdown was reached.
+fn evaluate_showdown(state: &GameState, t: &Traversal) -> f64 {
+    let pot = t.awarded_pot(state);
+    let (position, win) = if t.traverser == t.hero_position {
+        (t.hero_position, t.equity.win)
+    } else {
+        (t.hero_position.other(), t.equity.lose)
+    };
+    pot * win + t.equity.tie * split_pot_share(pot, position, t.chips_per_bb)
+}
 
-    let hero_is_traverser = if state.position == Position::IP {
-        traverser == Position::IP
+/// Traverser's utility for a showdown with `invested` chips put in since
+/// the root.
+///
+/// Chip utilities are linear, so the expected share of the pot is enough.
+/// ICM payoffs aren't, so winning, tying and losing are scored separately
+/// and weighted by their probabilities.
+fn showdown_utility(state: &GameState, t: &Traversal, invested: f64) -> f64 {
+    if t.icm.is_none() {
+        return evaluate_showdown(state, t) - invested;
+    }
+    let pot = t.awarded_pot(state);
+    let (position, win, lose) = if t.traverser == t.hero_position {
+        (t.hero_position, t.equity.win, t.equity.lose)
     } else {
-        traverser == Position::OOP
+        (t.hero_position.other(), t.equity.lose, t.equity.win)
     };
+    let tie_share = split_pot_share(pot, position, t.chips_per_bb);
+    win * t.payoff(pot - invested)
+        + t.equity.tie * t.payoff(tie_share - invested)
+        + lose * t.payoff(-invested)
+}
 
-    if t_score > o_score {
-        // Hero wins
-        if hero_is_traverser {
-            state.pot_size
-        } else {
-            0.0
+/// Converts the traverser's net chips into its change in prize equity for
+/// one solve.
+///
+/// At the root the pot belongs to neither player, so ending the hand `u`
+/// chips up leaves the traverser with its stack plus `u` and the opponent
+/// with its stack plus the root pot less `u`. Utilities are relative to
+/// `u = 0`, mirroring chip utilities. A tree has few distinct payoffs, so
+/// they are memoized.
+pub(crate) struct IcmPayoff<'a> {
+    model: &'a IcmModel,
+    root_pot: f64,
+    memo: RefCell<FxHashMap<(bool, i64), f64>>,
+}
+
+impl<'a> IcmPayoff<'a> {
+    pub(crate) fn new(model: &'a IcmModel, root_pot: f64) -> Self {
+        Self {
+            model,
+            root_pot,
+            memo: RefCell::new(FxHashMap::default()),
         }
-    } else if t_score < o_score {
-        // Villain wins
-        if hero_is_traverser {
-            0.0
-        } else {
-            state.pot_size
+    }
+
+    /// Change in prize equity for hero (or villain, if `!for_hero`) ending
+    /// the hand `chips` up
+    fn utility(&self, for_hero: bool, chips: f64) -> f64 {
+        let key = (for_hero, (chips * 1e6).round() as i64);
+        if let Some(&utility) = self.memo.borrow().get(&key) {
+            return utility;
         }
-    } else {
-        state.pot_size / 2.0
+        let model = self.model;
+        let (seat, other) = if for_hero {
+            (model.hero_seat, model.villain_seat)
+        } else {
+            (model.villain_seat, model.hero_seat)
+        };
+        let equity = |u: f64| {
+            model.equity_with(
+                seat,
+                model.stacks[seat] + u,
+                other,
+                model.stacks[other] + self.root_pot - u,
+            )
+        };
+        let utility = equity(chips) - equity(0.0);
+        self.memo.borrow_mut().insert(key, utility);
+        utility
     }
 }
 
 /// Solve the game state using MCCFR with default configuration.
-pub fn solve_mccfr(state: &GameState, iterations: u32) -> Strategy {
+///
+/// A state the solver rejects, e.g. one offering an illegal action, is an
+/// `InvalidGameState` error.
+pub fn solve_mccfr(state: &GameState, iterations: u32) -> crate::Result<Strategy> {
     let config = MccfrConfig {
         iterations,
         ..Default::default()
     };
-    solve_with_config(state.clone(), config).unwrap() // Unwrap safe as we control inputs
+    solve_with_config(state.clone(), config)
 }
 
 /// Solve with custom configuration.
-pub fn solve_with_config(state: GameState, config: MccfrConfig) -> Result<Strategy, String> {
-    let mut regret_table = RegretTable::new();
-    let mut sampler = McSampler::new(config.seed);
-    let mut convergence_tracker = ConvergenceTracker::new();
+pub fn solve_with_config(state: GameState, config: MccfrConfig) -> crate::Result<Strategy> {
+    solve_with_cache(state, config, &SharedEvalCache::new())
+}
+
+/// Solve with custom configuration, scoring showdowns through a cache that
+/// may be shared with other solves on the same board.
+pub fn solve_with_cache(
+    state: GameState,
+    config: MccfrConfig,
+    eval_cache: &SharedEvalCache,
+) -> crate::Result<Strategy> {
+    solve_profiled(state, config, eval_cache).map(|(strategy, _)| strategy)
+}
 
+/// Solve like `solve_with_cache`, also returning a timing breakdown when
+/// `config.profile` is set.
+pub fn solve_profiled(
+    state: GameState,
+    config: MccfrConfig,
+    eval_cache: &SharedEvalCache,
+) -> crate::Result<(Strategy, Option<SolveProfile>)> {
+    solve_observed(state, config, eval_cache, &mut |_| true)
+}
+
+/// `solve_profiled`, passing a `Snapshot` to `observer` at every
+/// convergence check and once at the end. Returning false from `observer`
+/// stops the solve early; the strategy then reports the iterations run.
+pub fn solve_observed(
+    state: GameState,
+    config: MccfrConfig,
+    eval_cache: &SharedEvalCache,
+    observer: &mut dyn FnMut(Snapshot) -> bool,
+) -> crate::Result<(Strategy, Option<SolveProfile>)> {
+    solve_full(state, config, eval_cache, observer)
+        .map(|(strategy, profile, _)| (strategy, profile))
+}
+
+/// Solve like `solve_with_config`, also returning the regret table so a
+/// similar spot can start from it via `MccfrConfig::warm_start`.
+pub fn solve_with_regrets(
+    state: GameState,
+    config: MccfrConfig,
+) -> crate::Result<(Strategy, RegretTable)> {
+    solve_full(state, config, &SharedEvalCache::new(), &mut |_| true)
+        .map(|(strategy, _, regret_table)| (strategy, regret_table))
+}
+
+/// `solve_observed`, also returning the regret table
+fn solve_full(
+    state: GameState,
+    config: MccfrConfig,
+    eval_cache: &SharedEvalCache,
+    observer: &mut dyn FnMut(Snapshot) -> bool,
+) -> crate::Result<(Strategy, Option<SolveProfile>, RegretTable)> {
+    state.validate_actions()?;
     let mut root = state.clone();
-    if root.available_actions.is_empty() {
-        if root.to_call > 0.0 {
-            root.available_actions = vec![Action::Fold, Action::Call];
-        } else {
-            root.available_actions = vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))];
+    root.available_actions = Some(node_actions(&root, config.hero_sizes_on(root.street), true));
+
+    let (training, subsample) = training_state(&root, &config);
+    let mut history = Vec::new();
+    let mut recording_observer = |snapshot: Snapshot| {
+        if config.record_convergence {
+            history.push((snapshot.iteration, snapshot.convergence));
         }
+        observer(snapshot)
+    };
+    let (regret_table, convergence, profile, iterations) =
+        train(&training, &config, eval_cache, &mut recording_observer)?;
+    // The first check has no earlier strategy to compare against and always
+    // reports 0, so it doesn't belong on the curve
+    if history.len() > 1 {
+        history.remove(0);
+    }
+
+    let mut strategy = extract_strategy(&root, &regret_table, &config, convergence);
+    strategy.iterations = iterations;
+    strategy.convergence_history = history;
+    strategy.provenance = config.provenance("mccfr", iterations);
+    strategy.villain_subsample = subsample;
+    Ok((strategy, profile, regret_table))
+}
+
+/// Solve the rest of the hand after hero opens with `forced`, e.g. to study
+/// how villain should respond to a pot-sized bet.
+///
+/// `forced` must be one of hero's actions at the root (with
+/// `config.hero_bet_sizes`) and must not end the hand. The tree is solved
+/// from the node `forced` leads to, where villain acts; the returned
+/// strategy is villain's there, averaged over villain's range by weight,
+/// with EVs in villain's net chips from that node.
+pub fn solve_with_forced_action(
+    state: GameState,
+    forced: Action,
+    config: MccfrConfig,
+) -> crate::Result<Strategy> {
+    state.validate_actions()?;
+    if !node_actions(&state, config.hero_sizes_on(state.street), true).contains(&forced) {
+        return Err(ModelError::Solver(format!(
+            "{} is not one of hero's actions at the root",
+            forced.display_name()
+        )));
+    }
+    let (mut root, terminal) = apply_action(&state, &forced);
+    if terminal {
+        return Err(ModelError::Solver(format!(
+            "{} ends the hand, leaving nothing to solve",
+            forced.display_name()
+        )));
     }
+    root.available_actions = Some(node_actions(
+        &root,
+        config.villain_sizes_on(root.street),
+        config.can_raise(&root),
+    ));
+
+    let (training, subsample) = training_state(&root, &config);
+    let (regret_table, convergence, _, iterations) = train_as(
+        &training,
+        state.position,
+        &config,
+        &SharedEvalCache::new(),
+        &mut |_| true,
+    )?;
+    let mut strategy = extract_villain_strategy(&training, &regret_table, &config, convergence);
+    strategy.iterations = iterations;
+    strategy.provenance = config.provenance("mccfr", iterations);
+    strategy.villain_subsample = subsample;
+    Ok(strategy)
+}
+
+/// Solve like `solve_with_config`, also returning villain's strategy.
+///
+/// Both players learn in the same solve. Villain's strategy is taken at
+/// villain's first decision on hero's main line: the node reached by hero's
+/// most frequent root action that doesn't end the hand (the earlier action
+/// on a tie), e.g. the response to hero's bet. It is averaged over villain's
+/// range by weight, with EVs in villain's net chips from that node. Fails if
+/// every action at the root ends the hand.
+pub fn solve_both(state: GameState, config: MccfrConfig) -> crate::Result<(Strategy, Strategy)> {
+    let (hero, regret_table) = solve_with_regrets(state, config.clone())?;
+    let root = hero
+        .game_state
+        .as_ref()
+        .expect("MCCFR strategies carry their root state");
+
+    let mut main_line: Option<(f64, GameState)> = None;
+    for action in &hero.actions {
+        let (next, terminal) = apply_action(root, &action.action);
+        let more_frequent = main_line
+            .as_ref()
+            .is_none_or(|(frequency, _)| action.frequency > *frequency);
+        if !terminal && more_frequent {
+            main_line = Some((action.frequency, next));
+        }
+    }
+    let Some((_, mut villain_node)) = main_line else {
+        return Err(ModelError::Solver(
+            "Every action at the root ends the hand, so villain never acts".to_string(),
+        ));
+    };
+    villain_node.available_actions = Some(node_actions(
+        &villain_node,
+        config.villain_sizes_on(villain_node.street),
+        config.can_raise(&villain_node),
+    ));
+
+    let mut villain =
+        extract_villain_strategy(&villain_node, &regret_table, &config, hero.convergence);
+    villain.iterations = hero.iterations;
+    villain.provenance = hero.provenance.clone();
+    villain.villain_subsample = hero.villain_subsample;
+    Ok((hero, villain))
+}
+
+/// `state` with its villain range cut down to `config.max_villain_combos`,
+/// and how many combos were kept if it was
+fn training_state(state: &GameState, config: &MccfrConfig) -> (GameState, Option<Subsample>) {
+    let mut training = state.clone();
+    let mut subsample = None;
+    if let Some(max_combos) = config.max_villain_combos {
+        let combos = state.villain_range.len();
+        if combos > max_combos {
+            training.villain_range =
+                McSampler::new(config.seed).subsample_range(&state.villain_range, max_combos);
+            subsample = Some(Subsample {
+                kept: training.villain_range.len(),
+                total: combos,
+            });
+        }
+    }
+    (training, subsample)
+}
+
+/// Run MCCFR from `state`, returning the regret table, the final strategy
+/// change, where the time went if `config.profile` is set, and the number of
+/// iterations run. Progress goes to `observer` as `solve_observed` describes.
+fn train(
+    state: &GameState,
+    config: &MccfrConfig,
+    eval_cache: &SharedEvalCache,
+    observer: &mut dyn FnMut(Snapshot) -> bool,
+) -> crate::Result<(RegretTable, f64, Option<SolveProfile>, u32)> {
+    train_as(state, state.position, config, eval_cache, observer)
+}
+
+/// `train` with `state.hero_hand` held by the player at `hero_position`,
+/// who needn't be the one to act at `state`
+fn train_as(
+    state: &GameState,
+    hero_position: Position,
+    config: &MccfrConfig,
+    eval_cache: &SharedEvalCache,
+    observer: &mut dyn FnMut(Snapshot) -> bool,
+) -> crate::Result<(RegretTable, f64, Option<SolveProfile>, u32)> {
+    let started = Instant::now();
+    let timings = config.profile.then(Timings::default);
+    let mut regret_table = match &config.warm_start {
+        Some(prior) => RegretTable::warm_started_from(prior),
+        None => RegretTable::new(),
+    };
+    let mut sampler = McSampler::new(config.seed);
+    let mut convergence_tracker = ConvergenceTracker::with_metric(config.convergence_metric);
+    let icm = config
+        .icm
+        .as_ref()
+        .map(|model| IcmPayoff::new(model, state.pot_size));
+    let mut std_err = config.stop_when.map(|StopWhen::StdErr(epsilon)| {
+        let key = InfoSetKey::from_game_state(state);
+        (StdErrEstimator::new(key, &regret_table), epsilon)
+    });
+    // Hero's hand and the board are fixed, so showdown equity depends only
+    // on the sampled villain hand
+    let mut equity_cache: FxHashMap<Hand, Equity> = FxHashMap::default();
+    let buckets = config
+        .villain_buckets
+        .map(|n| StrengthBuckets::new(&state.board, config.hero_range.as_ref(), n));
+    let mut bucket_cache: FxHashMap<Hand, u8> = FxHashMap::default();
+    let mut villain_bucket = |hand: &Hand| {
+        buckets.as_ref().map(|b| {
+            *bucket_cache
+                .entry(hand.clone())
+                .or_insert_with(|| b.bucket(hand))
+        })
+    };
+
+    // Each combo at its share of the range's weight
+    let enumerated: Option<Vec<(Hand, f64)>> =
+        (state.villain_range.len() < config.enumerate_villain_below).then(|| {
+            let total: f64 = state.villain_range.hands().map(|(_, w)| w).sum();
+            state
+                .villain_range
+                .hands()
+                .filter(|(_, w)| *w > 0.0)
+                .map(|(hand, w)| (hand.clone(), w / total))
+                .collect()
+        });
 
     let check_interval = if config.iterations <= 100 {
         (config.iterations / 2).max(1)
@@ -357,109 +1620,734 @@ pub fn solve_with_config(state: GameState, config: MccfrConfig) -> Result<Strate
         (config.iterations / 10).max(100)
     };
 
+    let mut samples = 0;
+    let mut completed = config.iterations;
     for i in 0..config.iterations {
+        // A relaxed load is cheap enough to answer a stop every iteration
+        if config
+            .stop
+            .as_ref()
+            .is_some_and(|stop| stop.load(Ordering::Relaxed))
+        {
+            completed = i;
+            break;
+        }
         // Check convergence
         if i > 0 && i % check_interval == 0 {
-            convergence_tracker.check_convergence(&regret_table);
+            let convergence = convergence_tracker.check_convergence(&regret_table);
             if convergence_tracker.is_converged(config.convergence_threshold) {
                 // Early stop?
                 // For now, we just track.
             }
+            if !observer(Snapshot {
+                iteration: i,
+                convergence,
+                samples,
+            }) {
+                completed = i;
+                break;
+            }
+            if let Some((estimator, epsilon)) = &mut std_err {
+                if estimator
+                    .check(&regret_table)
+                    .is_some_and(|err| err < *epsilon)
+                {
+                    completed = i;
+                    break;
+                }
+            }
         }
 
         let traverser = if i % 2 == 0 {
             state.position
-        } else if state.position == Position::IP {
-            Position::OOP
         } else {
-            Position::IP
+            state.position.other()
         };
 
-        let is_hero_traverser = traverser == state.position;
-
-        if is_hero_traverser {
-            for _ in 0..config.samples_per_iteration {
-                if let Some((villain_hand, _)) = sampler.sample_hand(&state.villain_range) {
-                    traverse(
-                        &root,
-                        traverser,
-                        &state.hero_hand,
-                        &villain_hand,
-                        &mut regret_table,
-                        &mut sampler,
-                    );
+        let mut play = |villain_hand: &Hand,
+                        weight: f64,
+                        regret_table: &mut RegretTable,
+                        sampler: &mut McSampler| {
+            samples += 1;
+            let equity = timed(
+                timings.as_ref(),
+                |t| &t.evaluation,
+                || {
+                    equity_cache
+                        .entry(villain_hand.clone())
+                        .or_insert_with(|| {
+                            showdown_equity(
+                                &state.hero_hand,
+                                villain_hand,
+                                &state.board,
+                                &state.dead_cards,
+                                eval_cache,
+                            )
+                        })
+                        .clone()
+                },
+            );
+            let mut t = Traversal::new(
+                traverser,
+                hero_position,
+                &state.hero_hand,
+                villain_hand,
+                equity,
+            );
+            t.chips_per_bb = config.chips_per_bb();
+            t.villain_bucket = villain_bucket(villain_hand);
+            t.timings = timings.as_ref();
+            t.hero_bet_sizes = config.hero_bet_sizes.as_deref();
+            t.villain_bet_sizes = config.villain_bet_sizes.as_deref();
+            t.street_bet_sizes = config.street_bet_sizes.as_ref();
+            t.max_depth = config.max_depth;
+            t.max_raises = config.max_raises;
+            t.rake = config.rake;
+            t.flop_eval_cache = config.deal_flop.then_some(eval_cache);
+            t.icm = icm.as_ref();
+            t.weight = weight;
+            traverse(state, &t, 0, 0.0, regret_table, sampler).map(|_| ())
+        };
+
+        match &enumerated {
+            Some(combos) => {
+                for (villain_hand, weight) in combos {
+                    play(villain_hand, *weight, &mut regret_table, &mut sampler)?;
                 }
             }
-        } else {
-            let hero_sample = state.hero_hand.clone();
-            for _ in 0..config.samples_per_iteration {
-                if let Some((villain_hand, _)) = sampler.sample_hand(&state.villain_range) {
-                    traverse(
-                        &root,
-                        traverser,
-                        &hero_sample,
-                        &villain_hand,
-                        &mut regret_table,
-                        &mut sampler,
+            None => {
+                for _ in 0..config.samples_at(i) {
+                    let sampled = timed(
+                        timings.as_ref(),
+                        |t| &t.sampling,
+                        || sampler.sample_hand(&state.villain_range),
                     );
+                    if let Some((villain_hand, _)) = sampled {
+                        play(&villain_hand, 1.0, &mut regret_table, &mut sampler)?;
+                    }
                 }
             }
         }
     }
 
     // Final convergence check
-    convergence_tracker.check_convergence(&regret_table);
-
-    Ok(extract_strategy(
-        &root,
-        &regret_table,
-        config.iterations,
-        convergence_tracker.max_change,
-    ))
+    let convergence = convergence_tracker.check_convergence(&regret_table);
+    observer(Snapshot {
+        iteration: completed,
+        convergence,
+        samples,
+    });
+    let profile = timings.map(|t| SolveProfile {
+        sampling: t.sampling.get(),
+        evaluation: t.evaluation.get(),
+        regret_updates: t.regret_updates.get(),
+        total: started.elapsed(),
+    });
+    Ok((regret_table, convergence, profile, completed))
 }
 
 fn extract_strategy(
     state: &GameState,
     regret_table: &RegretTable,
-    iterations: u32,
+    config: &MccfrConfig,
     convergence: f64,
 ) -> Strategy {
     let key = InfoSetKey::from_game_state(state);
-    let avg_strategy = regret_table.get_average_strategy(&key).unwrap_or_else(|| {
-        let n = if state.available_actions.is_empty() {
-            2
+    let root_strategy = if config.return_current_strategy {
+        regret_table.get_current_strategy(&key)
+    } else {
+        regret_table.get_average_strategy(&key)
+    };
+    let actions = node_actions(state, config.hero_sizes_on(state.street), true);
+    let root_strategy =
+        root_strategy.unwrap_or_else(|| vec![1.0 / actions.len() as f64; actions.len()]);
+
+    // EVs are net chips for the player at the root, averaged over samples
+    let action_values = regret_table
+        .get_action_values(&key)
+        .unwrap_or_else(|| vec![0.0; actions.len()]);
+
+    let action_strategies: Vec<ActionStrategy> = actions
+        .iter()
+        .zip(root_strategy.iter())
+        .zip(action_values.iter())
+        .map(|((action, &freq), &ev)| ActionStrategy {
+            action: action.clone(),
+            frequency: freq,
+            ev,
+        })
+        .collect();
+
+    Strategy {
+        root_ev: weighted_ev(&action_strategies),
+        actions: action_strategies,
+        iterations: config.iterations,
+        convergence,
+        game_state: Some(state.clone()),
+        schema_version: JSON_SCHEMA_VERSION,
+        provenance: None,
+        convergence_history: Vec::new(),
+        ev_unit: if config.icm.is_some() {
+            EvUnit::Payout
         } else {
-            state.available_actions.len()
-        };
-        vec![1.0 / n as f64; n]
-    });
+            EvUnit::BigBlinds
+        },
+        villain_subsample: None,
+    }
+}
 
-    let actions = if state.available_actions.is_empty() {
-        if state.to_call > 0.0 {
-            vec![Action::Fold, Action::Call]
+/// Villain's strategy at `state`, where villain acts, averaged over the
+/// combos of villain's range that reached it
+fn extract_villain_strategy(
+    state: &GameState,
+    regret_table: &RegretTable,
+    config: &MccfrConfig,
+    convergence: f64,
+) -> Strategy {
+    let actions = node_actions(
+        state,
+        config.villain_sizes_on(state.street),
+        config.can_raise(state),
+    );
+    let buckets = config
+        .villain_buckets
+        .map(|n| StrengthBuckets::new(&state.board, config.hero_range.as_ref(), n));
+
+    let mut frequencies = vec![0.0; actions.len()];
+    let mut values = vec![0.0; actions.len()];
+    let mut total_weight = 0.0;
+    for (hand, weight) in &state.villain_range {
+        let key = match &buckets {
+            Some(b) => InfoSetKey::from_bucket(state, b.bucket(hand)),
+            None => {
+                let mut state_for_key = state.clone();
+                state_for_key.hero_hand = hand.clone();
+                InfoSetKey::from_game_state(&state_for_key)
+            }
+        };
+        let strategy = if config.return_current_strategy {
+            regret_table.get_current_strategy(&key)
         } else {
-            vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]
+            regret_table.get_average_strategy(&key)
+        };
+        // Combos never sampled (e.g. blocked by hero) have no info set
+        let Some(strategy) = strategy else {
+            continue;
+        };
+        let action_values = regret_table
+            .get_action_values(&key)
+            .unwrap_or_else(|| vec![0.0; actions.len()]);
+        for i in 0..actions.len() {
+            frequencies[i] += weight * strategy[i];
+            values[i] += weight * action_values[i];
         }
-    } else {
-        state.available_actions.clone()
-    };
+        total_weight += weight;
+    }
 
-    let action_strategies = actions
+    let action_strategies: Vec<ActionStrategy> = actions
         .iter()
-        .zip(avg_strategy.iter())
-        .map(|(action, &freq)| {
-            ActionStrategy {
-                action: action.clone(),
-                frequency: freq,
-                ev: 0.0, // TODO: Compute EV
-            }
+        .enumerate()
+        .map(|(i, action)| ActionStrategy {
+            action: action.clone(),
+            frequency: if total_weight > 0.0 {
+                frequencies[i] / total_weight
+            } else {
+                1.0 / actions.len() as f64
+            },
+            ev: if total_weight > 0.0 {
+                values[i] / total_weight
+            } else {
+                0.0
+            },
         })
         .collect();
 
     Strategy {
+        root_ev: weighted_ev(&action_strategies),
         actions: action_strategies,
-        iterations,
+        iterations: config.iterations,
         convergence,
         game_state: Some(state.clone()),
+        schema_version: JSON_SCHEMA_VERSION,
+        provenance: None,
+        convergence_history: Vec::new(),
+        ev_unit: if config.icm.is_some() {
+            EvUnit::Payout
+        } else {
+            EvUnit::BigBlinds
+        },
+        villain_subsample: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::card::Card;
+    use crate::solver::equity::calculate_equity_runout;
+    use crate::test_util::{cards, DRY_RIVER};
+    use std::str::FromStr;
+
+    fn river_state(position: Position) -> GameState {
+        let board = ["Qh", "Jh", "Th", "2s", "3d"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        GameState::new(
+            Hand::from_str("AhKh").unwrap(),
+            board,
+            10.0,
+            100.0,
+            0.0,
+            position,
+            Range::new(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_street_bet_sizes_follow_the_street() {
+        let river = river_state(Position::OOP);
+        let flop = river.clone().with_board(river.board[..3].to_vec()).unwrap();
+        let sizes = StreetBetSizes::default();
+        let hand = Hand::from_str("7c4d").unwrap();
+        let mut t = Traversal::new(
+            Position::OOP,
+            Position::OOP,
+            &river.hero_hand,
+            &hand,
+            Equity::new(0.5, 0.0, 0.5),
+        );
+        t.street_bet_sizes = Some(&sizes);
+
+        let bets = |t: &Traversal, state: &GameState| -> Vec<Action> {
+            t.actions(state)
+                .into_iter()
+                .filter(|a| matches!(a, Action::Bet(_)))
+                .collect()
+        };
+        assert_eq!(
+            bets(&t, &flop),
+            vec![Action::Bet(BetSize::PotFraction(0.33))]
+        );
+        assert_eq!(
+            bets(&t, &river),
+            vec![
+                Action::Bet(BetSize::PotFraction(0.75)),
+                Action::Bet(BetSize::PotFraction(1.25))
+            ]
+        );
+
+        // A player's own sizes win over the street's
+        let own = [BetSize::PotFraction(1.0)];
+        t.hero_bet_sizes = Some(&own);
+        assert_eq!(
+            bets(&t, &flop),
+            vec![Action::Bet(BetSize::PotFraction(1.0))]
+        );
+    }
+
+    #[test]
+    fn test_strong_hand_raises_when_facing_a_bet() {
+        // Hero checks the royal flush and villain bets into it
+        let mut state = river_state(Position::OOP);
+        state.villain_range = Range::from_notation("74o,84o,AK").unwrap();
+        state.villain_range.remove_blockers(&state.board);
+        let (villain_node, _) = apply_action(&state, &Action::Check);
+        let (facing_bet, _) = apply_action(&villain_node, &Action::Bet(BetSize::PotFraction(0.5)));
+
+        // Raises below the root are opt-in
+        let config = MccfrConfig::builder()
+            .iterations(500)
+            .seed(11)
+            .max_raises(1)
+            .build();
+        assert_eq!(
+            node_actions(&facing_bet, None, false),
+            [Action::Fold, Action::Call]
+        );
+        let actions = node_actions(&facing_bet, None, config.can_raise(&facing_bet));
+        assert_eq!(actions, determine_available_actions(&facing_bet));
+        assert!(actions.contains(&Action::Raise(BetSize::PotFraction(1.0))));
+
+        // A raise uses up the cap, so villain can't re-raise it
+        let (facing_raise, _) = apply_action(&facing_bet, &actions[2]);
+        assert_eq!(facing_raise.street_raises, 1);
+        assert!(!config.can_raise(&facing_raise));
+
+        let (_, table) = solve_with_regrets(state, config).unwrap();
+        let strategy = table
+            .get_average_strategy(&InfoSetKey::from_game_state(&facing_bet))
+            .unwrap();
+        let raise: f64 = actions
+            .iter()
+            .zip(&strategy)
+            .filter(|(action, _)| matches!(action, Action::Raise(_) | Action::AllIn))
+            .map(|(_, frequency)| frequency)
+            .sum();
+        assert!(raise > 0.5, "{:?} {:?}", actions, strategy);
+    }
+
+    #[test]
+    fn test_showdown_attribution_is_symmetric() {
+        let h1 = Hand::from_str("AhKh").unwrap(); // Royal flush
+        let h2 = Hand::from_str("7c4d").unwrap();
+
+        for hero_position in [Position::IP, Position::OOP] {
+            let equity = showdown_equity(
+                &h1,
+                &h2,
+                &river_state(hero_position).board,
+                &[],
+                &SharedEvalCache::new(),
+            );
+            let as_hero = Traversal::new(hero_position, hero_position, &h1, &h2, equity.clone());
+            let as_villain = Traversal::new(hero_position.other(), hero_position, &h1, &h2, equity);
+
+            let reference = evaluate_showdown(&river_state(hero_position), &as_hero);
+            assert_eq!(reference, 10.0);
+
+            // Whoever happens to be "to act" at showdown must not matter
+            for actor in [Position::IP, Position::OOP] {
+                let state = river_state(actor);
+                let hero_util = evaluate_showdown(&state, &as_hero);
+                let villain_util = evaluate_showdown(&state, &as_villain);
+
+                assert_eq!(hero_util, reference);
+                assert_eq!(hero_util + villain_util, state.pot_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_raise_bookkeeping() {
+        let mut state = river_state(Position::OOP);
+        state.to_call = 5.0;
+        state.effective_stack = 50.0;
+
+        // Tiny raise is bumped to the 10 chip minimum
+        let (next, terminal) = apply_action(&state, &Action::Raise(BetSize::Amount(6.0)));
+        assert!(!terminal);
+        assert_eq!(next.pot_size, 20.0);
+        assert_eq!(next.to_call, 5.0);
+        assert_eq!(next.effective_stack, 45.0);
+
+        // Facing an all-in, calling puts both players' stacks in
+        let (all_in, _) = apply_action(&state, &Action::AllIn);
+        assert_eq!(all_in.to_call, 45.0);
+        assert_eq!(all_in.effective_stack, 45.0);
+        let (called, terminal) = apply_action(&all_in, &Action::Call);
+        assert!(terminal);
+        assert_eq!(called.pot_size, 10.0 + 50.0 + 45.0);
+        assert_eq!(called.effective_stack, 0.0);
+    }
+
+    #[test]
+    fn test_street_pot_tracking() {
+        let mut flop = river_state(Position::OOP);
+        flop.board = crate::models::Board::new(flop.board[..3].to_vec()).unwrap();
+        flop.street = crate::models::game_state::Street::Flop;
+        flop.starting_street_pot = flop.pot_size;
+
+        let (bet, _) = apply_action(&flop, &Action::Bet(BetSize::PotFraction(0.5)));
+        assert_eq!(bet.street_investment, 5.0);
+        let (called, _) = apply_action(&bet, &Action::Call);
+        assert_eq!(called.street_investment, 10.0);
+        assert_eq!(called.starting_street_pot, 10.0);
+
+        let mut turn = called.clone();
+        turn.deal_card(Card::from_str("2d").unwrap()).unwrap();
+        assert_eq!(turn.starting_street_pot, 20.0);
+        assert_eq!(turn.street_investment, 0.0);
+    }
+
+    /// Clairvoyant river toy game with a closed-form equilibrium.
+    ///
+    /// Hero holds a bluff catcher (Kh5d on Ks8d4c2h7s) and checks. Villain
+    /// holds the nuts (8h8c) or air (Qh3c) with equal weight and may bet
+    /// b = P/2 into the pot P = 10, or check behind and lose with air.
+    ///
+    /// Facing a bet, hero calls to win P + b against a bluff and loses b
+    /// against value, so hero is indifferent when bluffs make up
+    /// b / (P + 2b) = 1/4 of the betting range, i.e. one bluff per three
+    /// value bets. Villain bets all nuts, so air bluffs 1/3 of the time.
+    ///
+    /// A bluff wins P when hero folds and loses b when hero calls, while
+    /// checking air wins nothing. Villain is indifferent when hero calls
+    /// P / (P + b) = 2/3 of the time (minimum defense frequency).
+    #[test]
+    fn test_toy_river_matches_analytic_equilibrium() {
+        let board = cards(DRY_RIVER);
+        let mut villain_range = Range::new();
+        villain_range.add(Hand::from_str("8h8c").unwrap(), 1.0);
+        villain_range.add(Hand::from_str("Qh3c").unwrap(), 1.0);
+        // Deep enough that the stack never matters, shallow enough that the
+        // root and the facing-bet node land in the same SPR bucket
+        let mut root = GameState::new(
+            Hand::from_str("Kh5d").unwrap(),
+            board,
+            10.0,
+            40.0,
+            0.0,
+            Position::OOP,
+            villain_range,
+        )
+        .unwrap();
+        root.available_actions = Some(vec![Action::Check]);
+
+        let config = MccfrConfig {
+            iterations: 20_000,
+            samples_per_iteration: 4,
+            seed: Some(2024),
+            ..Default::default()
+        };
+        let (table, ..) = train(&root, &config, &SharedEvalCache::new(), &mut |_| true).unwrap();
+
+        let bet = Action::Bet(BetSize::PotFraction(0.5));
+        let (villain_node, _) = apply_action(&root, &Action::Check);
+        let (hero_node, _) = apply_action(&villain_node, &bet);
+
+        let average = |state: &GameState, hand: &str| {
+            let mut state = state.clone();
+            state.hero_hand = Hand::from_str(hand).unwrap();
+            table
+                .get_average_strategy(&InfoSetKey::from_game_state(&state))
+                .unwrap()
+        };
+
+        // [Check, Bet] for villain, [Fold, Call] for hero
+        let value_bet = average(&villain_node, "8h8c")[1];
+        let bluff = average(&villain_node, "Qh3c")[1];
+        let call = average(&hero_node, "Kh5d")[1];
+
+        assert!(value_bet > 0.95, "value bets {}", value_bet);
+        assert!((bluff - 1.0 / 3.0).abs() < 0.05, "bluffs {}", bluff);
+        assert!((call - 2.0 / 3.0).abs() < 0.05, "calls {}", call);
+    }
+
+    #[test]
+    fn test_asymmetric_bet_sizes() {
+        let mut state = river_state(Position::OOP);
+        state.available_actions = None;
+        for hand in ["QdQc", "9c8c"] {
+            state.villain_range.add(Hand::from_str(hand).unwrap(), 1.0);
+        }
+        let hero_sizes = [
+            BetSize::PotFraction(0.33),
+            BetSize::PotFraction(0.75),
+            BetSize::PotFraction(1.5),
+        ];
+        let villain_sizes = [BetSize::PotFraction(1.0)];
+
+        let h1 = Hand::from_str("AsKs").unwrap();
+        let h2 = Hand::from_str("QdQc").unwrap();
+        let equity = showdown_equity(&h1, &h2, &state.board, &[], &SharedEvalCache::new());
+        let mut t = Traversal::new(Position::OOP, Position::OOP, &h1, &h2, equity);
+        t.hero_bet_sizes = Some(&hero_sizes);
+        t.villain_bet_sizes = Some(&villain_sizes);
+
+        // Hero opens with a check or one of three sizes
+        let root_actions = t.actions(&state);
+        assert_eq!(root_actions.len(), 4);
+
+        // Villain facing a check only has the pot-sized bet
+        let (villain_node, _) = apply_action(&state, &Action::Check);
+        let villain_actions = t.actions(&villain_node);
+        assert_eq!(
+            villain_actions,
+            vec![Action::Check, Action::Bet(BetSize::PotFraction(1.0))]
+        );
+
+        // Either player facing a bet can fold or call
+        let (hero_facing, _) = apply_action(&villain_node, &villain_actions[1]);
+        assert_eq!(t.actions(&hero_facing), vec![Action::Fold, Action::Call]);
+        let (villain_facing, _) = apply_action(&state, &root_actions[3]);
+        assert_eq!(t.actions(&villain_facing), vec![Action::Fold, Action::Call]);
+
+        // Allowed to raise, each player raises with their own sizes
+        t.max_raises = 1;
+        assert_eq!(
+            t.actions(&hero_facing),
+            [Action::Fold, Action::Call]
+                .into_iter()
+                .chain(hero_sizes.map(Action::Raise))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            t.actions(&villain_facing),
+            vec![
+                Action::Fold,
+                Action::Call,
+                Action::Raise(BetSize::PotFraction(1.0))
+            ]
+        );
+
+        // The solve keeps each player's tree consistent
+        let config = MccfrConfig {
+            iterations: 200,
+            samples_per_iteration: 4,
+            seed: Some(7),
+            hero_bet_sizes: Some(hero_sizes.to_vec()),
+            villain_bet_sizes: Some(villain_sizes.to_vec()),
+            max_raises: 1,
+            ..Default::default()
+        };
+        let strategy = solve_with_config(state, config).unwrap();
+        assert_eq!(strategy.actions.len(), 4);
+        assert!(strategy.is_valid());
+    }
+
+    #[test]
+    fn test_wide_villain_range_is_subsampled() {
+        let mut state = river_state(Position::OOP);
+        let cards: Vec<Card> = (0..52)
+            .map(|i| Card::from_pokers_index(i).unwrap())
+            .collect();
+        for (i, &c1) in cards.iter().enumerate() {
+            for &c2 in &cards[i + 1..] {
+                state.villain_range.add(Hand::new(c1, c2), 0.5);
+            }
+        }
+        assert_eq!(state.villain_range.len(), 1326);
+
+        let config = MccfrConfig {
+            iterations: 20,
+            samples_per_iteration: 50,
+            seed: Some(11),
+            max_villain_combos: Some(100),
+            ..Default::default()
+        };
+        let (training, subsample) = training_state(&state, &config);
+        assert_eq!(training.villain_range.len(), 100);
+        assert_eq!(
+            subsample,
+            Some(Subsample {
+                kept: 100,
+                total: 1326
+            })
+        );
+        let total: f64 = training.villain_range.hands().map(|(_, w)| w).sum();
+        assert!((total - 663.0).abs() < 1e-9);
+
+        // Same seed, same subset
+        let (again, _) = training_state(&state, &config);
+        assert!(training
+            .villain_range
+            .hands()
+            .all(|(hand, _)| again.villain_range.contains(hand)));
+
+        // Only subset combos ever reach villain's info sets
+        let (table, ..) =
+            train(&training, &config, &SharedEvalCache::new(), &mut |_| true).unwrap();
+        let villain_hands: std::collections::HashSet<&Hand> = table
+            .keys()
+            .filter(|key| key.position != state.position)
+            .map(|key| &key.hero_hand)
+            .collect();
+        assert!(!villain_hands.is_empty());
+        assert!(villain_hands.len() <= 100);
+        assert!(villain_hands
+            .iter()
+            .all(|hand| training.villain_range.contains(hand)));
+    }
+
+    #[test]
+    fn test_rake_reduces_showdown_payoff() {
+        let mut state = river_state(Position::IP);
+        let h1 = Hand::from_str("AhKh").unwrap(); // Royal flush
+        let h2 = Hand::from_str("7c4d").unwrap();
+        let equity = showdown_equity(&h1, &h2, &state.board, &[], &SharedEvalCache::new());
+        let mut t = Traversal::new(Position::IP, Position::IP, &h1, &h2, equity);
+        assert_eq!(evaluate_showdown(&state, &t), 10.0);
+
+        // 5% of 10 BB is 0.5 BB, under the cap
+        t.rake = Some(Rake {
+            percent: 5.0,
+            cap: 1.0,
+            no_flop_no_drop: true,
+        });
+        assert!((evaluate_showdown(&state, &t) - 9.5).abs() < 1e-9);
+
+        // 5% of 40 BB is capped at 1 BB
+        state.pot_size = 40.0;
+        assert!((evaluate_showdown(&state, &t) - 39.0).abs() < 1e-9);
+
+        // No flop, no drop
+        let rake = t.rake.unwrap();
+        assert_eq!(rake.taken(40.0, Street::Preflop), 0.0);
+        assert_eq!(rake.taken(40.0, Street::Flop), 1.0);
+    }
+
+    #[test]
+    fn test_flop_all_in_runs_out_the_board() {
+        let board: Vec<Card> = ["2c", "7d", "9s"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        let aces = Hand::from_str("AhAd").unwrap();
+        let kings = Hand::from_str("KsKc").unwrap();
+        let root = GameState::new(
+            aces.clone(),
+            board.clone(),
+            10.0,
+            100.0,
+            0.0,
+            Position::IP,
+            Range::from_notation("KsKc").unwrap(),
+        )
+        .unwrap();
+
+        let equity = showdown_equity(&aces, &kings, &board, &[], &SharedEvalCache::new());
+        let runout = calculate_equity_runout(&aces, &root.villain_range, &board);
+        assert!((equity.win - runout.win).abs() < 1e-9);
+        // Kings hit a set or better often enough that aces are no lock
+        assert!(equity.win > 0.85 && equity.win < 0.95, "{}", equity);
+
+        // Hero shoves, villain calls: no decisions left, but two cards to come
+        let (shoved, _) = apply_action(&root, &Action::AllIn);
+        let (called, is_terminal) = apply_action(&shoved, &Action::Call);
+        assert!(is_terminal);
+        let t = Traversal::new(Position::IP, Position::IP, &aces, &kings, equity.clone());
+        let utility = terminal_utility(&shoved, &Action::Call, &called, &t, 100.0);
+
+        let expected = called.pot_size * (equity.win + equity.tie / 2.0) - 100.0;
+        assert!((utility - expected).abs() < 1e-9);
+        // Scoring the incomplete board would award aces the whole pot
+        assert!(utility < called.pot_size - 100.0 - 5.0);
+    }
+
+    #[test]
+    fn test_showdown_tie_splits_pot() {
+        let state = river_state(Position::IP);
+        let h1 = Hand::from_str("4c5c").unwrap();
+        let h2 = Hand::from_str("4d5d").unwrap();
+
+        let equity = showdown_equity(&h1, &h2, &state.board, &[], &SharedEvalCache::new());
+        let t = Traversal::new(Position::OOP, Position::IP, &h1, &h2, equity);
+        assert_eq!(evaluate_showdown(&state, &t), state.pot_size / 2.0);
+    }
+
+    #[test]
+    fn test_odd_chip_goes_out_of_position() {
+        // 10.01 BB at 100 chips per BB is 1001 chips
+        let pot = 10.01;
+        assert_eq!(split_pot_share(pot, Position::OOP, Some(100)), 5.01);
+        assert_eq!(split_pot_share(pot, Position::IP, Some(100)), 5.0);
+        assert_eq!(split_pot_share(pot, Position::IP, None), pot / 2.0);
+
+        // Through a tied showdown, each seat gets its chip-accurate share
+        let mut state = river_state(Position::IP);
+        state.pot_size = pot;
+        let h1 = Hand::from_str("4c5c").unwrap();
+        let h2 = Hand::from_str("4d5d").unwrap();
+        let equity = showdown_equity(&h1, &h2, &state.board, &[], &SharedEvalCache::new());
+
+        let mut oop = Traversal::new(Position::OOP, Position::IP, &h1, &h2, equity.clone());
+        oop.chips_per_bb = Some(100);
+        let mut ip = Traversal::new(Position::IP, Position::IP, &h1, &h2, equity);
+        ip.chips_per_bb = Some(100);
+
+        assert_eq!(evaluate_showdown(&state, &oop), 5.01);
+        assert_eq!(evaluate_showdown(&state, &ip), 5.0);
     }
 }
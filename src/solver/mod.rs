@@ -1,12 +1,32 @@
 //! GTO solver implementation using MCCFR
 
 // Re-export solver components
+pub mod best_response;
+pub mod blockers;
 pub mod cfr;
-// pub mod equity;
+pub mod equity;
+pub mod eval_cache;
 pub mod evaluator;
 pub mod info_set;
 pub mod mccfr;
+pub mod preflop_equity;
+pub mod progress;
+pub mod push_fold;
+pub mod range_solve;
 pub mod regret;
+pub mod solve_cache;
 
-pub use cfr::solve;
-pub use mccfr::{solve_with_config, MccfrConfig};
+pub use best_response::{best_response, StrategyTree};
+pub use blockers::BlockerReport;
+pub use cfr::{solve, solve_with_kind, solve_with_kind_profiled, SolverKind};
+pub use eval_cache::SharedEvalCache;
+pub use mccfr::{
+    solve_both, solve_observed, solve_profiled, solve_with_cache, solve_with_config,
+    solve_with_forced_action, solve_with_regrets, ConvergenceMetric, MccfrConfig,
+    MccfrConfigBuilder, Rake, SampleSchedule, SolveProfile, StdErrEstimator, StopWhen,
+};
+pub use preflop_equity::preflop_equity;
+pub use progress::{solve_with_progress_channel, Snapshot, SolveHandle};
+pub use push_fold::{push_fold, Blinds};
+pub use range_solve::solve_range_vs_range;
+pub use solve_cache::SolveCache;
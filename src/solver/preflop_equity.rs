@@ -0,0 +1,235 @@
+//! Exact heads-up preflop all-in equity
+//!
+//! Enumerating all 1,712,304 boards for every matchup is too slow to do per
+//! call, so equities come from a precomputed table. Matchups that only
+//! differ by a relabelling of suits or by which player is which have the
+//! same equity, so the table holds one entry per class of matchups: 47,008
+//! in all, keyed by `canonical_key`.
+//!
+//! `preflop_equity.bin` is a sorted list of little-endian `u32` pairs: the
+//! key, then twice the boards the key's first hand wins plus the boards it
+//! ties. `build_table` computes it from scratch, and
+//! `cargo run --release --example preflop_equity_table` writes it out.
+
+use crate::models::hand::Hand;
+use pokers::{exact_equity, HandRange};
+use std::collections::BTreeSet;
+use std::sync::{atomic::AtomicBool, Arc};
+
+/// Boards dealt to a heads-up preflop all-in: C(48, 5)
+pub const PREFLOP_BOARDS: u32 = 1_712_304;
+
+const TABLE: &[u8] = include_bytes!("preflop_equity.bin");
+
+/// Bytes per table entry: a key and a value
+const ENTRY_SIZE: usize = 8;
+
+/// Hero's exact all-in equity against `villain` with no board, ties
+/// counting half; `None` if the hands share a card
+pub fn preflop_equity(hero: &Hand, villain: &Hand) -> Option<f64> {
+    let hero = hero.cards.map(|c| c.to_pokers_index());
+    let villain = villain.cards.map(|c| c.to_pokers_index());
+    if hero.iter().any(|c| villain.contains(c)) {
+        return None;
+    }
+
+    let (key, swapped) = canonical_key(hero, villain);
+    let value = lookup(key).expect("every canonical matchup is in the table");
+    let equity = value as f64 / (2.0 * PREFLOP_BOARDS as f64);
+    Some(if swapped { 1.0 - equity } else { equity })
+}
+
+/// Value stored for `key`, by binary search over the sorted entries
+fn lookup(key: u32) -> Option<u32> {
+    let entries = TABLE.len() / ENTRY_SIZE;
+    let read = |i: usize, offset: usize| {
+        let start = i * ENTRY_SIZE + offset;
+        u32::from_le_bytes(TABLE[start..start + 4].try_into().expect("4 bytes"))
+    };
+
+    let (mut low, mut high) = (0, entries);
+    while low < high {
+        let mid = (low + high) / 2;
+        match read(mid, 0).cmp(&key) {
+            std::cmp::Ordering::Less => low = mid + 1,
+            std::cmp::Ordering::Greater => high = mid,
+            std::cmp::Ordering::Equal => return Some(read(mid, 4)),
+        }
+    }
+    None
+}
+
+/// Smallest key of any suit relabelling of the matchup, with either hand
+/// first, and whether that key has `villain` first.
+///
+/// A key packs the four `pokers` card indices into 6 bits each: the first
+/// hand's low and high card, then the second hand's.
+fn canonical_key(hero: [u8; 2], villain: [u8; 2]) -> (u32, bool) {
+    let pack = |hand: [u8; 2]| {
+        let (low, high) = (hand[0].min(hand[1]), hand[0].max(hand[1]));
+        (low as u32) << 6 | high as u32
+    };
+
+    let mut best = (u32::MAX, false);
+    for suits in suit_permutations() {
+        let relabel = |hand: [u8; 2]| hand.map(|c| c - c % 4 + suits[(c % 4) as usize]);
+        let (hero, villain) = (pack(relabel(hero)), pack(relabel(villain)));
+        best = best.min((hero << 12 | villain, false));
+        best = best.min((villain << 12 | hero, true));
+    }
+    best
+}
+
+/// The contents of `preflop_equity.bin`, by enumerating every board of
+/// every canonical matchup; a few minutes in release builds
+pub fn build_table() -> Vec<u8> {
+    let keys = canonical_keys();
+    let mut table = Vec::with_capacity(keys.len() * ENTRY_SIZE);
+    for key in keys {
+        let value = enumerated_value(unpack(key >> 12), unpack(key & 0xfff));
+        table.extend_from_slice(&key.to_le_bytes());
+        table.extend_from_slice(&value.to_le_bytes());
+    }
+    table
+}
+
+/// The canonical key of every heads-up matchup of disjoint hands
+fn canonical_keys() -> BTreeSet<u32> {
+    let mut keys = BTreeSet::new();
+    for hero in 0..52 * 52 {
+        let (a, b) = ((hero / 52) as u8, (hero % 52) as u8);
+        for villain in 0..52 * 52 {
+            let (c, d) = ((villain / 52) as u8, (villain % 52) as u8);
+            let cards = [a, b, c, d];
+            if a < b && c < d && (1..4).all(|i| !cards[..i].contains(&cards[i])) {
+                keys.insert(canonical_key([a, b], [c, d]).0);
+            }
+        }
+    }
+    keys
+}
+
+/// The two card indices of a hand packed by `canonical_key`
+fn unpack(bits: u32) -> [u8; 2] {
+    [(bits >> 6) as u8, (bits & 63) as u8]
+}
+
+/// Twice the boards `hero` wins plus the boards it ties against `villain`,
+/// by full enumeration
+fn enumerated_value(hero: [u8; 2], villain: [u8; 2]) -> u32 {
+    let notation = |hand: [u8; 2]| {
+        hand.map(|index| {
+            crate::models::Card::from_pokers_index(index)
+                .expect("packed indices are cards")
+                .notation()
+        })
+        .concat()
+    };
+    let ranges = HandRange::from_strings(vec![notation(hero), notation(villain)]);
+    let result = exact_equity(&ranges, 0, 0, 1, Arc::new(AtomicBool::new(false)), |_| {})
+        .expect("disjoint hands");
+    // Results are scaled by combo weight, ties split between both players
+    let scale = (result.wins[0] as f64 + result.wins[1] as f64 + 2.0 * result.ties[0])
+        / PREFLOP_BOARDS as f64;
+    let value = 2.0 * (result.wins[0] as f64 + result.ties[0]) / scale;
+    debug_assert!((value - value.round()).abs() < 1e-6, "{}", value);
+    value.round() as u32
+}
+
+/// All 24 orderings of the four suits
+fn suit_permutations() -> impl Iterator<Item = [u8; 4]> {
+    (0..4u8).flat_map(|a| {
+        (0..4u8).flat_map(move |b| {
+            (0..4u8).flat_map(move |c| {
+                (0..4u8)
+                    .map(move |d| [a, b, c, d])
+                    .filter(|suits| (1..4).all(|i| !suits[..i].contains(&suits[i])))
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn hand(notation: &str) -> Hand {
+        Hand::from_str(notation).unwrap()
+    }
+
+    fn entries() -> impl Iterator<Item = (u32, u32)> {
+        TABLE.chunks_exact(ENTRY_SIZE).map(|entry| {
+            let word = |i: usize| u32::from_le_bytes(entry[i..i + 4].try_into().unwrap());
+            (word(0), word(4))
+        })
+    }
+
+    #[test]
+    fn test_table_matches_enumeration() {
+        for (hero, villain) in [
+            ("AhKd", "QsQc"),
+            ("7h2d", "QsQc"),
+            ("AsAh", "KsKh"),
+            ("JhTh", "9h8h"),
+            ("5c4c", "AdKs"),
+        ] {
+            let index = |h: &str| hand(h).cards.map(|c| c.to_pokers_index());
+            let expected = enumerated_value(index(hero), index(villain)) as f64
+                / (2.0 * PREFLOP_BOARDS as f64);
+            let equity = preflop_equity(&hand(hero), &hand(villain)).unwrap();
+            assert!((equity - expected).abs() < 1e-12, "{} vs {}", hero, villain);
+
+            let flipped = preflop_equity(&hand(villain), &hand(hero)).unwrap();
+            assert!((equity + flipped - 1.0).abs() < 1e-12);
+        }
+
+        // An even spread across the whole table
+        for (key, value) in entries().step_by(500) {
+            assert_eq!(
+                value,
+                enumerated_value(unpack(key >> 12), unpack(key & 0xfff)),
+                "key {}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_table_holds_every_canonical_matchup() {
+        // Distinct canonical keys, one per class, so every matchup finds
+        // its entry
+        let keys: Vec<u32> = entries().map(|(key, _)| key).collect();
+        assert_eq!(keys.len(), 47_008);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        for &key in &keys {
+            let canonical = canonical_key(unpack(key >> 12), unpack(key & 0xfff));
+            assert_eq!(canonical, (key, false));
+        }
+        assert!(entries().all(|(_, value)| value <= 2 * PREFLOP_BOARDS));
+
+        // A pair is about a 4:1 favourite over any smaller pair
+        let rank = |card: u8| card / 4;
+        for (key, value) in entries() {
+            let (first, second) = (unpack(key >> 12), unpack(key & 0xfff));
+            let pair = |hand: [u8; 2]| rank(hand[0]) == rank(hand[1]);
+            if pair(first) && pair(second) && rank(first[0]) != rank(second[0]) {
+                let equity = value as f64 / (2.0 * PREFLOP_BOARDS as f64);
+                let higher = if rank(first[0]) > rank(second[0]) {
+                    equity
+                } else {
+                    1.0 - equity
+                };
+                assert!((0.77..0.84).contains(&higher), "key {}: {}", key, higher);
+            }
+        }
+    }
+
+    #[test]
+    fn test_suit_relabelling_keeps_equity() {
+        let equity = preflop_equity(&hand("AhKd"), &hand("QsQc")).unwrap();
+        let relabelled = preflop_equity(&hand("AsKc"), &hand("QdQh")).unwrap();
+        assert_eq!(equity, relabelled);
+        assert!(preflop_equity(&hand("AhKd"), &hand("AhQc")).is_none());
+    }
+}
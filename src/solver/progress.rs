@@ -0,0 +1,73 @@
+//! Solving on a background thread with progress streamed over a channel
+//!
+//! Meant for front-ends that render progress without blocking: the solve
+//! runs on its own thread and sends a `Snapshot` at every convergence check.
+
+use crate::error::ModelError;
+use crate::models::{game_state::GameState, strategy::Strategy};
+use crate::solver::{
+    eval_cache::SharedEvalCache,
+    mccfr::{solve_observed, MccfrConfig},
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// Solver progress at one convergence check
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Snapshot {
+    /// Iterations completed so far
+    pub iteration: u32,
+    /// Largest average-strategy change since the previous check
+    pub convergence: f64,
+    /// Villain hands played so far, sampled or enumerated
+    pub samples: u64,
+}
+
+/// Handle to a solve running on a background thread
+#[derive(Debug)]
+pub struct SolveHandle {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<crate::Result<Strategy>>,
+}
+
+impl SolveHandle {
+    /// Ask the solve to stop before its next iteration
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait for the solve to finish and return its strategy
+    pub fn join(self) -> crate::Result<Strategy> {
+        self.thread
+            .join()
+            .map_err(|_| ModelError::Solver("Solver thread panicked".to_string()))?
+    }
+}
+
+/// Solve `state` on a new thread, streaming progress through the returned
+/// receiver.
+///
+/// A `Snapshot` arrives at every convergence check and once when the solve
+/// ends; the channel closes when the thread finishes. Dropping the receiver
+/// ends the solve at the next check and calling `SolveHandle::stop` ends it
+/// before the next iteration; the strategy reports the iterations actually
+/// run.
+#[must_use]
+pub fn solve_with_progress_channel(
+    state: GameState,
+    mut config: MccfrConfig,
+) -> (Receiver<Snapshot>, SolveHandle) {
+    let (sender, receiver) = mpsc::channel();
+    // Shares the caller's flag if the config already has one
+    let stop = config.stop.get_or_insert_with(Arc::default).clone();
+
+    let thread = thread::spawn(move || {
+        let mut observer = |snapshot: Snapshot| sender.send(snapshot).is_ok();
+        solve_observed(state, config, &SharedEvalCache::new(), &mut observer)
+            .map(|(strategy, _)| strategy)
+    });
+
+    (receiver, SolveHandle { stop, thread })
+}
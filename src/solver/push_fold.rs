@@ -0,0 +1,121 @@
+//! Heads-up push/fold
+//!
+//! Short-stacked in the small blind, hero either shoves all-in or folds,
+//! and the big blind calls with a given range or folds. The whole game is
+//! one decision each, so every hero combo's shove EV comes straight from
+//! how often villain calls and hero's all-in equity against the calls,
+//! without running MCCFR.
+//!
+//! Every villain calling combo is counted at its weight, and each
+//! matchup's all-in equity is exact (see `preflop_equity`), so the result
+//! involves no sampling.
+
+use crate::error::{ModelError, Result};
+use crate::models::{
+    action::Action,
+    hand::Hand,
+    range::Range,
+    strategy::{ActionStrategy, ComboStrategy, RangeStrategy, Strategy},
+};
+use crate::solver::preflop_equity::preflop_equity;
+use rayon::prelude::*;
+
+/// Forced bets in big blinds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Blinds {
+    /// Hero's small blind
+    pub small: f64,
+    /// Villain's big blind
+    pub big: f64,
+}
+
+impl Default for Blinds {
+    fn default() -> Self {
+        Self {
+            small: 0.5,
+            big: 1.0,
+        }
+    }
+}
+
+/// Shove-or-fold strategy for every combo of `hero_range` in the small
+/// blind, with `stack` big blinds effective before the blinds are posted,
+/// against a big blind calling with `villain_call_range`.
+///
+/// Villain holds any combo hero doesn't block, and calls with each at its
+/// weight in `villain_call_range`. EVs are net chips from hero's decision
+/// on, with the blinds in the pot: folding is 0, and a shove villain folds
+/// to wins both blinds. Each combo takes whichever action has the higher
+/// EV, folding on a tie.
+pub fn push_fold(
+    hero_range: &Range,
+    villain_call_range: &Range,
+    stack: f64,
+    blinds: Blinds,
+) -> Result<RangeStrategy> {
+    if !(blinds.small > 0.0 && blinds.small <= blinds.big) {
+        return Err(ModelError::InvalidGameState(format!(
+            "Push/fold blinds must satisfy 0 < small ({}) <= big ({})",
+            blinds.small, blinds.big
+        )));
+    }
+    if stack.is_nan() || stack < blinds.big {
+        return Err(ModelError::InvalidGameState(format!(
+            "Push/fold stack {} is below the big blind {}",
+            stack, blinds.big
+        )));
+    }
+
+    let combos: Vec<(&Hand, f64)> = hero_range.hands().filter(|(_, w)| *w > 0.0).collect();
+
+    let combos = combos
+        .into_par_iter()
+        .map(|(hand, weight)| {
+            let shove_ev = shove_ev(hand, villain_call_range, stack, blinds);
+            let shove = shove_ev > 0.0;
+            let actions = vec![
+                ActionStrategy {
+                    action: Action::Fold,
+                    frequency: if shove { 0.0 } else { 1.0 },
+                    ev: 0.0,
+                },
+                ActionStrategy {
+                    action: Action::AllIn,
+                    frequency: if shove { 1.0 } else { 0.0 },
+                    ev: shove_ev,
+                },
+            ];
+            ComboStrategy {
+                hand: hand.clone(),
+                weight,
+                strategy: Strategy::new(actions, 0, 0.0),
+            }
+        })
+        .collect();
+    Ok(RangeStrategy { combos })
+}
+
+/// EV of shoving `hand`: the blinds when villain folds, hero's share of
+/// the all-in pot less the rest of hero's stack when villain calls
+fn shove_ev(hand: &Hand, villain_call_range: &Range, stack: f64, blinds: Blinds) -> f64 {
+    // Villain is dealt any of the combos hero doesn't block: C(50, 2)
+    const VILLAIN_COMBOS: f64 = 1225.0;
+
+    let mut call_weight = 0.0;
+    let mut weighted_equity = 0.0;
+    for (villain, weight) in villain_call_range.hands().filter(|(_, w)| *w > 0.0) {
+        if let Some(equity) = preflop_equity(hand, villain) {
+            call_weight += weight;
+            weighted_equity += weight * equity;
+        }
+    }
+    let call_probability = (call_weight / VILLAIN_COMBOS).min(1.0);
+    let pot = blinds.small + blinds.big;
+    if call_probability == 0.0 {
+        return pot;
+    }
+
+    let equity = weighted_equity / call_weight;
+    let called = equity * 2.0 * stack - (stack - blinds.small);
+    (1.0 - call_probability) * pot + call_probability * called
+}
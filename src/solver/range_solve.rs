@@ -0,0 +1,162 @@
+//! Range-vs-range solving
+//!
+//! Solves every combo of a hero range against the villain range in parallel.
+//! All combos share one `SharedEvalCache`, since villain showdown scores do
+//! not depend on which hero combo is being solved.
+
+use crate::error::ModelError;
+use crate::models::{
+    card::Card,
+    game_state::{GameState, Street},
+    hand::Hand,
+    range::Range,
+    strategy::{ComboStrategy, RangeStrategy},
+};
+use crate::solver::{
+    cfr::determine_available_actions,
+    eval_cache::SharedEvalCache,
+    evaluator::{hand_category, HandCategory},
+    mccfr::{solve_with_cache, MccfrConfig},
+};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+
+/// Solve each combo of `hero_range` in the spot described by `state`.
+///
+/// `state.hero_hand` is ignored. Hero combos blocked by the board or dead
+/// cards, or that leave villain no unblocked combos, are skipped. With
+/// `config.villain_buckets` set and no `config.hero_range`, villain hand
+/// strength is bucketed against `hero_range`.
+pub fn solve_range_vs_range(
+    state: &GameState,
+    hero_range: &Range,
+    config: MccfrConfig,
+) -> crate::Result<RangeStrategy> {
+    solve_range_vs_range_with_cache(state, hero_range, config, &SharedEvalCache::new())
+}
+
+/// `solve_range_vs_range` using a caller-provided evaluation cache
+pub fn solve_range_vs_range_with_cache(
+    state: &GameState,
+    hero_range: &Range,
+    mut config: MccfrConfig,
+    eval_cache: &SharedEvalCache,
+) -> crate::Result<RangeStrategy> {
+    let mut state = state.clone();
+    if state.available_actions.is_none() {
+        state.available_actions = Some(determine_available_actions(&state));
+    }
+    let combos = hero_combos(&state, hero_range);
+    if config.villain_buckets.is_some() && config.hero_range.is_none() {
+        config.hero_range = Some(hero_range.clone());
+    }
+
+    let solved: crate::Result<Vec<ComboStrategy>> = combos
+        .into_par_iter()
+        .map(|(combo_state, weight)| {
+            let hand = combo_state.hero_hand.clone();
+            let strategy = solve_with_cache(combo_state, config.clone(), eval_cache)?;
+            Ok(ComboStrategy {
+                hand,
+                weight,
+                strategy,
+            })
+        })
+        .collect();
+
+    Ok(RangeStrategy { combos: solved? })
+}
+
+/// Group a range solve by each combo's `HandCategory` on `board`, strongest
+/// category first
+pub fn by_category(
+    strategy: &RangeStrategy,
+    board: &[Card],
+) -> BTreeMap<HandCategory, RangeStrategy> {
+    strategy.group_by(|hand| hand_category(hand, board))
+}
+
+/// Solve the turn after every possible turn card, in deck order.
+///
+/// `state` must be a flop spot with no bet pending; each turn starts from
+/// its pot, as if the flop checked through, and its villain range loses the
+/// combos the turn card blocks. Turns that block every hero combo are skipped.
+///
+/// Each turn is its own range solve, so this costs one solve per turn card.
+/// That is deliberate: a flop solve ends with the flop betting and scores the
+/// turn and river by equity over the runouts, so its tree has no turn nodes
+/// to group by card. Flop play is therefore not shared between turns; every
+/// turn assumes the flop checked through. The turns do share one evaluation
+/// cache.
+pub fn by_turn(
+    state: &GameState,
+    hero_range: &Range,
+    config: MccfrConfig,
+) -> crate::Result<Vec<(Card, RangeStrategy)>> {
+    if state.street != Street::Flop {
+        return Err(ModelError::InvalidGameState(format!(
+            "A turn report needs a flop spot, got {:?}",
+            state.street
+        )));
+    }
+
+    let turns = (0..52)
+        .filter_map(|i| Card::from_pokers_index(i).ok())
+        .filter(|c| !state.board.contains(c) && !state.dead_cards.contains(c));
+    let eval_cache = SharedEvalCache::new();
+    let mut solved = Vec::new();
+    for turn in turns {
+        // hero_hand is only a placeholder for the range, so it must not hold the turn
+        let Some(placeholder) = hero_range
+            .hands()
+            .filter(|(hand, weight)| {
+                *weight > 0.0
+                    && !hand.contains_card(&turn)
+                    && !hand
+                        .cards
+                        .iter()
+                        .any(|c| state.board.contains(c) || state.dead_cards.contains(c))
+            })
+            .map(|(hand, _)| hand)
+            .min_by_key(|hand| hand.cards.map(|c| c.to_pokers_index()))
+        else {
+            continue;
+        };
+        let mut turn_state = state.clone();
+        turn_state.hero_hand = placeholder.clone();
+        turn_state.deal_card(turn)?;
+        turn_state.villain_range.remove_blockers(&[turn]);
+        let strategy =
+            solve_range_vs_range_with_cache(&turn_state, hero_range, config.clone(), &eval_cache)?;
+        solved.push((turn, strategy));
+    }
+    Ok(solved)
+}
+
+/// Per-combo game states in a stable order, with villain blockers removed
+fn hero_combos(state: &GameState, hero_range: &Range) -> Vec<(GameState, f64)> {
+    let mut hands: Vec<(&Hand, f64)> = hero_range
+        .hands()
+        .filter(|(hand, weight)| {
+            *weight > 0.0
+                && !hand
+                    .cards
+                    .iter()
+                    .any(|c| state.board.contains(c) || state.dead_cards.contains(c))
+        })
+        .collect();
+    hands.sort_by_key(|(hand, _)| hand.cards.map(|c| c.to_pokers_index()));
+
+    hands
+        .into_iter()
+        .filter_map(|(hand, weight)| {
+            let mut combo_state = state.clone();
+            combo_state.hero_hand = hand.clone();
+            combo_state.villain_range.remove_blockers(&hand.cards);
+            if combo_state.villain_range.num_combos() == 0 {
+                return None;
+            }
+            Some((combo_state, weight))
+        })
+        .collect()
+}
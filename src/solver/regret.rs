@@ -11,6 +11,7 @@ use rustc_hash::FxHashMap;
 /// Any negative regrets are treated as zero. The resulting strategy is normalized
 /// so that probabilities sum to 1.0. If all positive regrets are zero, a uniform
 /// strategy is returned.
+#[must_use]
 pub fn regret_to_strategy(regrets: &[f64]) -> Vec<f64> {
     let positive_regrets: Vec<f64> = regrets.iter().map(|&r| r.max(0.0)).collect();
     let sum_positive_regret: f64 = positive_regrets.iter().sum();
@@ -31,11 +32,17 @@ pub fn regret_to_strategy(regrets: &[f64]) -> Vec<f64> {
 }
 
 /// Storage for cumulative regrets and strategy sums across all information sets.
+#[derive(Debug, Clone)]
 pub struct RegretTable {
     /// Cumulative regret per action per info set
     regrets: FxHashMap<InfoSetKey, Vec<f64>>,
-    /// Sum of strategies weighted by reach probability (for average strategy)
-    strategy_sum: FxHashMap<InfoSetKey, Vec<f64>>,
+    /// Sum of strategies weighted by reach probability (for average
+    /// strategy) and the regret updates made, i.e. how often the info set
+    /// was reached
+    strategy_sum: FxHashMap<InfoSetKey, (Vec<f64>, u64)>,
+    /// Weighted sum of sampled action utilities and total visit weight per
+    /// info set (for EV)
+    action_values: FxHashMap<InfoSetKey, (Vec<f64>, f64)>,
 }
 
 impl Default for RegretTable {
@@ -50,17 +57,42 @@ impl RegretTable {
         Self {
             regrets: FxHashMap::default(),
             strategy_sum: FxHashMap::default(),
+            action_values: FxHashMap::default(),
+        }
+    }
+
+    /// A table starting from `prior`'s regrets and strategy sums, to warm
+    /// start a solve of a similar spot.
+    ///
+    /// Info sets the new solve shares with `prior` pick up where it left off;
+    /// the rest start fresh. Action values and visit counts are not copied,
+    /// so EVs and reach only reflect the new solve.
+    pub fn warm_started_from(prior: &RegretTable) -> Self {
+        Self {
+            regrets: prior.regrets.clone(),
+            strategy_sum: prior
+                .strategy_sum
+                .iter()
+                .map(|(key, (sum, _))| (key.clone(), (sum.clone(), 0)))
+                .collect(),
+            action_values: FxHashMap::default(),
         }
     }
 
     /// Get the current strategy for an info set using regret matching.
     ///
     /// If the info set doesn't exist, it initializes it with zero regrets.
+    /// An info set stored with a different number of actions (e.g. carried
+    /// over by `warm_started_from`) is reset the same way.
     pub fn get_strategy(&mut self, key: &InfoSetKey, n_actions: usize) -> Vec<f64> {
         let regrets = self
             .regrets
             .entry(key.clone())
             .or_insert_with(|| vec![0.0; n_actions]);
+        if regrets.len() != n_actions {
+            *regrets = vec![0.0; n_actions];
+            self.strategy_sum.remove(key);
+        }
         regret_to_strategy(regrets)
     }
 
@@ -94,19 +126,20 @@ impl RegretTable {
         // Since we iterate all hero hands, the "reach prob" is the probability of having that hand (range weight).
 
         let current_strategy = regret_to_strategy(regrets);
-        let strategy_sum = self
+        let (strategy_sum, visits) = self
             .strategy_sum
             .entry(key)
-            .or_insert_with(|| vec![0.0; n_actions]);
+            .or_insert_with(|| (vec![0.0; n_actions], 0));
 
         for (i, &prob) in current_strategy.iter().enumerate() {
             strategy_sum[i] += prob * reach_prob;
         }
+        *visits += 1;
     }
 
     /// Get the average strategy for an info set (converged strategy).
     pub fn get_average_strategy(&self, key: &InfoSetKey) -> Option<Vec<f64>> {
-        self.strategy_sum.get(key).map(|sum| {
+        self.strategy_sum.get(key).map(|(sum, _)| {
             let total: f64 = sum.iter().sum();
             if total > 0.0 {
                 sum.iter().map(|&s| s / total).collect()
@@ -118,6 +151,49 @@ impl RegretTable {
         })
     }
 
+    /// Raw (unnormalized) strategy sums for an info set
+    pub fn get_strategy_sum(&self, key: &InfoSetKey) -> Option<&[f64]> {
+        self.strategy_sum.get(key).map(|(sum, _)| sum.as_slice())
+    }
+
+    /// Get the current regret-matched strategy for an info set, without
+    /// creating it.
+    pub fn get_current_strategy(&self, key: &InfoSetKey) -> Option<Vec<f64>> {
+        self.regrets
+            .get(key)
+            .map(|regrets| regret_to_strategy(regrets))
+    }
+
+    /// Record the utility of every action observed on one visit to an info set.
+    pub fn record_action_values(&mut self, key: &InfoSetKey, values: &[f64]) {
+        self.record_weighted_action_values(key, values, 1.0);
+    }
+
+    /// Record one visit's action utilities counting `weight` times, for
+    /// visits that stand for more or less than one sample.
+    pub fn record_weighted_action_values(&mut self, key: &InfoSetKey, values: &[f64], weight: f64) {
+        let (sum, visits) = self
+            .action_values
+            .entry(key.clone())
+            .or_insert_with(|| (vec![0.0; values.len()], 0.0));
+        for (i, &v) in values.iter().enumerate() {
+            sum[i] += weight * v;
+        }
+        *visits += weight;
+    }
+
+    /// Get the average recorded utility per action for an info set.
+    pub fn get_action_values(&self, key: &InfoSetKey) -> Option<Vec<f64>> {
+        self.action_values
+            .get(key)
+            .map(|(sum, visits)| sum.iter().map(|&v| v / visits).collect())
+    }
+
+    /// Number of regret updates made at an info set, 0 if never reached
+    pub fn visits(&self, key: &InfoSetKey) -> u64 {
+        self.strategy_sum.get(key).map_or(0, |(_, visits)| *visits)
+    }
+
     /// Get all info set keys.
     pub fn keys(&self) -> impl Iterator<Item = &InfoSetKey> {
         self.strategy_sum.keys()
@@ -0,0 +1,171 @@
+//! Memoized solves for repeated spots
+//!
+//! A REPL session or a batch file often asks for the same spot more than
+//! once. `SolveCache` keeps the most recently used strategies keyed by the
+//! game state and the solver settings, so a repeat returns the earlier
+//! strategy instead of solving again. Only solves that repeat exactly are
+//! cached: an unseeded MCCFR solve draws a fresh sample every time.
+
+use crate::error::Result;
+use crate::models::{GameState, Strategy};
+use crate::solver::cfr::{solve_with_kind, SolverKind};
+use crate::solver::mccfr::MccfrConfig;
+use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Bounded least-recently-used cache of solved strategies, safe to share
+/// across threads
+#[derive(Debug)]
+pub struct SolveCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Strategies by key, each with the tick it was last used at
+#[derive(Debug, Default)]
+struct Entries {
+    strategies: FxHashMap<String, (Strategy, u64)>,
+    tick: u64,
+}
+
+impl Default for SolveCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl SolveCache {
+    /// Strategies kept by `SolveCache::default()`
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Create an empty cache holding at most `capacity` strategies; a
+    /// capacity of 0 caches nothing
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// `solve_with_kind`, returning the cached strategy when the same spot
+    /// was solved with the same settings before
+    pub fn solve(
+        &self,
+        game_state: GameState,
+        kind: SolverKind,
+        config: MccfrConfig,
+    ) -> Result<Strategy> {
+        let key = cache_key(&game_state, kind, &config);
+        self.get_or_insert_with(key, || solve_with_kind(game_state, kind, config))
+    }
+
+    /// The strategy cached for `game_state` solved by `kind` with `config`,
+    /// or the result of `solve`, which is cached if it succeeds.
+    ///
+    /// Unseeded MCCFR solves, and solves warm-started from a regret table
+    /// or tied to a stop flag, would not repeat from the settings alone, so
+    /// they always run `solve` and count as neither a hit nor a miss.
+    pub fn get_or_solve<E>(
+        &self,
+        game_state: &GameState,
+        kind: SolverKind,
+        config: &MccfrConfig,
+        solve: impl FnOnce() -> std::result::Result<Strategy, E>,
+    ) -> std::result::Result<Strategy, E> {
+        self.get_or_insert_with(cache_key(game_state, kind, config), solve)
+    }
+
+    fn get_or_insert_with<E>(
+        &self,
+        key: Option<String>,
+        solve: impl FnOnce() -> std::result::Result<Strategy, E>,
+    ) -> std::result::Result<Strategy, E> {
+        let Some(key) = key else {
+            return solve();
+        };
+        if let Some(strategy) = self.lookup(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(strategy);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        // Solve without holding the lock so other spots stay available
+        let strategy = solve()?;
+        self.insert(key, strategy.clone());
+        Ok(strategy)
+    }
+
+    fn lookup(&self, key: &str) -> Option<Strategy> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        let (strategy, last_used) = entries.strategies.get_mut(key)?;
+        *last_used = tick;
+        Some(strategy.clone())
+    }
+
+    fn insert(&self, key: String, strategy: Strategy) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.strategies.len() >= self.capacity && !entries.strategies.contains_key(&key) {
+            let oldest = entries
+                .strategies
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.strategies.remove(&oldest);
+            }
+        }
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.strategies.insert(key, (strategy, tick));
+    }
+
+    /// Lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Lookups that had to solve
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Number of cached strategies
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().strategies.len()
+    }
+
+    /// Returns true if nothing has been cached yet
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Key for solving `game_state` with `kind` and `config`, `None` if the
+/// solve can't be cached. The config goes in whole, through its `Debug`
+/// form, so a new setting is part of the key without touching this;
+/// settings that only change how a solve is reported (`profile`) are reset
+/// first.
+fn cache_key(game_state: &GameState, kind: SolverKind, config: &MccfrConfig) -> Option<String> {
+    let unseeded = kind == SolverKind::Mccfr && config.seed.is_none();
+    if unseeded || config.warm_start.is_some() || config.stop.is_some() {
+        return None;
+    }
+    let mut config = config.clone();
+    config.profile = false;
+    Some(format!(
+        "{}|{:?}|{:?}",
+        game_state.cache_key(),
+        kind,
+        config
+    ))
+}
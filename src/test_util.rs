@@ -0,0 +1,94 @@
+//! Proptest strategies generating valid cards, hands and ranges, plus
+//! property tests of the invariants they should uphold, and the card
+//! fixtures tests share.
+
+use crate::models::{card::Card, hand::Hand, range::Range};
+use proptest::prelude::*;
+use std::str::FromStr;
+
+/// A dry rainbow river, the board most solver tests play on
+pub const DRY_RIVER: &str = "Ks8d4c2h7s";
+
+/// Cards from notation such as "Ks8d4c" or "Ks 8d 4c"
+///
+/// # Panics
+///
+/// On anything that isn't a list of cards.
+pub fn cards(notation: &str) -> Vec<Card> {
+    let packed: String = notation.split_whitespace().collect();
+    (0..packed.len())
+        .step_by(2)
+        .map(|i| Card::from_str(&packed[i..i + 2]).unwrap())
+        .collect()
+}
+
+/// Any of the 52 cards
+pub fn arb_card() -> impl Strategy<Value = Card> {
+    (0u8..52).prop_map(|i| Card::from_pokers_index(i).unwrap())
+}
+
+/// Two distinct cards, in either order
+pub fn arb_hand() -> impl Strategy<Value = Hand> {
+    (0u8..52, 0u8..51).prop_map(|(a, b)| {
+        // Skip over `a` so the second card is always different
+        let b = if b >= a { b + 1 } else { b };
+        Hand::new(
+            Card::from_pokers_index(a).unwrap(),
+            Card::from_pokers_index(b).unwrap(),
+        )
+    })
+}
+
+/// A non-empty range of canonical combos with weights in (0, 1]
+pub fn arb_range() -> impl Strategy<Value = Range> {
+    prop::collection::vec((arb_hand(), 1u8..=100), 1..40).prop_map(|combos| {
+        let mut range = Range::new();
+        for (hand, weight) in combos {
+            range.add(hand, weight as f64 / 100.0);
+        }
+        range
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    proptest! {
+        #[test]
+        fn card_notation_round_trips(card in arb_card()) {
+            prop_assert_eq!(Card::from_str(&card.notation()).unwrap(), card);
+        }
+
+        #[test]
+        fn canonical_is_idempotent(hand in arb_hand()) {
+            let canonical = hand.canonical();
+            prop_assert_eq!(canonical.canonical(), canonical.clone());
+            prop_assert!(canonical.contains_card(&hand.cards[0]));
+            prop_assert!(canonical.contains_card(&hand.cards[1]));
+        }
+
+        #[test]
+        fn range_notation_round_trips(range in arb_range()) {
+            let parsed = Range::from_notation(&range.to_notation()).unwrap();
+            prop_assert_eq!(parsed.len(), range.len());
+            for (hand, _) in &range {
+                prop_assert!(parsed.contains(hand), "missing {}", hand.combo_notation());
+            }
+        }
+
+        #[test]
+        fn remove_blockers_never_adds_combos(
+            range in arb_range(),
+            blockers in prop::collection::vec(arb_card(), 0..5),
+        ) {
+            let mut blocked = range.clone();
+            blocked.remove_blockers(&blockers);
+            prop_assert!(blocked.len() <= range.len());
+            for (hand, _) in &blocked {
+                prop_assert!(!blockers.iter().any(|c| hand.contains_card(c)));
+            }
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::process::Command;
 use std::str;
 
 #[test]
+#[allow(clippy::needless_borrows_for_generic_args)]
 fn test_cli_error_messages() {
     // 1. Invalid Card
     let output = Command::new("cargo")
@@ -95,3 +96,451 @@ fn test_cli_error_messages() {
             || stderr.contains("invalid value")
     );
 }
+
+#[test]
+fn test_cli_spr_sets_stack() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--villain-range",
+            "QQ",
+            "--pot",
+            "10",
+            "--spr",
+            "5",
+            "--iterations",
+            "10",
+            "--json",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["game_state"]["effective_stack"], 50.0);
+
+    // Both --stack and --spr is ambiguous
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--villain-range",
+            "QQ",
+            "--pot",
+            "10",
+            "--stack",
+            "50",
+            "--spr",
+            "5",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(!output.status.success());
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("only one of --stack or --spr"));
+}
+
+#[test]
+fn test_cli_hero_range_category_report() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero-range",
+            "88,J9o",
+            "--board",
+            "Ks8d4c2h7s",
+            "--villain-range",
+            "AK,QQ,JJ",
+            "--pot",
+            "10",
+            "--stack",
+            "50",
+            "--iterations",
+            "200",
+            "--report",
+            "categories",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains("Category"));
+    assert!(stdout.contains("set"));
+    assert!(stdout.contains("air"));
+}
+
+#[test]
+fn test_cli_villain_perspective_reports_villain_strategy() {
+    let analyze = |acting: &str, opponent: &str, position: &str, extra: &[&str]| {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                "analyze",
+                "--hero-range",
+                acting,
+                "--villain-range",
+                opponent,
+                "--board",
+                "Ks8d4c2h7s",
+                "--pot",
+                "10",
+                "--stack",
+                "50",
+                "--position",
+                position,
+                "--iterations",
+                "200",
+                "--seed",
+                "5",
+            ])
+            .args(extra)
+            .output()
+            .expect("Failed to run CLI");
+        assert!(output.status.success(), "{:?}", output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // Strong hero in position against a draw-heavy villain: the two players
+    // hold different ranges, so their strategies differ
+    let hero = analyze("AA,KK", "QJs,T9s,65s", "IP", &["--json"]);
+    let villain = analyze(
+        "AA,KK",
+        "QJs,T9s,65s",
+        "IP",
+        &["--json", "--perspective", "villain"],
+    );
+    assert_ne!(hero, villain);
+
+    // The villain view is villain's range acting out of position
+    let villain_acting = analyze("QJs,T9s,65s", "AA,KK", "OOP", &["--json"]);
+    assert_eq!(villain, villain_acting);
+
+    let summary = analyze("AA,KK", "QJs,T9s,65s", "IP", &["--perspective", "villain"]);
+    assert!(
+        summary.contains("Villain Range: QJs,T9s,65s"),
+        "{}",
+        summary
+    );
+}
+
+#[test]
+fn test_cli_villain_perspective_rejects_a_bet_to_call() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--villain-range",
+            "QQ+",
+            "--board",
+            "Ks8d4c2h7s",
+            "--pot",
+            "10",
+            "--stack",
+            "50",
+            "--to-call",
+            "5",
+            "--perspective",
+            "villain",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("--to-call 0"), "{}", stderr);
+}
+
+#[test]
+fn test_cli_exit_codes_and_quiet() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhXX",
+            "--villain-range",
+            "AA",
+            "--pot",
+            "10",
+            "--stack",
+            "100",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert_eq!(output.status.code(), Some(fpe::CliError::PARSE_EXIT_CODE));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--board",
+            "Ts9s2h",
+            "--villain-range",
+            "QQ",
+            "--pot",
+            "10",
+            "--stack",
+            "100",
+            "--iterations",
+            "100",
+            "--quiet",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    assert!(!stdout.contains("Input Summary"));
+    assert!(!stdout.contains("Strategy computed"));
+    assert!(stdout.contains("Check"));
+}
+
+#[test]
+fn test_cli_precision() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--board",
+            "Ts9s2h",
+            "--villain-range",
+            "QQ",
+            "--pot",
+            "10",
+            "--stack",
+            "100",
+            "--iterations",
+            "100",
+            "--quiet",
+            "--precision",
+            "3",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+
+    // Every frequency has three decimals after a '.', never a ','
+    let frequencies: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split('|').nth(2))
+        .map(str::trim)
+        .filter(|cell| cell.ends_with('%'))
+        .collect();
+    assert!(!frequencies.is_empty());
+    for frequency in frequencies {
+        let (_, decimals) = frequency.trim_end_matches('%').split_once('.').unwrap();
+        assert_eq!(decimals.len(), 3, "{}", frequency);
+    }
+    assert!(!stdout.contains(','));
+}
+
+#[test]
+fn test_cli_list_ranges() {
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "--list-ranges"])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains("@BTN_open"));
+    assert!(stdout.contains("@BB_defend"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--villain-range",
+            "@CO_limp",
+            "--pot",
+            "10",
+            "--stack",
+            "100",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert_eq!(output.status.code(), Some(fpe::CliError::PARSE_EXIT_CODE));
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Unknown preset"), "{}", stderr);
+}
+
+#[test]
+fn test_cli_sort_canonical() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "analyze",
+            "--hero",
+            "AhKd",
+            "--board",
+            "Ts9s2h",
+            "--villain-range",
+            "QQ",
+            "--pot",
+            "10",
+            "--stack",
+            "100",
+            "--iterations",
+            "100",
+            "--quiet",
+            "--sort",
+            "canonical",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+
+    let rows: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.split('|').nth(1))
+        .map(str::trim)
+        .filter(|cell| *cell != "Action")
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            "Check",
+            "Bet 50% pot (5.0 BB)",
+            "Bet 100% pot (10.0 BB)",
+            "All-In"
+        ]
+    );
+}
+
+#[test]
+fn test_cli_compare_sizes() {
+    // A set against a combo draw: small bets let the draw call with the
+    // right price, a pot-sized bet folds it out and takes the whole pot
+    let run = |to_call: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                "analyze",
+                "--hero",
+                "9c9d",
+                "--board",
+                "9hTh2s4c",
+                "--villain-range",
+                "QhJh",
+                "--pot",
+                "10",
+                "--stack",
+                "50",
+                "--to-call",
+                to_call,
+                "--iterations",
+                "2000",
+                "--seed",
+                "1",
+                "--quiet",
+                "--compare-sizes",
+                "0.25pot,0.5pot,pot",
+            ])
+            .output()
+            .expect("Failed to run CLI")
+    };
+
+    let output = run("0");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+
+    let comparison = &stdout[stdout.find("Pure EV").expect(stdout)..];
+    let sizes: Vec<&str> = comparison
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split('|').nth(1))
+        .map(str::trim)
+        .collect();
+    assert_eq!(sizes, ["Bet 100% pot", "Bet 50% pot", "Bet 25% pot"]);
+    assert!(
+        stdout.contains("Best size: Bet 100% pot (+10.00 BB pure"),
+        "{}",
+        stdout
+    );
+
+    let output = run("5");
+    assert_eq!(output.status.code(), Some(fpe::CliError::PARSE_EXIT_CODE));
+    let stderr = str::from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("--compare-sizes"), "{}", stderr);
+}
+
+#[test]
+fn test_cli_equity_by_runout() {
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "equity",
+            "--hero",
+            "AhKh",
+            "--board",
+            "9h8h2c",
+            "--villain-range",
+            "QQ,JJ",
+            "--by-runout",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert!(output.status.success());
+    let stdout = str::from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains("Equity: 52.7%"), "{}", stdout);
+    assert!(stdout.contains("| Rank |"), "{}", stdout);
+    assert!(stdout.contains("Best: 3♥ 100.0%"), "{}", stdout);
+
+    // A river has no next card
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "equity",
+            "--hero",
+            "AhKh",
+            "--board",
+            "9h8h2c3d4s",
+            "--villain-range",
+            "QQ",
+            "--by-runout",
+        ])
+        .output()
+        .expect("Failed to run CLI");
+    assert_eq!(output.status.code(), Some(2));
+}
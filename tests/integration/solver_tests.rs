@@ -1,5 +1,7 @@
 use fpe::models::{action::Action, Card, GameState, Hand, Position, Range};
-use fpe::solver::solve;
+use fpe::solver::equity::calculate_equity_runout;
+use fpe::solver::{solve, solve_with_config, solve_with_kind, MccfrConfig, SolverKind};
+use fpe::test_util::{cards, DRY_RIVER};
 use std::str::FromStr;
 
 #[test]
@@ -106,6 +108,100 @@ fn test_solver_nuts_vs_air_bet_frequency() {
     );
 }
 
+#[test]
+fn test_solver_prefers_marginal_call_over_fold() {
+    // Facing 5 into 10 on the river, QQ beats JJ and loses to AKs.
+    // Calling wins 20 about 29% of the time for a 5 chip price (+0.7 BB),
+    // so folding must not look free.
+    let hero = Hand::from_str("QhQd").unwrap();
+    let board = vec![
+        Card::from_str("Ks").unwrap(),
+        Card::from_str("8d").unwrap(),
+        Card::from_str("4c").unwrap(),
+        Card::from_str("2h").unwrap(),
+        Card::from_str("7s").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("JJ@20,AKs").unwrap();
+    villain_range.remove_blockers(&board);
+
+    let state = GameState::new(hero, board, 15.0, 95.0, 5.0, Position::OOP, villain_range).unwrap();
+
+    let strategy = solve(state, 5000).unwrap();
+
+    let freq = |pred: fn(&Action) -> bool| -> f64 {
+        strategy
+            .actions
+            .iter()
+            .filter(|a| pred(&a.action))
+            .map(|a| a.frequency)
+            .sum()
+    };
+    let fold_freq = freq(|a| matches!(a, Action::Fold));
+    let call_freq = freq(|a| matches!(a, Action::Call));
+
+    assert!(
+        call_freq > fold_freq,
+        "Calling should beat folding, got call {} vs fold {}",
+        call_freq,
+        fold_freq
+    );
+    assert!(fold_freq < 0.2, "Fold should be rare, got {}", fold_freq);
+}
+
+#[test]
+fn test_solver_turn_flush_draw_realizes_equity() {
+    // Nut flush draw plus overcards against top pair on the turn: behind now,
+    // but the river gives hero 15 outs, so betting is profitable.
+    let hero = Hand::from_str("AhKh").unwrap();
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("7h").unwrap(),
+        Card::from_str("2c").unwrap(),
+        Card::from_str("3s").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("QJo").unwrap();
+    villain_range.remove_blockers(&board);
+
+    let state = GameState::new(
+        hero.clone(),
+        board.clone(),
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        villain_range.clone(),
+    )
+    .unwrap();
+
+    let strategy = solve(state, 2000).unwrap();
+
+    let ev_of = |pred: fn(&Action) -> bool| {
+        strategy
+            .actions
+            .iter()
+            .find(|a| pred(&a.action))
+            .map(|a| a.ev)
+            .unwrap()
+    };
+    let check_ev = ev_of(|a| matches!(a, Action::Check));
+    let bet_ev = ev_of(|a| matches!(a, Action::Bet(_)));
+
+    // Checking back realizes hero's river equity in the pot
+    let equity = calculate_equity_runout(&hero, &villain_range, &board);
+    assert!(
+        (check_ev - 10.0 * equity.win).abs() < 0.5,
+        "Check EV {} should match realized equity {}",
+        check_ev,
+        equity.win
+    );
+
+    assert!(
+        bet_ev > 1.0,
+        "Betting the draw should be +EV, got {}",
+        bet_ev
+    );
+}
+
 #[test]
 fn test_solver_convergence_metric() {
     let hero = Hand::from_str("AhKh").unwrap();
@@ -158,6 +254,250 @@ fn test_solver_convergence_improvement() {
     );
 }
 
+#[test]
+fn test_solve_range_vs_range() {
+    use fpe::solver::mccfr::MccfrConfig;
+    use fpe::solver::solve_range_vs_range;
+
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("Jh").unwrap(),
+        Card::from_str("Th").unwrap(),
+        Card::from_str("2s").unwrap(),
+        Card::from_str("3d").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("22+").unwrap();
+    villain_range.remove_blockers(&board);
+
+    // hero_hand is replaced by each combo of the hero range
+    let state = GameState::new(
+        Hand::from_str("AsAd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        villain_range,
+    )
+    .unwrap();
+
+    // QhQs is blocked by the board and skipped
+    let hero_range = Range::from_notation("AhKh,7c4d,QhQs").unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(500)
+        .samples_per_iteration(10)
+        .seed(7)
+        .build();
+
+    let result = solve_range_vs_range(&state, &hero_range, config).unwrap();
+
+    assert_eq!(result.combos.len(), 2);
+    let royal = result
+        .combos
+        .iter()
+        .find(|c| c.hand == Hand::from_str("AhKh").unwrap())
+        .expect("royal flush combo solved");
+    let royal_bet: f64 = royal
+        .strategy
+        .actions
+        .iter()
+        .filter(|a| matches!(a.action, Action::Bet(_) | Action::AllIn))
+        .map(|a| a.frequency)
+        .sum();
+    assert!(royal_bet > 0.9, "Royal flush should bet, got {}", royal_bet);
+
+    let total: f64 = result.aggregate().iter().map(|a| a.frequency).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_merge_range_chunks() {
+    use fpe::solver::solve_range_vs_range;
+
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("Jh").unwrap(),
+        Card::from_str("Th").unwrap(),
+        Card::from_str("2s").unwrap(),
+        Card::from_str("3d").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("22+").unwrap();
+    villain_range.remove_blockers(&board);
+    let state = GameState::new(
+        Hand::from_str("AsAd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        villain_range,
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(300)
+        .samples_per_iteration(10)
+        .seed(3)
+        .build();
+    let solve_range = |notation: &str| {
+        let hero_range = Range::from_notation(notation).unwrap();
+        solve_range_vs_range(&state, &hero_range, config.clone()).unwrap()
+    };
+
+    let first = solve_range("AhKh,7c4d");
+    let second = solve_range("KsKc");
+    let combined = solve_range("AhKh,7c4d,KsKc");
+
+    let merged = first.merge(std::slice::from_ref(&second)).unwrap();
+    assert_eq!(merged.combos.len(), 3);
+    for (m, c) in merged.aggregate().iter().zip(combined.aggregate()) {
+        assert_eq!(m.action, c.action);
+        assert!((m.frequency - c.frequency).abs() < 1e-9);
+        assert!((m.ev - c.ev).abs() < 1e-9);
+    }
+
+    // A combo solved in two chunks is rejected
+    assert!(merged.merge(&[second]).is_err());
+}
+
+#[test]
+fn test_weighted_hero_range_aggregate() {
+    use fpe::models::RangeStrategy;
+    use fpe::solver::solve_range_vs_range;
+
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("Jh").unwrap(),
+        Card::from_str("Th").unwrap(),
+        Card::from_str("2s").unwrap(),
+        Card::from_str("3d").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("22+").unwrap();
+    villain_range.remove_blockers(&board);
+    let state = GameState::new(
+        Hand::from_str("AsAd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        villain_range,
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(300)
+        .samples_per_iteration(10)
+        .seed(3)
+        .build();
+    let solve_range = |notation: &str| {
+        let hero_range = Range::from_notation(notation).unwrap();
+        solve_range_vs_range(&state, &hero_range, config.clone()).unwrap()
+    };
+    let bet_frequency = |strategy: &RangeStrategy| -> f64 {
+        strategy
+            .aggregate()
+            .iter()
+            .filter(|a| matches!(a.action, Action::Bet(_) | Action::AllIn))
+            .map(|a| a.frequency)
+            .sum()
+    };
+    // Weighted bet combos in the aggregate: frequency times total weight
+    let bet_combos = |strategy: &RangeStrategy| -> f64 {
+        let total_weight: f64 = strategy.combos.iter().map(|c| c.weight).sum();
+        bet_frequency(strategy) * total_weight
+    };
+
+    let full = solve_range("AhKh,7c4d");
+    let halved = solve_range("AhKh:0.5,7c4d");
+    let bluff_only = solve_range("7c4d");
+    let royal = Hand::from_str("AhKh").unwrap();
+    let weight = |strategy: &RangeStrategy| {
+        strategy
+            .combos
+            .iter()
+            .find(|c| c.hand == royal)
+            .unwrap()
+            .weight
+    };
+    assert_eq!(weight(&full), 1.0);
+    assert_eq!(weight(&halved), 0.5);
+
+    let royal_full = bet_combos(&full) - bet_combos(&bluff_only);
+    let royal_halved = bet_combos(&halved) - bet_combos(&bluff_only);
+    assert!(
+        royal_full > 0.5,
+        "Royal flush should bet, got {}",
+        royal_full
+    );
+    assert!(
+        (royal_halved - royal_full / 2.0).abs() < 1e-9,
+        "halved {} vs full {}",
+        royal_halved,
+        royal_full
+    );
+}
+
+#[test]
+fn test_range_report_by_category() {
+    use fpe::cli::output::format_category_report;
+    use fpe::solver::evaluator::HandCategory;
+    use fpe::solver::mccfr::MccfrConfig;
+    use fpe::solver::range_solve::by_category;
+    use fpe::solver::solve_range_vs_range;
+
+    let board = vec![
+        Card::from_str("Ks").unwrap(),
+        Card::from_str("8d").unwrap(),
+        Card::from_str("4c").unwrap(),
+        Card::from_str("2h").unwrap(),
+        Card::from_str("7s").unwrap(),
+    ];
+    let mut villain_range = Range::from_notation("AK,QQ,JJ").unwrap();
+    villain_range.remove_blockers(&board);
+
+    let state = GameState::new(
+        Hand::from_str("8h8s").unwrap(),
+        board.clone(),
+        10.0,
+        50.0,
+        0.0,
+        Position::IP,
+        villain_range,
+    )
+    .unwrap();
+
+    // 88 is a set, J9o is air
+    let hero_range = Range::from_notation("88,J9o").unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(500)
+        .samples_per_iteration(10)
+        .seed(3)
+        .build();
+    let result = solve_range_vs_range(&state, &hero_range, config).unwrap();
+
+    let groups = by_category(&result, &board);
+    assert_eq!(
+        groups.keys().copied().collect::<Vec<_>>(),
+        vec![HandCategory::Set, HandCategory::Air]
+    );
+
+    let bet_freq = |category: HandCategory| -> f64 {
+        groups[&category]
+            .aggregate()
+            .iter()
+            .filter(|a| matches!(a.action, Action::Bet(_) | Action::AllIn))
+            .map(|a| a.frequency)
+            .sum()
+    };
+    assert!(
+        bet_freq(HandCategory::Set) > bet_freq(HandCategory::Air) + 0.5,
+        "Set should bet far more often than air"
+    );
+
+    let report = format_category_report(&result, &board);
+    assert!(report.contains("set"));
+    assert!(report.contains("air"));
+}
+
 #[test]
 fn test_solve_with_config() {
     use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
@@ -174,12 +514,12 @@ fn test_solve_with_config() {
 
     let state = GameState::new(hero, board, 10.0, 100.0, 0.0, Position::IP, villain_range).unwrap();
 
-    let config = MccfrConfig {
-        iterations: 50,
-        samples_per_iteration: 10,
-        convergence_threshold: 0.001,
-        seed: Some(42),
-    };
+    let config = MccfrConfig::builder()
+        .iterations(50)
+        .samples_per_iteration(10)
+        .convergence_threshold(0.001)
+        .seed(42)
+        .build();
 
     let strategy = solve_with_config(state, config).unwrap();
 
@@ -204,14 +544,14 @@ fn test_solver_complex_game_tree() {
         GameState::new(hero, board, 10.0, 100.0, 0.0, Position::IP, villain_range).unwrap();
 
     // Add multiple actions
-    state.available_actions = vec![
+    state.available_actions = Some(vec![
         Action::Check,
         Action::Bet(BetSize::PotFraction(0.33)),
         Action::Bet(BetSize::PotFraction(0.5)),
         Action::Bet(BetSize::PotFraction(0.75)),
         Action::Bet(BetSize::PotFraction(1.0)),
         Action::AllIn,
-    ];
+    ]);
 
     let strategy = solve(state, 1000).unwrap();
 
@@ -222,3 +562,395 @@ fn test_solver_complex_game_tree() {
     let sum: f64 = strategy.actions.iter().map(|a| a.frequency).sum();
     assert!((sum - 1.0).abs() < 0.001);
 }
+
+#[test]
+fn test_best_response_bets_into_folding_opponent() {
+    use fpe::solver::{best_response, StrategyTree};
+
+    let board = ["Qh", "Jh", "Th", "2s", "3d"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    // Hero has nothing, but villain never calls a bet
+    let state = GameState::new(
+        Hand::from_str("7c4c").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::OOP,
+        Range::from_notation("AA,KK").unwrap(),
+    )
+    .unwrap();
+
+    // Root is hero's check/bet; villain checks back or folds to the bet
+    let always_fold = StrategyTree::new(
+        vec![],
+        vec![
+            StrategyTree::new(vec![1.0, 0.0], vec![]),
+            StrategyTree::new(vec![1.0, 0.0], vec![]),
+        ],
+    );
+    let strategy = best_response(&state, &always_fold).unwrap();
+
+    let bet = &strategy.actions[1];
+    assert!(matches!(bet.action, Action::Bet(_)));
+    assert_eq!(bet.frequency, 1.0);
+    assert_eq!(bet.ev, 10.0);
+    assert_eq!(strategy.actions[0].frequency, 0.0);
+    assert_eq!(strategy.actions[0].ev, 0.0);
+}
+
+#[test]
+fn test_uniform_solver_preview() {
+    let state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![
+            Card::from_str("Ts").unwrap(),
+            Card::from_str("9s").unwrap(),
+            Card::from_str("2h").unwrap(),
+        ],
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("QQ").unwrap(),
+    )
+    .unwrap();
+
+    let strategy = solve_with_kind(state, SolverKind::Uniform, MccfrConfig::default()).unwrap();
+
+    assert_eq!(strategy.iterations, 0);
+    assert!(strategy.actions.len() > 1);
+    let first = strategy.actions[0].frequency;
+    assert!(strategy.actions.iter().all(|a| a.frequency == first));
+    assert_eq!(first, 1.0 / strategy.actions.len() as f64);
+    assert!(strategy.is_valid());
+}
+
+#[test]
+fn test_solve_with_progress_channel() {
+    use fpe::solver::{solve_observed, solve_with_progress_channel, SharedEvalCache};
+    use std::sync::{atomic::AtomicBool, Arc};
+
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("Jh").unwrap(),
+        Card::from_str("Th").unwrap(),
+        Card::from_str("2s").unwrap(),
+        Card::from_str("3d").unwrap(),
+    ];
+    let state = GameState::new(
+        Hand::from_str("AhKh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("22+").unwrap(),
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(1000)
+        .samples_per_iteration(5)
+        .seed(9)
+        .build();
+
+    let (progress, handle) = solve_with_progress_channel(state.clone(), config.clone());
+    let snapshots: Vec<_> = progress.iter().collect();
+    let strategy = handle.join().unwrap();
+
+    assert!(snapshots.len() >= 5, "got {} snapshots", snapshots.len());
+    assert!(snapshots
+        .windows(2)
+        .all(|w| w[0].iteration < w[1].iteration));
+    assert_eq!(snapshots.last().unwrap().iteration, 1000);
+    assert_eq!(strategy.iterations, 1000);
+    assert!(strategy.is_valid());
+
+    // A raised stop flag cancels the solve before the first iteration
+    let stop = Arc::new(AtomicBool::new(true));
+    let mut stopped = config.clone();
+    stopped.stop = Some(stop);
+    let (progress, handle) = solve_with_progress_channel(state.clone(), stopped);
+    let snapshots: Vec<_> = progress.iter().collect();
+    let strategy = handle.join().unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(strategy.iterations, 0);
+
+    // An observer that declines a snapshot stops the solve at that check
+    let (strategy, _) = solve_observed(state, config, &SharedEvalCache::new(), &mut |s| {
+        s.iteration < 300
+    })
+    .unwrap();
+    assert_eq!(strategy.iterations, 300);
+    assert!(strategy.is_valid());
+}
+
+#[test]
+fn test_seeded_json_records_provenance() {
+    use fpe::solver::{solve_observed, SharedEvalCache};
+
+    let board = vec![
+        Card::from_str("Qh").unwrap(),
+        Card::from_str("Jh").unwrap(),
+        Card::from_str("Th").unwrap(),
+        Card::from_str("2s").unwrap(),
+        Card::from_str("3d").unwrap(),
+    ];
+    let state = GameState::new(
+        Hand::from_str("AhKh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("22+").unwrap(),
+    )
+    .unwrap();
+
+    let unseeded = solve(state.clone(), 100).unwrap();
+    let json = serde_json::to_value(&unseeded).unwrap();
+    assert!(json.get("provenance").is_none());
+
+    // Stop at the first progress check, well short of the requested count
+    let config = MccfrConfig::builder()
+        .iterations(1000)
+        .samples_per_iteration(5)
+        .seed(42)
+        .build();
+    let (strategy, _) =
+        solve_observed(state, config, &SharedEvalCache::new(), &mut |_| false).unwrap();
+
+    let json = serde_json::to_value(&strategy).unwrap();
+    let provenance = &json["provenance"];
+    assert_eq!(provenance["seed"], 42);
+    assert_eq!(provenance["iterations"], 100);
+    assert_eq!(strategy.iterations, 100);
+    assert_eq!(provenance["solver"], "mccfr");
+    assert_eq!(provenance["version"], env!("CARGO_PKG_VERSION"));
+}
+
+#[test]
+fn test_turn_report_separates_flush_turns() {
+    use fpe::solver::range_solve::by_turn;
+
+    let board = vec![
+        Card::from_str("Ah").unwrap(),
+        Card::from_str("7h").unwrap(),
+        Card::from_str("2c").unwrap(),
+    ];
+    // Villain always holds a set: hero's flush draw is a value bet once it
+    // gets there and a hopeless bluff otherwise
+    let villain_range = Range::from_notation("AsAd,AsAc,AdAc").unwrap();
+    let state = GameState::new(
+        Hand::from_str("KhQh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        villain_range,
+    )
+    .unwrap();
+    let hero_range = Range::from_notation("KhQh").unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(300)
+        .samples_per_iteration(5)
+        .seed(11)
+        .build();
+
+    let turns = by_turn(&state, &hero_range, config).unwrap();
+    // 49 unseen cards, less the two that block hero's only combo
+    assert_eq!(turns.len(), 47);
+
+    let bet_frequency = |card: &str| -> f64 {
+        let card = Card::from_str(card).unwrap();
+        let (_, range_strategy) = turns.iter().find(|(turn, _)| *turn == card).unwrap();
+        range_strategy
+            .aggregate()
+            .iter()
+            .filter(|a| matches!(a.action, Action::Bet(_) | Action::Raise(_) | Action::AllIn))
+            .map(|a| a.frequency)
+            .sum()
+    };
+    let flush = bet_frequency("3h");
+    let blank = bet_frequency("4d");
+    assert!(
+        flush > blank + 0.5,
+        "flush turn {} vs blank turn {}",
+        flush,
+        blank
+    );
+}
+
+#[test]
+fn test_solve_rejects_spot_without_legal_actions() {
+    use fpe::models::action::BetSize;
+
+    let board = vec![
+        Card::from_str("Ah").unwrap(),
+        Card::from_str("7h").unwrap(),
+        Card::from_str("2c").unwrap(),
+    ];
+    let mut state = GameState::new(
+        Hand::from_str("KhQh").unwrap(),
+        board,
+        15.0,
+        95.0,
+        5.0,
+        Position::IP,
+        Range::from_notation("88").unwrap(),
+    )
+    .unwrap();
+    state.available_actions = Some(vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]);
+
+    let err = solve(state.clone(), 100).unwrap_err();
+    assert!(err.to_string().contains("No legal action"), "{}", err);
+    let err =
+        solve_with_kind(state.clone(), SolverKind::Uniform, MccfrConfig::default()).unwrap_err();
+    assert!(err.to_string().contains("No legal action"), "{}", err);
+
+    // An empty action set on the flop isn't a showdown
+    state.available_actions = Some(vec![]);
+    let err = solve(state.clone(), 100).unwrap_err();
+    assert!(
+        err.to_string().contains("No actions on the Flop"),
+        "{}",
+        err
+    );
+    let err = solve_with_kind(state, SolverKind::Uniform, MccfrConfig::default()).unwrap_err();
+    assert!(
+        err.to_string().contains("No actions on the Flop"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_convergence_history() {
+    let board = ["Ks", "Td", "7c", "4h", "2s"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("KQ,TT,QJ").unwrap(),
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(2000)
+        .samples_per_iteration(10)
+        .seed(5)
+        .record_convergence(true)
+        .build();
+
+    let strategy = solve_with_config(state.clone(), config.clone()).unwrap();
+    let history = &strategy.convergence_history;
+    // Checks every 200 iterations less the first, which has no baseline,
+    // plus the final check
+    assert_eq!(history.len(), 9);
+    assert_eq!(history.last().unwrap().0, 2000);
+    assert_eq!(history.last().unwrap().1, strategy.convergence);
+    for pair in history.windows(2) {
+        assert!(pair[1].0 > pair[0].0);
+        assert!(pair[1].1 <= pair[0].1 + 1e-9, "{:?}", history);
+    }
+
+    // Off by default, and then left out of the JSON
+    let mut config = config;
+    config.record_convergence = false;
+    let quiet = solve_with_config(state, config).unwrap();
+    assert!(quiet.convergence_history.is_empty());
+    let json = serde_json::to_string(&quiet).unwrap();
+    assert!(!json.contains("convergence_history"));
+}
+
+#[test]
+fn test_stop_when_std_err() {
+    use fpe::solver::StopWhen;
+
+    let board = ["Ks", "Td", "7c", "4h", "2s"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let state = GameState::new(
+        Hand::from_str("QhJh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("KQ,TT,QJ,98s").unwrap(),
+    )
+    .unwrap();
+    let solve_until = |epsilon: f64| {
+        let config = MccfrConfig::builder()
+            .iterations(4000)
+            .samples_per_iteration(5)
+            .seed(5)
+            .stop_when(StopWhen::StdErr(epsilon))
+            .build();
+        solve_with_config(state.clone(), config).unwrap().iterations
+    };
+
+    let loose = solve_until(0.05);
+    let tight = solve_until(0.001);
+    assert!(
+        loose < 4000,
+        "loose epsilon should stop early, ran {}",
+        loose
+    );
+    assert!(loose < tight, "loose {} vs tight {}", loose, tight);
+}
+
+#[test]
+fn test_icm_folds_a_bubble_call() {
+    use fpe::models::icm::IcmModel;
+
+    // Villain shoves 20 into 20 on the river; QQ beats the four JTs bluffs
+    // and loses to the three AKs, so calling wins 4/7 of the time against
+    // pot odds of 1/3
+    let board = cards(DRY_RIVER);
+    let mut villain_range = Range::from_notation("AKs,JTs").unwrap();
+    villain_range.remove_blockers(&board);
+    let state = GameState::new(
+        Hand::from_str("QhQd").unwrap(),
+        board,
+        40.0,
+        20.0,
+        20.0,
+        Position::OOP,
+        villain_range,
+    )
+    .unwrap();
+    let call_frequency = |icm: Option<IcmModel>| {
+        let config = MccfrConfig::builder()
+            .iterations(1000)
+            .samples_per_iteration(10)
+            .seed(3)
+            .icm(icm)
+            .build();
+        let strategy = solve_with_config(state.clone(), config).unwrap();
+        strategy
+            .actions
+            .iter()
+            .filter(|a| matches!(a.action, Action::Call))
+            .map(|a| a.frequency)
+            .sum::<f64>()
+    };
+
+    // On the bubble of a four-handed sit-and-go paying three, with two
+    // short stacks left, busting costs hero far more than the chips
+    let bubble = IcmModel::new(vec![20.0, 0.0, 5.0, 5.0], vec![0.5, 0.3, 0.2], 0, 1).unwrap();
+
+    let chip_ev = call_frequency(None);
+    let icm = call_frequency(Some(bubble));
+    assert!(chip_ev > 0.9, "chip EV should call, got {}", chip_ev);
+    assert!(icm < 0.1, "ICM should fold, got {}", icm);
+}
@@ -16,3 +16,159 @@ fn test_bet_size_display() {
     let raise = Action::Raise(BetSize::Amount(25.0));
     assert_eq!(raise.display_name(), "Raise to 25 BB");
 }
+
+#[test]
+fn test_raise_is_clamped_to_minimum() {
+    let (pot, stack, to_call) = (10.0, 100.0, 5.0);
+
+    // Raising by a tenth of the pot would be short of the 10 chip minimum
+    let tiny = Action::Raise(BetSize::PotFraction(0.1));
+    assert_eq!(tiny.amount(pot, stack, to_call), 10.0);
+
+    let tiny_amount = Action::Raise(BetSize::Amount(6.0));
+    assert_eq!(tiny_amount.amount(pot, stack, to_call), 10.0);
+    assert_eq!(Action::min_raise(to_call), 10.0);
+}
+
+#[test]
+fn test_oversized_raise_becomes_all_in() {
+    let (pot, stack, to_call) = (10.0, 30.0, 5.0);
+
+    let huge = Action::Raise(BetSize::Amount(500.0));
+    assert_eq!(huge.amount(pot, stack, to_call), 30.0);
+    assert_eq!(huge.normalize(pot, stack, to_call), Action::AllIn);
+
+    let small = Action::Raise(BetSize::Amount(15.0));
+    assert_eq!(small.normalize(pot, stack, to_call), small);
+}
+
+#[test]
+fn test_bet_size_from_str() {
+    assert_eq!(
+        "0.5pot".parse::<BetSize>().unwrap(),
+        BetSize::PotFraction(0.5)
+    );
+    assert_eq!("pot".parse::<BetSize>().unwrap(), BetSize::PotFraction(1.0));
+    assert_eq!("10bb".parse::<BetSize>().unwrap(), BetSize::Amount(10.0));
+    assert_eq!("allin".parse::<BetSize>().unwrap(), BetSize::AllIn);
+    assert_eq!(
+        "0.75POT".parse::<BetSize>().unwrap(),
+        BetSize::PotFraction(0.75)
+    );
+
+    assert_eq!("3x".parse::<BetSize>().unwrap(), BetSize::Multiplier(3.0));
+    assert_eq!("2.5X".parse::<BetSize>().unwrap(), BetSize::Multiplier(2.5));
+
+    for bad in ["", "half", "-1bb", "0pot", "xpot", "10", "x", "0x"] {
+        assert!(
+            bad.parse::<BetSize>().is_err(),
+            "'{}' should not parse",
+            bad
+        );
+    }
+}
+
+#[test]
+fn test_action_from_str() {
+    assert_eq!("fold".parse::<Action>().unwrap(), Action::Fold);
+    assert_eq!("check".parse::<Action>().unwrap(), Action::Check);
+    assert_eq!("call".parse::<Action>().unwrap(), Action::Call);
+    assert_eq!("allin".parse::<Action>().unwrap(), Action::AllIn);
+    assert_eq!(
+        "bet:0.5pot".parse::<Action>().unwrap(),
+        Action::Bet(BetSize::PotFraction(0.5))
+    );
+    assert_eq!(
+        "raise:25bb".parse::<Action>().unwrap(),
+        Action::Raise(BetSize::Amount(25.0))
+    );
+
+    // Parsed tokens render the same as hand-built actions
+    let cases = [
+        ("fold", "Fold"),
+        ("call", "Call"),
+        ("allin", "All-In"),
+        ("bet:0.75pot", "Bet 75% pot"),
+        ("raise:25bb", "Raise to 25 BB"),
+        ("raise:3x", "Raise 3x"),
+    ];
+    for (token, name) in cases {
+        assert_eq!(token.parse::<Action>().unwrap().display_name(), name);
+    }
+
+    for bad in ["", "shove", "bet", "bet:", "bet:huge", "limp:1bb", "bet:3x"] {
+        assert!(bad.parse::<Action>().is_err(), "'{}' should not parse", bad);
+    }
+}
+
+#[test]
+fn test_all_in_bet_size_commits_stack() {
+    let bet = Action::Bet(BetSize::AllIn);
+    assert_eq!(bet.amount(10.0, 40.0, 0.0), 40.0);
+    assert_eq!(bet.normalize(10.0, 40.0, 0.0), Action::AllIn);
+}
+
+#[test]
+fn test_canonical_action_order() {
+    let mut actions = vec![
+        Action::AllIn,
+        Action::Raise(BetSize::PotFraction(1.0)),
+        Action::Bet(BetSize::PotFraction(1.0)),
+        Action::Call,
+        Action::Bet(BetSize::PotFraction(0.33)),
+        Action::Raise(BetSize::PotFraction(0.5)),
+        Action::Check,
+        Action::Fold,
+    ];
+    actions.sort_by_key(Action::sort_key);
+    assert_eq!(
+        actions,
+        vec![
+            Action::Fold,
+            Action::Check,
+            Action::Call,
+            Action::Bet(BetSize::PotFraction(0.33)),
+            Action::Bet(BetSize::PotFraction(1.0)),
+            Action::Raise(BetSize::PotFraction(0.5)),
+            Action::Raise(BetSize::PotFraction(1.0)),
+            Action::AllIn,
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "only sizes a raise")]
+fn test_multiplier_bet_has_no_amount() {
+    Action::Bet(BetSize::Multiplier(3.0)).amount(5.0, 100.0, 0.0);
+}
+
+#[test]
+fn test_multiplier_raise_sizes_off_the_bet() {
+    let raise = Action::Raise(BetSize::Multiplier(3.0));
+    // Facing a 2 BB bet: raise to 6 BB
+    assert_eq!(raise.amount(5.0, 100.0, 2.0), 6.0);
+    // A short stack can't cover it and goes all-in
+    assert_eq!(raise.amount(5.0, 4.0, 2.0), 4.0);
+    assert_eq!(raise.normalize(5.0, 4.0, 2.0), Action::AllIn);
+    // Below 2x is still a legal minimum raise
+    let small = Action::Raise(BetSize::Multiplier(1.5));
+    assert_eq!(small.amount(5.0, 100.0, 2.0), Action::min_raise(2.0));
+
+    // Multipliers sort between pot fractions and fixed amounts
+    let mut raises = vec![
+        Action::Raise(BetSize::Amount(10.0)),
+        Action::Raise(BetSize::Multiplier(3.0)),
+        Action::Raise(BetSize::PotFraction(1.0)),
+        Action::Raise(BetSize::Multiplier(2.5)),
+    ];
+    raises.sort_by_key(Action::sort_key);
+    assert_eq!(
+        raises,
+        vec![
+            Action::Raise(BetSize::PotFraction(1.0)),
+            Action::Raise(BetSize::Multiplier(2.5)),
+            Action::Raise(BetSize::Multiplier(3.0)),
+            Action::Raise(BetSize::Amount(10.0)),
+        ]
+    );
+}
@@ -0,0 +1,81 @@
+use fpe::cli::analyze::{analyze, AnalyzeInput};
+use fpe::cli::args::AnalyzeRequest;
+use fpe::cli::equity::{equity, EquityInput};
+use fpe::cli::output::{format_analysis, format_equity, AnalysisFormat};
+use fpe::models::Action;
+use fpe::solver::{MccfrConfig, SolverKind};
+use fpe::ModelError;
+
+fn request(hero: &str, hero_range: &str) -> AnalyzeRequest {
+    AnalyzeRequest {
+        hero: hero.to_string(),
+        hero_range: hero_range.to_string(),
+        board: "Ts9s2h4c".to_string(),
+        villain_range: "QQ+,AK".to_string(),
+        pot: 10.0,
+        stack: 100.0,
+        iterations: 50,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_profiled_analyze_validates_actions() {
+    let config = MccfrConfig::builder().seed(1).profile(true).build();
+    let mut input =
+        AnalyzeInput::from_request(&request("AhKd", ""), SolverKind::Mccfr, config).unwrap();
+    let output = analyze(input.clone()).unwrap();
+    assert!(output.meta.profile.is_some());
+    assert!(output.meta.range.is_none());
+
+    // Calling is impossible with nothing to call
+    input.game_state.available_actions = Some(vec![Action::Call]);
+    assert!(matches!(
+        analyze(input),
+        Err(ModelError::InvalidGameState(_))
+    ));
+}
+
+#[test]
+fn test_range_analyze_aggregates_combos() {
+    let config = MccfrConfig::builder().seed(1).build();
+    let input =
+        AnalyzeInput::from_request(&request("", "AK,QQ"), SolverKind::Mccfr, config.clone())
+            .unwrap();
+    assert_eq!(input.hero_range.as_ref().unwrap().notation, "AK,QQ");
+
+    let output = analyze(input.clone()).unwrap();
+    let range = output.meta.range.as_ref().unwrap();
+    assert_eq!(range.strategy.combos.len(), 16 + 6);
+    let frequencies = |actions: &[fpe::models::ActionStrategy]| -> Vec<f64> {
+        actions.iter().map(|a| a.frequency).collect()
+    };
+    assert_eq!(
+        frequencies(&output.strategy.actions),
+        frequencies(&range.strategy.aggregate())
+    );
+    assert_eq!(output.strategy.convergence, range.strategy.convergence());
+    let text = format_analysis(&output, &AnalysisFormat::default(), None);
+    assert!(text.contains("Hero Range: AK,QQ (22 combos)"), "{}", text);
+
+    let uniform = AnalyzeInput {
+        solver: SolverKind::Uniform,
+        ..input
+    };
+    assert!(matches!(analyze(uniform), Err(ModelError::Solver(_))));
+}
+
+#[test]
+fn test_equity_input_validates_the_spot() {
+    let input = EquityInput::parse("AhKd", "Ts9s2h", "QQ+", true).unwrap();
+    let output = equity(&input);
+    assert_eq!(output.runouts.as_ref().unwrap().len(), 52 - 5);
+    let text = format_equity(&output);
+    assert!(text.starts_with("Hero: AKo  Board: "), "{}", text);
+    assert!(text.contains("Best: "), "{}", text);
+
+    assert!(EquityInput::parse("AhKd", "", "QQ+", false).is_err());
+    assert!(EquityInput::parse("AhKd", "AhKs2c", "QQ+", false).is_err());
+    assert!(EquityInput::parse("AhKd", "Ts9s2h4c3d", "QQ+", true).is_err());
+    assert!(EquityInput::parse("AhAd", "AsAc2c", "AA", false).is_err());
+}
@@ -29,3 +29,236 @@ fn test_game_state_invalid_pot() {
     let result = GameState::new(hand, vec![], 0.0, 100.0, 0.0, Position::IP, Range::new());
     assert!(result.is_err());
 }
+
+#[test]
+fn test_validate_against_deck() {
+    use fpe::models::card::Card;
+    use fpe::ModelError;
+
+    let board = vec![
+        Card::from_str("Ks").unwrap(),
+        Card::from_str("8d").unwrap(),
+        Card::from_str("4c").unwrap(),
+    ];
+    let mut range = Range::from_notation("KK,QQ").unwrap();
+    let state = GameState::new(
+        Hand::from_str("AhAd").unwrap(),
+        board.clone(),
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        range.clone(),
+    )
+    .unwrap();
+
+    // KK combos holding the Ks are still in the range
+    assert!(matches!(
+        state.validate_against_deck(),
+        Err(ModelError::DuplicateCard(card)) if card == "K♠"
+    ));
+
+    range.remove_blockers(&board);
+    let state = GameState::new(
+        Hand::from_str("AhAd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        range,
+    )
+    .unwrap();
+    assert!(state.validate_against_deck().is_ok());
+}
+
+#[test]
+fn test_dead_cards_change_runout_equity() {
+    use fpe::models::card::Card;
+    use fpe::solver::equity::{calculate_equity_runout, calculate_equity_runout_with_dead};
+
+    let cards = |s: &str| -> Vec<Card> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| Card::from_str(&s[i..i + 2]).unwrap())
+            .collect()
+    };
+    // Flush draw against an overpair on the turn
+    let hero = Hand::from_str("AhKh").unwrap();
+    let board = cards("Qh7h2c3s");
+    let villain = Range::from_notation("JsJd").unwrap();
+
+    let live = calculate_equity_runout(&hero, &villain, &board);
+    // Two of hero's hearts are known to be gone
+    let dead = cards("5h9h");
+    let with_dead = calculate_equity_runout_with_dead(&hero, &villain, &board, &dead);
+    assert!(with_dead.win < live.win);
+
+    let mut state = GameState::new(
+        hero,
+        board.clone(),
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("JJ").unwrap(),
+    )
+    .unwrap();
+    state.set_dead_cards(cards("Jc")).unwrap();
+    assert_eq!(state.villain_range.num_combos(), 3);
+    assert!(state.set_dead_cards(cards("Qh")).is_err());
+    assert!(state.set_dead_cards(cards("5h5h")).is_err());
+}
+
+#[test]
+fn test_pot_odds_and_mdf_half_pot_bet() {
+    // Villain bet 5 into 10: the pot now holds 15 and hero must call 5
+    let facing = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![],
+        15.0,
+        100.0,
+        5.0,
+        Position::IP,
+        Range::new(),
+    )
+    .unwrap();
+    assert!((facing.pot_odds() - 0.25).abs() < 1e-9);
+    assert!((facing.mdf() - 2.0 / 3.0).abs() < 1e-9);
+
+    // Hero betting 5 into 10 leaves villain the same defense requirement
+    let unopened = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![],
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::new(),
+    )
+    .unwrap();
+    assert_eq!(unopened.pot_odds(), 0.0);
+    assert_eq!(unopened.mdf(), 1.0);
+    assert!((unopened.bet_mdf(5.0) - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_validate_actions() {
+    use fpe::models::action::{Action, BetSize};
+
+    let mut state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![],
+        10.0,
+        100.0,
+        5.0,
+        Position::IP,
+        Range::new(),
+    )
+    .unwrap();
+
+    // Unset means "use the defaults"
+    assert!(state.validate_actions().is_ok());
+
+    // No actions at all before the river leaves the hand stuck
+    state.available_actions = Some(vec![]);
+    let err = state.validate_actions().unwrap_err();
+    assert!(
+        err.to_string().contains("No actions on the Preflop"),
+        "{}",
+        err
+    );
+
+    // One legal action is enough
+    state.available_actions = Some(vec![Action::Check, Action::Call]);
+    assert!(state.validate_actions().is_ok());
+
+    // Nothing to check or bet into while facing a bet
+    state.available_actions = Some(vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]);
+    let err = state.validate_actions().unwrap_err();
+    assert!(err.to_string().contains("No legal action"), "{}", err);
+
+    // A multiplier has no bet to multiply
+    state.to_call = 0.0;
+    state.available_actions = Some(vec![Action::Check, Action::Bet(BetSize::Multiplier(3.0))]);
+    let err = state.validate_actions().unwrap_err();
+    assert!(err.to_string().contains("multiplier"), "{}", err);
+}
+
+#[test]
+fn test_with_builders_revalidate() {
+    use fpe::models::card::Card;
+
+    let state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![],
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::new(),
+    )
+    .unwrap();
+
+    let bigger = state.clone().with_pot(25.0).unwrap();
+    assert_eq!(bigger.pot_size, 25.0);
+    assert_eq!(bigger.effective_stack, 100.0);
+    assert!(state.clone().with_pot(0.0).is_err());
+    assert!(state.clone().with_pot(-5.0).is_err());
+
+    assert!(state.clone().with_to_call(150.0).is_err());
+    assert_eq!(state.clone().with_to_call(5.0).unwrap().to_call, 5.0);
+
+    let flop: Vec<Card> = ["Ks", "8d", "2c"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let on_flop = state.clone().with_board(flop).unwrap();
+    assert_eq!(on_flop.street, Street::Flop);
+    assert_eq!(
+        on_flop.with_position(Position::OOP).unwrap().position,
+        Position::OOP
+    );
+
+    // Hero's own card can't be on the board
+    let clash = vec![
+        Card::from_str("Ah").unwrap(),
+        Card::from_str("2c").unwrap(),
+        Card::from_str("3c").unwrap(),
+    ];
+    assert!(state.with_board(clash).is_err());
+}
+
+#[test]
+fn test_non_finite_and_huge_amounts_rejected() {
+    use fpe::ModelError;
+
+    let new = |pot: f64, stack: f64, to_call: f64| {
+        let hand = Hand::from_str("AhKd").unwrap();
+        GameState::new(
+            hand,
+            vec![],
+            pot,
+            stack,
+            to_call,
+            Position::IP,
+            Range::new(),
+        )
+    };
+    let rejected =
+        |result: fpe::Result<GameState>| matches!(result, Err(ModelError::InvalidGameState(_)));
+
+    for bad in [f64::NAN, f64::INFINITY, 1e308, GameState::MAX_CHIPS + 1.0] {
+        assert!(rejected(new(bad, 100.0, 0.0)), "pot {}", bad);
+        assert!(rejected(new(10.0, bad, 0.0)), "stack {}", bad);
+    }
+    assert!(rejected(new(10.0, 100.0, f64::NAN)));
+    assert!(rejected(new(f64::NEG_INFINITY, 100.0, 0.0)));
+
+    let max = GameState::MAX_CHIPS;
+    assert!(new(max, max, max).is_ok());
+    assert!(new(10.0, 100.0, 0.0)
+        .unwrap()
+        .with_pot(f64::INFINITY)
+        .is_err());
+}
@@ -4,6 +4,7 @@ use fpe::models::{
     range::Range,
 };
 use fpe::solver::info_set::{InfoSetKey, SprBucket};
+use fpe::test_util::{cards, DRY_RIVER};
 use std::str::FromStr;
 
 #[test]
@@ -35,3 +36,59 @@ fn test_info_set_key_from_game_state() {
     // SPR = 100/10 = 10 -> VeryDeep
     assert_eq!(key.spr_bucket, SprBucket::VeryDeep);
 }
+
+#[test]
+fn test_info_set_key_shared_within_spr_bucket() {
+    let spot = |stack: f64| {
+        GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            vec![],
+            10.0,
+            stack,
+            0.0,
+            Position::OOP,
+            Range::new(),
+        )
+        .unwrap()
+    };
+    let spr_3 = spot(30.0).info_set_key();
+    let spr_4 = spot(40.0).info_set_key();
+
+    assert_eq!(spr_3.spr_bucket, SprBucket::Medium);
+    assert_eq!(spr_3, spr_4);
+    assert_eq!(spr_3.to_string(), spr_4.to_string());
+    assert!(spr_3.to_string().contains("spr=Medium"), "{}", spr_3);
+
+    assert_ne!(spot(60.0).info_set_key(), spr_3);
+}
+
+#[test]
+fn test_strength_buckets_order_hands() {
+    use fpe::solver::info_set::StrengthBuckets;
+
+    let board = cards(DRY_RIVER);
+    let hero_range = Range::from_notation("KQ,88,54").unwrap();
+    let buckets = StrengthBuckets::new(&board, Some(&hero_range), 4);
+    let bucket = |h: &str| buckets.bucket(&Hand::from_str(h).unwrap());
+
+    assert_eq!(buckets.count(), 4);
+    // A set of kings beats every hero combo, seven high beats none
+    assert_eq!(bucket("KhKd"), 3);
+    assert_eq!(bucket("3d2c"), 0);
+    assert!(bucket("KhJd") > bucket("9h9d"));
+
+    let key = InfoSetKey::from_bucket(
+        &GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            vec![],
+            10.0,
+            100.0,
+            0.0,
+            Position::IP,
+            Range::new(),
+        )
+        .unwrap(),
+        2,
+    );
+    assert_eq!(key.strength_bucket, Some(2));
+}
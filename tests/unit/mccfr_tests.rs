@@ -1,4 +1,5 @@
 use fpe::solver::regret::regret_to_strategy;
+use fpe::test_util::{cards, DRY_RIVER};
 
 #[test]
 fn test_regret_matching_uniform() {
@@ -29,7 +30,10 @@ fn test_regret_matching_negative() {
 
 #[test]
 fn test_convergence_tracker_check() {
-    use fpe::models::{game_state::Position, hand::Hand};
+    use fpe::models::{
+        game_state::{Position, Street},
+        hand::Hand,
+    };
     use fpe::solver::info_set::{InfoSetKey, SprBucket};
     use fpe::solver::mccfr::ConvergenceTracker;
     use fpe::solver::regret::RegretTable;
@@ -43,6 +47,11 @@ fn test_convergence_tracker_check() {
         hero_hand: Hand::from_str("AhAs").unwrap(),
         spr_bucket: SprBucket::Medium,
         position: Position::IP,
+        strength_bucket: None,
+        street: Street::River,
+        pot_cents: 1000,
+        to_call_cents: 0,
+        board_mask: 0,
     };
 
     // Update table with some regrets
@@ -77,6 +86,57 @@ fn test_convergence_tracker_is_converged() {
     assert!(tracker.is_converged(0.001));
 }
 
+#[test]
+fn test_reach_weighted_convergence_discounts_rare_nodes() {
+    use fpe::models::{
+        game_state::{Position, Street},
+        hand::Hand,
+    };
+    use fpe::solver::info_set::{InfoSetKey, SprBucket};
+    use fpe::solver::mccfr::{ConvergenceMetric, ConvergenceTracker};
+    use fpe::solver::regret::RegretTable;
+    use std::str::FromStr;
+
+    let key = |hand: &str| InfoSetKey {
+        hero_hand: Hand::from_str(hand).unwrap(),
+        spr_bucket: SprBucket::Medium,
+        position: Position::IP,
+        strength_bucket: None,
+        street: Street::River,
+        pot_cents: 1000,
+        to_call_cents: 0,
+        board_mask: 0,
+    };
+    let (busy, rare) = (key("AhAs"), key("7c2d"));
+
+    let mut table = RegretTable::new();
+    for _ in 0..99 {
+        table.update_regrets(busy.clone(), &[1.0, 0.0], 1.0);
+    }
+    table.update_regrets(rare.clone(), &[1.0, 0.0], 1.0);
+    assert_eq!(table.visits(&busy), 99);
+    assert_eq!(table.visits(&rare), 1);
+
+    let mut plain = ConvergenceTracker::new();
+    let mut weighted = ConvergenceTracker::with_metric(ConvergenceMetric::ReachWeighted);
+    plain.check_convergence(&table);
+    weighted.check_convergence(&table);
+
+    // The busy node holds steady while the rare one swings to a 50/50 mix
+    table.update_regrets(busy.clone(), &[1.0, 0.0], 1.0);
+    table.update_regrets(rare.clone(), &[-1.0, 1.0], 1.0);
+    let plain_change = plain.check_convergence(&table);
+    let weighted_change = weighted.check_convergence(&table);
+
+    assert!((plain_change - 0.5).abs() < 1e-12, "{}", plain_change);
+    // Two visits against the busy node's hundred
+    assert!(
+        (weighted_change - 0.5 * 2.0 / 100.0).abs() < 1e-12,
+        "{}",
+        weighted_change
+    );
+}
+
 #[test]
 fn test_mccfr_config_default() {
     use fpe::solver::mccfr::MccfrConfig;
@@ -92,17 +152,139 @@ fn test_mccfr_config_default() {
 fn test_mccfr_config_custom() {
     use fpe::solver::mccfr::MccfrConfig;
 
-    let config = MccfrConfig {
-        iterations: 500,
-        samples_per_iteration: 50,
-        convergence_threshold: 0.01,
-        seed: Some(12345),
-    };
+    let config = MccfrConfig::builder()
+        .iterations(500)
+        .samples_per_iteration(50)
+        .convergence_threshold(0.01)
+        .seed(12345)
+        .build();
 
     assert_eq!(config.iterations, 500);
     assert_eq!(config.seed, Some(12345));
 }
 
+#[test]
+fn test_mccfr_config_builder_keeps_other_defaults() {
+    use fpe::solver::mccfr::MccfrConfig;
+
+    let config = MccfrConfig::builder()
+        .max_depth(4)
+        .record_convergence(true)
+        .build();
+    let default = MccfrConfig::default();
+
+    assert_eq!(config.max_depth, Some(4));
+    assert!(config.record_convergence);
+    assert_eq!(config.iterations, default.iterations);
+    assert_eq!(config.samples_per_iteration, default.samples_per_iteration);
+    assert!(config.seed.is_none());
+
+    // Option setters also take an Option, so a missing value clears them
+    let cleared = MccfrConfig::builder().seed(7).seed(None).build();
+    assert!(cleared.seed.is_none());
+}
+
+#[test]
+fn test_linear_sample_schedule_grows() {
+    use fpe::models::range::Range;
+    use fpe::models::{game_state::GameState, game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{MccfrConfig, SampleSchedule};
+    use fpe::solver::solve_with_progress_channel;
+    use std::str::FromStr;
+
+    let board = cards(DRY_RIVER);
+    let state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("QQ,K9s,A8s").unwrap(),
+    )
+    .unwrap();
+    let config = MccfrConfig::builder()
+        .iterations(100)
+        .sample_schedule(SampleSchedule::Linear {
+            start: 10,
+            end: 200,
+        })
+        .seed(42)
+        .build();
+    let expected_early: u64 = (0..50).map(|i| config.samples_at(i) as u64).sum();
+    let expected_total: u64 = (0..100).map(|i| config.samples_at(i) as u64).sum();
+
+    // 100 iterations check at 50 and report once more at the end, so the
+    // two snapshots split the solve into halves
+    let (snapshots, handle) = solve_with_progress_channel(state, config);
+    handle.join().unwrap();
+    let counts: Vec<u64> = snapshots.iter().map(|s| s.samples).collect();
+    assert_eq!(counts, vec![expected_early, expected_total]);
+
+    let early = counts[0];
+    let late = counts[1] - counts[0];
+    assert!(late > early, "late {} should exceed early {}", late, early);
+}
+
+#[test]
+fn test_multiplier_sizes_only_raises() {
+    use fpe::models::action::{Action, BetSize};
+    use fpe::models::range::Range;
+    use fpe::models::{game_state::GameState, game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+    use std::str::FromStr;
+
+    let board = cards(DRY_RIVER);
+    let spot = |to_call: f64| {
+        GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            board.clone(),
+            10.0,
+            100.0,
+            to_call,
+            Position::IP,
+            Range::from_notation("QQ,K9s,A8s").unwrap(),
+        )
+        .unwrap()
+    };
+    let config = MccfrConfig::builder()
+        .iterations(20)
+        .seed(42)
+        .hero_bet_sizes(vec![BetSize::PotFraction(0.5), BetSize::Multiplier(3.0)])
+        .build();
+    let actions = |to_call: f64| -> Vec<Action> {
+        solve_with_config(spot(to_call), config.clone())
+            .unwrap()
+            .actions
+            .into_iter()
+            .map(|a| a.action)
+            .collect()
+    };
+
+    // Unopened, the multiplier has no bet to multiply and offers nothing
+    assert_eq!(
+        actions(0.0),
+        vec![Action::Check, Action::Bet(BetSize::PotFraction(0.5))]
+    );
+    // Facing a bet, it sizes a raise
+    assert!(actions(5.0).contains(&Action::Raise(BetSize::Multiplier(3.0))));
+}
+
+#[test]
+fn test_sample_schedule_defaults_to_constant() {
+    use fpe::solver::mccfr::{MccfrConfig, SampleSchedule};
+
+    let config = MccfrConfig::default();
+    assert_eq!(config.samples_at(0), config.samples_per_iteration);
+    assert_eq!(config.samples_at(9_999), config.samples_per_iteration);
+
+    assert_eq!(SampleSchedule::Constant(7).samples_at(3, 10), 7);
+    assert_eq!(
+        SampleSchedule::Linear { start: 0, end: 0 }.samples_at(3, 10),
+        1
+    );
+}
+
 // US2 Tests
 // Note: ConvergenceTracker is not yet implemented, so we can't import it yet.
 // But we can write the test structure and comment it out or expect failure if we could import it.
@@ -138,3 +320,529 @@ fn test_convergence_tracker_check() {
 // I'll add these tests after I create the struct skeleton in T038.
 // But the plan says "Tests FIRST".
 // I will add them now, and accept that `cargo test` will fail to compile.
+
+#[test]
+fn test_action_values_are_averaged() {
+    use fpe::models::{
+        game_state::{Position, Street},
+        hand::Hand,
+    };
+    use fpe::solver::info_set::{InfoSetKey, SprBucket};
+    use fpe::solver::regret::RegretTable;
+    use std::str::FromStr;
+
+    let mut table = RegretTable::new();
+    let key = InfoSetKey {
+        hero_hand: Hand::from_str("AhAs").unwrap(),
+        spr_bucket: SprBucket::Medium,
+        position: Position::IP,
+        strength_bucket: None,
+        street: Street::River,
+        pot_cents: 1000,
+        to_call_cents: 0,
+        board_mask: 0,
+    };
+
+    assert!(table.get_action_values(&key).is_none());
+
+    table.record_action_values(&key, &[2.0, -1.0]);
+    table.record_action_values(&key, &[4.0, 1.0]);
+
+    assert_eq!(table.get_action_values(&key), Some(vec![3.0, 0.0]));
+}
+
+#[test]
+fn test_profiling_does_not_change_strategy() {
+    use fpe::models::range::Range;
+    use fpe::models::{game_state::GameState, game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{solve_profiled, MccfrConfig};
+    use fpe::solver::SharedEvalCache;
+    use std::str::FromStr;
+
+    let board = cards(DRY_RIVER);
+    let state = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("QQ,K9s,A8s").unwrap(),
+    )
+    .unwrap();
+
+    let solve = |profile: bool| {
+        let config = MccfrConfig::builder()
+            .iterations(200)
+            .samples_per_iteration(10)
+            .seed(42)
+            .profile(profile)
+            .build();
+        solve_profiled(state.clone(), config, &SharedEvalCache::new()).unwrap()
+    };
+
+    let (plain, no_profile) = solve(false);
+    let (profiled, profile) = solve(true);
+
+    assert!(no_profile.is_none());
+    let profile = profile.unwrap();
+    assert!(profile.total >= profile.sampling);
+
+    let freqs =
+        |s: &fpe::models::Strategy| -> Vec<f64> { s.actions.iter().map(|a| a.frequency).collect() };
+    assert_eq!(freqs(&plain), freqs(&profiled));
+}
+
+#[test]
+fn test_current_strategy_differs_from_average() {
+    use fpe::models::range::Range;
+    use fpe::models::{game_state::GameState, game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+    use std::str::FromStr;
+
+    // A bluff catcher's bet is good against air and bad against sets, so
+    // regret matching keeps mixing while the average lags behind
+    let board = cards(DRY_RIVER);
+    let state = GameState::new(
+        Hand::from_str("Kh5d").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::OOP,
+        Range::from_notation("8h8c,Qh3c").unwrap(),
+    )
+    .unwrap();
+
+    let solve = |return_current_strategy: bool| {
+        let config = MccfrConfig::builder()
+            .iterations(50)
+            .samples_per_iteration(2)
+            .seed(1)
+            .return_current_strategy(return_current_strategy)
+            .build();
+        let strategy = solve_with_config(state.clone(), config).unwrap();
+        strategy
+            .actions
+            .iter()
+            .map(|a| a.frequency)
+            .collect::<Vec<f64>>()
+    };
+
+    let average = solve(false);
+    let current = solve(true);
+    assert!((current.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert_ne!(average, current);
+}
+
+/// Hero holds the royal flush on a Broadway river, against an empty range
+fn river_state(position: fpe::models::game_state::Position) -> fpe::models::GameState {
+    use fpe::models::{card::Card, hand::Hand, range::Range};
+    use std::str::FromStr;
+
+    let board = ["Qh", "Jh", "Th", "2s", "3d"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    fpe::models::GameState::new(
+        Hand::from_str("AhKh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        position,
+        Range::new(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_indifference_spread_shrinks_with_convergence() {
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+
+    // The nuts against air and chopping hands: betting and checking both
+    // win the pot, so the equilibrium mixes between them
+    let mut state = river_state(Position::OOP);
+    state.villain_range = Range::from_notation("74o,84o,AK").unwrap();
+    state.villain_range.remove_blockers(&state.board);
+    state.villain_range.remove_blockers(&state.hero_hand.cards);
+    let solve = |iterations| {
+        let config = MccfrConfig::builder()
+            .iterations(iterations)
+            .seed(7)
+            .build();
+        solve_with_config(state.clone(), config).unwrap()
+    };
+
+    let converged = solve(3000);
+    assert!(converged.is_mixed(), "{}", converged);
+    assert!(converged.indifference_spread() < 0.05, "{}", converged);
+
+    let early = solve(10);
+    assert!(early.indifference_spread() > 0.1, "{}", early);
+}
+
+#[test]
+fn test_enumerating_small_range_lowers_variance() {
+    use fpe::models::{game_state::Position, hand::Hand, range::Range};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+    use std::str::FromStr;
+
+    // A bluff catcher against one better hand and two bluffs
+    let mut state = river_state(Position::OOP);
+    state.hero_hand = Hand::from_str("9s9c").unwrap();
+    state.villain_range = Range::from_notation("AsKs,7c4d,8c4d").unwrap();
+    assert_eq!(state.villain_range.len(), 3);
+
+    // Variance across seeds of the first action's EV
+    let ev_variance = |enumerate_below| {
+        let evs: Vec<f64> = (0..10)
+            .map(|seed| {
+                let config = MccfrConfig::builder()
+                    .iterations(300)
+                    .samples_per_iteration(1)
+                    .enumerate_villain_below(enumerate_below)
+                    .seed(seed)
+                    .build();
+                let strategy = solve_with_config(state.clone(), config).unwrap();
+                assert!(strategy.is_valid());
+                strategy.actions[0].ev
+            })
+            .collect();
+        let mean = evs.iter().sum::<f64>() / evs.len() as f64;
+        evs.iter().map(|ev| (ev - mean).powi(2)).sum::<f64>() / evs.len() as f64
+    };
+
+    let sampled = ev_variance(0);
+    let enumerated = ev_variance(4);
+    assert!(enumerated * 4.0 < sampled, "{} vs {}", enumerated, sampled);
+}
+
+#[test]
+fn test_stop_flag_returns_partial_strategy() {
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    let mut state = river_state(Position::OOP);
+    state.villain_range = Range::from_notation("74o,84o,AK").unwrap();
+    state.villain_range.remove_blockers(&state.board);
+    let stop = Arc::new(AtomicBool::new(false));
+    let config = MccfrConfig::builder()
+        .iterations(u32::MAX)
+        .seed(5)
+        .stop(Arc::clone(&stop))
+        .build();
+
+    let flag = Arc::clone(&stop);
+    let stopper = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        flag.store(true, Ordering::Relaxed);
+    });
+    let started = Instant::now();
+    let strategy = solve_with_config(state, config).unwrap();
+    stopper.join().unwrap();
+
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert!(strategy.iterations > 0 && strategy.iterations < u32::MAX);
+    assert!(strategy.is_valid());
+}
+
+#[test]
+fn test_street_bet_sizes_size_the_root() {
+    use fpe::models::action::StreetBetSizes;
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+
+    // Check, or the river's two sizes
+    let config = MccfrConfig::builder()
+        .iterations(10)
+        .seed(1)
+        .street_bet_sizes(StreetBetSizes::default())
+        .build();
+    let mut state = river_state(Position::OOP);
+    state.villain_range = Range::from_notation("22").unwrap();
+    let strategy = solve_with_config(state, config).unwrap();
+    assert_eq!(strategy.actions.len(), 3);
+}
+
+#[test]
+fn test_forced_bet_solves_villain_response() {
+    use fpe::models::action::{Action, BetSize};
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_with_forced_action, MccfrConfig};
+    use fpe::ModelError;
+
+    let mut state = river_state(Position::OOP);
+    state.villain_range = Range::from_notation("QQ,JJ,22").unwrap();
+    state.villain_range.remove_blockers(&state.board);
+    let bet = Action::Bet(BetSize::PotFraction(0.5));
+    let config = MccfrConfig::builder().iterations(200).seed(3).build();
+
+    let strategy = solve_with_forced_action(state.clone(), bet, config.clone()).unwrap();
+    assert_eq!(strategy.game_state.as_ref().unwrap().position, Position::IP);
+    let actions: Vec<&Action> = strategy.actions.iter().map(|a| &a.action).collect();
+    assert!(actions.contains(&&Action::Fold));
+    assert!(actions.contains(&&Action::Call));
+    assert!(strategy.is_valid());
+    // Every villain combo loses to the royal flush, so calling burns chips
+    let fold = &strategy.actions[0];
+    assert_eq!(fold.action, Action::Fold);
+    assert!(fold.frequency > 0.5, "{}", strategy);
+
+    // Checking to villain leaves a decision; folding doesn't
+    assert!(solve_with_forced_action(state.clone(), Action::Check, config.clone()).is_ok());
+    assert!(matches!(
+        solve_with_forced_action(state, Action::Fold, config),
+        Err(ModelError::Solver(_))
+    ));
+}
+
+#[test]
+fn test_solve_mccfr_reports_invalid_state() {
+    use fpe::models::action::Action;
+    use fpe::models::game_state::Position;
+    use fpe::solver::mccfr::solve_mccfr;
+    use fpe::ModelError;
+
+    let mut state = river_state(Position::IP).with_to_call(5.0).unwrap();
+    // Checking isn't possible facing a bet
+    state.available_actions = Some(vec![Action::Check]);
+    let result = solve_mccfr(&state, 10);
+    assert!(matches!(result, Err(ModelError::InvalidGameState(_))));
+
+    state.available_actions = None;
+    assert!(solve_mccfr(&state, 10).unwrap().is_valid());
+}
+
+#[test]
+fn test_solve_both_returns_villain_response() {
+    use fpe::models::action::{Action, BetSize};
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_both, MccfrConfig};
+    use fpe::ModelError;
+
+    // Checking back ends the hand, so villain acts only after a bet
+    let mut state = river_state(Position::IP);
+    state.villain_range = Range::from_notation("QQ,JJ,22").unwrap();
+    state.villain_range.remove_blockers(&state.board);
+    let config = MccfrConfig::builder()
+        .iterations(300)
+        .seed(3)
+        .hero_bet_sizes(vec![BetSize::PotFraction(1.0)])
+        .build();
+
+    let (hero, villain) = solve_both(state, config).unwrap();
+    assert!(hero.is_valid());
+    assert!(villain.is_valid());
+    assert_eq!(villain.game_state.as_ref().unwrap().position, Position::OOP);
+    let actions: Vec<&Action> = villain.actions.iter().map(|a| &a.action).collect();
+    assert!(actions.contains(&&Action::Fold));
+    assert!(actions.contains(&&Action::Call));
+    assert_eq!(villain.iterations, hero.iterations);
+
+    // Facing a river all-in, folding and calling both end the hand
+    let facing_all_in = river_state(Position::IP).with_to_call(100.0).unwrap();
+    let config = MccfrConfig::builder().iterations(10).build();
+    assert!(matches!(
+        solve_both(facing_all_in, config),
+        Err(ModelError::Solver(_))
+    ));
+}
+
+#[test]
+fn test_villain_info_sets_bounded_by_buckets() {
+    use fpe::models::{game_state::Position, range::Range};
+    use fpe::solver::mccfr::{solve_with_regrets, MccfrConfig};
+
+    let mut state = river_state(Position::OOP);
+    state.villain_range = Range::from_notation("22+,A2s+,K2s+,Q2s+,A2o+,K2o+").unwrap();
+    state.villain_range.remove_blockers(&state.board);
+    state.villain_range.remove_blockers(&state.hero_hand.cards);
+
+    let villain_keys = |buckets: Option<u8>| {
+        let config = MccfrConfig::builder()
+            .iterations(200)
+            .samples_per_iteration(20)
+            .seed(7)
+            .villain_buckets(buckets)
+            .build();
+        let (_, table) = solve_with_regrets(state.clone(), config).unwrap();
+        table
+            .keys()
+            .filter(|key| key.position == Position::IP)
+            .count()
+    };
+
+    // Villain acts after a check and facing a bet
+    assert!(villain_keys(Some(3)) <= 3 * 2);
+    assert!(villain_keys(None) > 3 * 2);
+}
+
+#[test]
+fn test_wide_villain_range_reports_subsample() {
+    use fpe::models::strategy::Subsample;
+    use fpe::models::{card::Card, game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{solve_with_config, MccfrConfig};
+
+    let mut state = river_state(Position::OOP);
+    let cards: Vec<Card> = (0..52)
+        .map(|i| Card::from_pokers_index(i).unwrap())
+        .collect();
+    for (i, &c1) in cards.iter().enumerate() {
+        for &c2 in &cards[i + 1..] {
+            state.villain_range.add(Hand::new(c1, c2), 0.5);
+        }
+    }
+
+    let config = MccfrConfig::builder()
+        .iterations(20)
+        .samples_per_iteration(50)
+        .seed(11)
+        .max_villain_combos(100)
+        .build();
+    let strategy = solve_with_config(state, config).unwrap();
+    assert!(strategy.is_valid());
+    assert_eq!(
+        strategy.villain_subsample,
+        Some(Subsample {
+            kept: 100,
+            total: 1326
+        })
+    );
+}
+
+#[test]
+fn test_max_depth_bounds_tree() {
+    use fpe::models::action::{Action, BetSize};
+    use fpe::models::{game_state::Position, hand::Hand};
+    use fpe::solver::mccfr::{solve_with_config, solve_with_regrets, MccfrConfig};
+    use std::str::FromStr;
+
+    let mut state = river_state(Position::OOP);
+    state.effective_stack = 1000.0;
+    state.to_call = 5.0;
+    state.available_actions = Some(vec![
+        Action::Fold,
+        Action::Call,
+        Action::Raise(BetSize::PotFraction(1.0)),
+        Action::Raise(BetSize::PotFraction(3.0)),
+        Action::AllIn,
+    ]);
+    for hand in ["QdQc", "9c8c"] {
+        state.villain_range.add(Hand::from_str(hand).unwrap(), 1.0);
+    }
+
+    let config = MccfrConfig::builder()
+        .iterations(200)
+        .samples_per_iteration(4)
+        .seed(5)
+        .max_depth(1)
+        .build();
+    // Villain never gets to decide facing a raise
+    let (_, table) = solve_with_regrets(state.clone(), config.clone()).unwrap();
+    assert!(table.keys().all(|key| key.position == state.position));
+
+    let strategy = solve_with_config(state, config).unwrap();
+    assert_eq!(strategy.actions.len(), 5);
+    assert!(strategy.is_valid());
+}
+
+#[test]
+fn test_preflop_continues_to_flop() {
+    use fpe::models::action::{Action, BetSize};
+    use fpe::models::game_state::{GameState, Position, Street};
+    use fpe::models::{hand::Hand, range::Range};
+    use fpe::solver::info_set::InfoSetKey;
+    use fpe::solver::mccfr::{solve_with_config, solve_with_regrets, MccfrConfig};
+    use std::str::FromStr;
+
+    // Hero opens or limps on the button; a call or check deals a flop
+    let mut root = GameState::new(
+        Hand::from_str("AhKd").unwrap(),
+        vec![],
+        1.5,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("QQ,JJ,T9s").unwrap(),
+    )
+    .unwrap();
+    root.available_actions = Some(vec![Action::Check, Action::Bet(BetSize::Amount(2.5))]);
+
+    let config = MccfrConfig::builder()
+        .iterations(10)
+        .samples_per_iteration(2)
+        .seed(1)
+        .deal_flop(true)
+        .build();
+    let (_, table) = solve_with_regrets(root.clone(), config.clone()).unwrap();
+
+    assert!(table.keys().any(|key| key.street == Street::Preflop));
+    let flop_keys: Vec<&InfoSetKey> = table
+        .keys()
+        .filter(|key| key.street == Street::Flop)
+        .collect();
+    assert!(!flop_keys.is_empty());
+    assert!(flop_keys.iter().all(|key| key.board_mask.count_ones() == 3));
+
+    let strategy = solve_with_config(root, config).unwrap();
+    assert!(strategy.is_valid());
+}
+
+#[test]
+fn test_warm_start_converges_sooner() {
+    use fpe::models::{card::Card, game_state::GameState, game_state::Position};
+    use fpe::models::{hand::Hand, range::Range};
+    use fpe::solver::mccfr::{solve_with_config, solve_with_regrets, MccfrConfig};
+    use fpe::solver::solve_with_progress_channel;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    let spot = |stack: f64| {
+        let board = ["Ts", "9s", "2h"]
+            .iter()
+            .map(|c| Card::from_str(c).unwrap())
+            .collect();
+        GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            board,
+            10.0,
+            stack,
+            0.0,
+            Position::IP,
+            Range::from_notation("QQ,JJ,AsQs,8s7s").unwrap(),
+        )
+        .unwrap()
+    };
+    let config = MccfrConfig::builder()
+        .iterations(2000)
+        .samples_per_iteration(10)
+        .convergence_threshold(0.002)
+        .seed(3)
+        .build();
+    // First check below the threshold; the first check has nothing to
+    // compare against and always reports 0
+    let converged_at = |config: &MccfrConfig, state: GameState| {
+        let (snapshots, handle) = solve_with_progress_channel(state, config.clone());
+        let first = snapshots
+            .iter()
+            .find(|s| s.iteration > 200 && s.convergence < config.convergence_threshold)
+            .map_or(u32::MAX, |s| s.iteration);
+        handle.join().unwrap();
+        first
+    };
+
+    let (_, prior) = solve_with_regrets(spot(100.0), config.clone()).unwrap();
+    let cold = converged_at(&config, spot(105.0));
+    let mut warm_config = config.clone();
+    warm_config.warm_start = Some(Arc::new(prior));
+    let warm = converged_at(&warm_config, spot(105.0));
+    assert!(warm < cold, "warm {} vs cold {}", warm, cold);
+
+    let strategy = solve_with_config(spot(105.0), warm_config).unwrap();
+    assert!(strategy.is_valid());
+}
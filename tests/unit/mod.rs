@@ -1,9 +1,15 @@
 pub mod action_tests;
+pub mod analyze_tests;
 pub mod game_state_tests;
 pub mod output_tests;
+pub mod parse_tests;
+pub mod push_fold_tests;
 pub mod validation_tests;
 
 pub mod info_set_tests;
 pub mod mccfr_tests;
 pub mod range_tests;
+pub mod repl_tests;
+pub mod showdown_tests;
+pub mod solve_cache_tests;
 pub mod strategy_tests;
@@ -1,4 +1,5 @@
 use fpe::models::{Action, ActionStrategy, Strategy};
+use fpe::test_util::{cards, DRY_RIVER};
 use serde_json::Value;
 
 #[test]
@@ -41,3 +42,226 @@ fn test_json_structure() {
 
     assert_eq!(first_action["action"], "Fold");
 }
+
+#[test]
+fn test_strategy_summary() {
+    use fpe::cli::output::format_strategy_summary;
+    use fpe::models::BetSize;
+
+    let pure = Strategy::new(
+        vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.02,
+                ev: 0.0,
+            },
+            ActionStrategy {
+                action: Action::Bet(BetSize::PotFraction(0.75)),
+                frequency: 0.98,
+                ev: 1.0,
+            },
+        ],
+        1000,
+        0.001,
+    );
+    assert_eq!(format_strategy_summary(&pure), "Pure strategy: Bet 75% pot");
+
+    let mut mixed = pure.clone();
+    mixed.actions[0].frequency = 0.4;
+    mixed.actions[1].frequency = 0.6;
+    assert_eq!(
+        format_strategy_summary(&mixed),
+        "Mixed strategy (mostly Bet 75% pot at 60.0%)"
+    );
+}
+
+#[test]
+fn test_empty_strategy_table() {
+    use fpe::cli::output::{format_strategy_summary, format_strategy_table};
+
+    let empty = Strategy::new(Vec::new(), 0, 0.0);
+    assert_eq!(format_strategy_table(&empty), "(no actions)");
+    assert_eq!(format_strategy_summary(&empty), "No actions available");
+}
+
+#[test]
+fn test_defense_summary() {
+    use fpe::cli::output::format_defense_summary;
+    use fpe::models::{BetSize, GameState, Hand, Position, Range};
+    use std::str::FromStr;
+
+    let mut strategy = Strategy::new(
+        vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.4,
+                ev: 1.0,
+            },
+            ActionStrategy {
+                action: Action::Bet(BetSize::PotFraction(0.5)),
+                frequency: 0.6,
+                ev: 1.5,
+            },
+        ],
+        1000,
+        0.001,
+    );
+    assert_eq!(format_defense_summary(&strategy), "");
+
+    strategy.game_state = Some(
+        GameState::new(
+            Hand::from_str("AhKd").unwrap(),
+            vec![],
+            10.0,
+            100.0,
+            0.0,
+            Position::IP,
+            Range::new(),
+        )
+        .unwrap(),
+    );
+    assert_eq!(
+        format_defense_summary(&strategy),
+        "Villain MDF vs Bet 50% pot: 66.7%"
+    );
+}
+
+#[test]
+fn test_explanation_mentions_category_and_action() {
+    use fpe::cli::output::format_explanation;
+    use fpe::models::{BetSize, GameState, Hand, Position, Range};
+    use std::str::FromStr;
+
+    let mut strategy = Strategy::new(
+        vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.2,
+                ev: 0.9,
+            },
+            ActionStrategy {
+                action: Action::Bet(BetSize::PotFraction(0.75)),
+                frequency: 0.8,
+                ev: 1.3,
+            },
+        ],
+        1000,
+        0.001,
+    );
+    assert_eq!(format_explanation(&strategy), "");
+
+    let board = cards(DRY_RIVER);
+    strategy.game_state = Some(
+        GameState::new(
+            Hand::from_str("KhQd").unwrap(),
+            board,
+            10.0,
+            100.0,
+            0.0,
+            Position::IP,
+            Range::from_notation("QQ,JJ").unwrap(),
+        )
+        .unwrap(),
+    );
+
+    let text = format_explanation(&strategy);
+    assert!(text.contains("top pair"), "{}", text);
+    assert!(text.contains("100% equity"), "{}", text);
+    assert!(
+        text.contains("Bet 75% pot is +1.3 BB and chosen 80% of the time"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("Playing one action only, Bet 75% pot is recommended."),
+        "{}",
+        text
+    );
+}
+
+#[test]
+fn test_number_formatting_precision() {
+    use fpe::cli::output::{format_ev, format_percent, format_strategy_table_with_precision};
+
+    assert_eq!(format_percent(0.625, 1), "62.5%");
+    assert_eq!(format_percent(0.625, 3), "62.500%");
+    assert_eq!(format_percent(1.0, 0), "100%");
+    assert_eq!(format_ev(1.25, 2), " +1.25");
+    assert_eq!(format_ev(-0.5, 3), "-0.500");
+
+    let strategy = Strategy::new(
+        vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.6,
+                ev: 1.5,
+            },
+            ActionStrategy {
+                action: Action::Fold,
+                frequency: 0.4,
+                ev: 0.0,
+            },
+        ],
+        100,
+        0.0,
+    );
+    let table = format_strategy_table_with_precision(&strategy, 3);
+    assert!(table.contains("60.000%"));
+    assert!(table.contains("+1.500"));
+    assert!(!table.contains(','));
+}
+
+#[test]
+fn test_blocker_report_format() {
+    use fpe::cli::output::format_blocker_report;
+    use fpe::models::{Card, Hand, Range};
+    use std::str::FromStr;
+
+    let board: Vec<Card> = ["Kh", "8h", "3h"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let villain = Range::from_notation("AhQh,7h6h,QsJs").unwrap();
+    let report = villain.blocker_report(&Hand::from_str("AhQd").unwrap(), &board);
+
+    let text = format_blocker_report(&report);
+    assert!(
+        text.contains("Value combos: 2.0 -> 1.0 (blocked AhQh flush)"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Bluff combos: 1.0 -> 1.0"), "{}", text);
+}
+
+#[test]
+fn test_table_shows_bet_amounts_for_the_spot() {
+    use fpe::cli::output::format_strategy_table;
+    use fpe::models::action::BetSize;
+    use fpe::models::{GameState, Hand, Position, Range};
+    use std::str::FromStr;
+
+    let mut strategy = Strategy::new(
+        vec![
+            ActionStrategy {
+                action: Action::Check,
+                frequency: 0.4,
+                ev: 0.0,
+            },
+            ActionStrategy {
+                action: Action::Bet(BetSize::PotFraction(0.5)),
+                frequency: 0.6,
+                ev: 1.0,
+            },
+        ],
+        100,
+        0.0,
+    );
+    assert!(!format_strategy_table(&strategy).contains("pot ("));
+
+    let hero = Hand::from_str("AhKh").unwrap();
+    let state = GameState::new(hero, vec![], 10.0, 100.0, 0.0, Position::IP, Range::new());
+    strategy.game_state = Some(state.unwrap());
+    let table = format_strategy_table(&strategy);
+    assert!(table.contains("Bet 50% pot (5.0 BB)"), "{}", table);
+    assert!(!table.contains("Check ("), "{}", table);
+}
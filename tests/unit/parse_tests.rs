@@ -0,0 +1,92 @@
+use fpe::models::{BetSize, Hand, Position, Street};
+use fpe::{parse_bet_sizes, parse_board, parse_hand, parse_position, parse_range, ModelError};
+use std::str::FromStr;
+
+/// The field and reason of an `InvalidInput` error
+fn invalid_input(err: ModelError) -> (&'static str, String) {
+    match err {
+        ModelError::InvalidInput { field, reason, .. } => (field, reason),
+        other => panic!("expected InvalidInput, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_hand() {
+    assert_eq!(parse_hand("AhKd").unwrap(), Hand::from_str("AhKd").unwrap());
+    assert_eq!(
+        parse_hand(" Ah Kd ").unwrap(),
+        Hand::from_str("AhKd").unwrap()
+    );
+
+    let (field, reason) = invalid_input(parse_hand("AhKdQc").unwrap_err());
+    assert_eq!(field, "hand");
+    assert!(reason.contains("expected 2 cards"), "{}", reason);
+
+    let (_, reason) = invalid_input(parse_hand("AhAh").unwrap_err());
+    assert!(reason.contains("appears twice"), "{}", reason);
+
+    let err = parse_hand("AhXd").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid hand 'AhXd': Invalid card 'X': expected format like 'Ah', 'Kd', 'Ts'"
+    );
+}
+
+#[test]
+fn test_parse_board() {
+    assert_eq!(parse_board("").unwrap().street(), Street::Preflop);
+    assert_eq!(parse_board("Ts9s2h").unwrap().street(), Street::Flop);
+    assert_eq!(parse_board("Ts 9s, 2h 3c").unwrap().street(), Street::Turn);
+
+    let (field, reason) = invalid_input(parse_board("Ts9s").unwrap_err());
+    assert_eq!(field, "board");
+    assert!(reason.contains("got 2"), "{}", reason);
+
+    let (_, reason) = invalid_input(parse_board("Ts9sTs").unwrap_err());
+    assert!(reason.contains("appears twice"), "{}", reason);
+
+    let (_, reason) = invalid_input(parse_board("Ts9s2").unwrap_err());
+    assert!(reason.contains("two characters"), "{}", reason);
+}
+
+#[test]
+fn test_parse_range() {
+    assert_eq!(parse_range("QQ+,AKs").unwrap().num_combos(), 22);
+
+    let (field, reason) = invalid_input(parse_range("  ").unwrap_err());
+    assert_eq!(field, "range");
+    assert_eq!(reason, "empty range");
+
+    let (_, reason) = invalid_input(parse_range("QQ+,@nope").unwrap_err());
+    assert!(reason.contains("Unknown preset"), "{}", reason);
+}
+
+#[test]
+fn test_parse_position() {
+    assert_eq!(parse_position("ip").unwrap(), Position::IP);
+    assert_eq!(parse_position(" OOP ").unwrap(), Position::OOP);
+
+    let err = parse_position("BTN").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid position 'BTN': expected IP or OOP"
+    );
+}
+
+#[test]
+fn test_parse_bet_sizes() {
+    assert_eq!(
+        parse_bet_sizes("0.33pot, pot,10bb,allin").unwrap(),
+        [
+            BetSize::PotFraction(0.33),
+            BetSize::PotFraction(1.0),
+            BetSize::Amount(10.0),
+            BetSize::AllIn
+        ]
+    );
+
+    let (field, reason) = invalid_input(parse_bet_sizes("pot,half").unwrap_err());
+    assert_eq!(field, "bet sizes");
+    assert!(reason.contains("'half'"), "{}", reason);
+    assert!(parse_bet_sizes("").is_err());
+}
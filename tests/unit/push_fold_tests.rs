@@ -0,0 +1,94 @@
+use fpe::models::{action::Action, range::Range};
+use fpe::solver::push_fold::{push_fold, Blinds};
+
+fn shoves(strategy: &fpe::models::strategy::RangeStrategy, class: &str) -> bool {
+    let combos: Vec<_> = strategy
+        .combos
+        .iter()
+        .filter(|c| c.hand.notation() == class)
+        .collect();
+    assert!(!combos.is_empty(), "{}", class);
+    let shove = |c: &&fpe::models::strategy::ComboStrategy| {
+        c.strategy.dominant_action().unwrap().action == Action::AllIn
+    };
+    assert!(
+        combos.iter().all(shove) || !combos.iter().any(shove),
+        "{}",
+        class
+    );
+    shove(&combos[0])
+}
+
+#[test]
+fn test_push_fold_boundary_vs_any_two() {
+    let any_two = Range::new().complement(&[]);
+    let hero = Range::from_notation("AA,22,A2o,72o,32o").unwrap();
+
+    // At 10BB against a call with any two, a shove needs 9.5/20 = 47.5%
+    // equity: 22 (~50%) and A2o (~55%) clear it, 72o (~35%) doesn't
+    let strategy = push_fold(&hero, &any_two, 10.0, Blinds::default()).unwrap();
+    assert_eq!(strategy.combos.len(), hero.num_combos());
+    assert!(shoves(&strategy, "AA"));
+    assert!(shoves(&strategy, "22"));
+    assert!(shoves(&strategy, "A2o"));
+    assert!(!shoves(&strategy, "72o"));
+    assert!(!shoves(&strategy, "32o"));
+
+    // Nearly blinded out, the pot odds make every hand a shove
+    let strategy = push_fold(&hero, &any_two, 1.2, Blinds::default()).unwrap();
+    assert!(shoves(&strategy, "32o"));
+
+    // Shoving into a villain who never calls wins the blinds outright
+    let strategy = push_fold(&hero, &Range::new(), 10.0, Blinds::default()).unwrap();
+    assert!(strategy
+        .combos
+        .iter()
+        .all(|c| c.strategy.actions[1].ev == 1.5));
+
+    assert!(push_fold(&hero, &any_two, 0.5, Blinds::default()).is_err());
+}
+
+#[test]
+fn test_push_fold_ev_is_exact() {
+    use fpe::models::Hand;
+    use fpe::solver::preflop_equity;
+    use std::str::FromStr;
+
+    let hero = Range::from_notation("AhAd").unwrap();
+    let calls = Range::from_notation("KhKs,KcKd").unwrap();
+    let strategy = push_fold(&hero, &calls, 10.0, Blinds::default()).unwrap();
+
+    // Villain calls with 2 of 1225 combos, and AhAd has the same equity
+    // against both kings
+    let aces = Hand::from_str("AhAd").unwrap();
+    let equity = preflop_equity(&aces, &Hand::from_str("KhKs").unwrap()).unwrap();
+    assert_eq!(
+        equity,
+        preflop_equity(&aces, &Hand::from_str("KcKd").unwrap()).unwrap()
+    );
+    let call = 2.0 / 1225.0;
+    let expected = (1.0 - call) * 1.5 + call * (equity * 20.0 - 9.5);
+    let ev = strategy.combos[0].strategy.actions[1].ev;
+    assert!((ev - expected).abs() < 1e-12, "{} vs {}", ev, expected);
+}
+
+#[test]
+fn test_push_fold_reproduces_nash_push_boundary() {
+    // The big blind's heads-up Nash calling range at 10BB, about 36% of
+    // hands; the small blind's best response to it is its Nash push range
+    let nash_calls =
+        Range::from_notation("22+,A2s+,A2o+,K2s+,K6o+,Q8s+,Q9o+,J9s+,JTo,T9s").unwrap();
+
+    // The offsuit edge of the chart runs K2o, Q6o, J7o, T7o, 97o and 86o,
+    // with suited hands reaching further down each row
+    let pushes = ["K2o", "Q8o", "J8o", "T8o", "98o", "Q2s", "J4s", "95s"];
+    let folds = ["Q2o", "J4o", "T5o", "95o", "74o", "72s", "72o", "32s"];
+    let hero = Range::from_notation(&[pushes, folds].concat().join(",")).unwrap();
+    let strategy = push_fold(&hero, &nash_calls, 10.0, Blinds::default()).unwrap();
+    for class in pushes {
+        assert!(shoves(&strategy, class), "{} should shove", class);
+    }
+    for class in folds {
+        assert!(!shoves(&strategy, class), "{} should fold", class);
+    }
+}
@@ -3,6 +3,14 @@ use fpe::models::hand::Hand;
 use fpe::models::range::Range;
 use std::str::FromStr;
 
+/// Every combo with its weight, in the range's own order
+fn combos(range: &Range) -> Vec<(Hand, f64)> {
+    range
+        .hands()
+        .map(|(hand, weight)| (hand.clone(), weight))
+        .collect()
+}
+
 #[test]
 fn test_basic_range_parsing() {
     // Pair
@@ -48,6 +56,15 @@ fn test_combined_notation() {
     assert_eq!(range.num_combos(), 12);
 }
 
+#[test]
+fn test_specific_combo_keeps_suits() {
+    // Hearts and spades used to be swapped when reading pokers combos
+    let range = Range::from_notation("AhKs").expect("Failed to parse AhKs");
+    assert_eq!(range.num_combos(), 1);
+    assert!(range.contains(&Hand::from_str("AhKs").unwrap()));
+    assert!(!range.contains(&Hand::from_str("AsKh").unwrap()));
+}
+
 #[test]
 fn test_blocker_removal() {
     let mut range = Range::from_notation("AA").expect("Failed to parse AA");
@@ -69,3 +86,336 @@ fn test_blocker_removal() {
     assert!(!range.contains(&Hand::from_str("AhAs").unwrap()));
     assert!(range.contains(&Hand::from_str("AsAc").unwrap()));
 }
+
+fn overweighted_range() -> (Range, Hand, Hand) {
+    let heavy = Hand::from_str("AhAs").unwrap();
+    let light = Hand::from_str("KhKs").unwrap();
+    let mut range = Range::new();
+    range.add(heavy.clone(), 1.5);
+    range.add(light.clone(), 0.75);
+    (range, heavy, light)
+}
+
+#[test]
+fn test_clamp_weights() {
+    let (mut range, heavy, light) = overweighted_range();
+    range.clamp_weights();
+
+    assert_eq!(range.weight(&heavy).unwrap(), 1.0);
+    assert_eq!(range.weight(&light).unwrap(), 0.75);
+}
+
+#[test]
+fn test_normalize_weights() {
+    let (mut range, heavy, light) = overweighted_range();
+    range.normalize_weights();
+
+    assert!((range.weight(&heavy).unwrap() - 1.0).abs() < 1e-9);
+    assert!((range.weight(&light).unwrap() - 0.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_scale_weights() {
+    let (mut range, heavy, light) = overweighted_range();
+    range.scale(2.0);
+
+    assert!((range.weight(&heavy).unwrap() - 3.0).abs() < 1e-9);
+    assert!((range.weight(&light).unwrap() - 1.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_range_json_round_trip() {
+    let range = Range::from_notation("AKs,QQ@50").unwrap();
+
+    let json = serde_json::to_value(&range).unwrap();
+    assert_eq!(json["hands"]["QhQd"], 0.5);
+
+    let parsed: Range = serde_json::from_value(json).unwrap();
+    assert_eq!(combos(&parsed), combos(&range));
+}
+
+#[test]
+fn test_filter_by_predicate() {
+    use fpe::models::card::Rank;
+
+    let pairs = Range::from_notation("22+").unwrap();
+    let big_pairs = pairs.filter(|hand, _| hand.is_pair() && hand.cards[0].rank >= Rank::Ten);
+
+    // TT, JJ, QQ, KK, AA at six combos each
+    assert_eq!(big_pairs.num_combos(), 30);
+    assert!(big_pairs.contains(&Hand::from_str("TdTs").unwrap()));
+    assert!(!big_pairs.contains(&Hand::from_str("9d9s").unwrap()));
+    assert_eq!(pairs.num_combos(), 78);
+
+    let mut retained = pairs.clone();
+    retained.retain(|hand, _| hand.cards[0].rank >= Rank::Ten);
+    assert_eq!(retained.num_combos(), 30);
+}
+
+#[test]
+fn test_iterate_range_by_reference() {
+    let range = Range::from_notation("AA,KK@50").unwrap();
+
+    let mut total = 0.0;
+    for (_, weight) in &range {
+        total += weight;
+    }
+    assert!((total - 9.0).abs() < 1e-9);
+
+    assert_eq!(range.len(), 12);
+    assert_eq!(range.len(), range.num_combos());
+    assert!(!range.is_empty());
+    assert!(Range::new().is_empty());
+    assert_eq!(
+        (&range).into_iter().filter(|(h, _)| h.is_pair()).count(),
+        12
+    );
+}
+
+#[test]
+fn test_grid_weights() {
+    let grid = Range::from_notation("AA").unwrap().to_grid_weights();
+    for (row, cells) in grid.iter().enumerate() {
+        for (col, &weight) in cells.iter().enumerate() {
+            let expected = if (row, col) == (0, 0) { 1.0 } else { 0.0 };
+            assert!(
+                (weight - expected).abs() < 1e-9,
+                "[{}][{}] = {}",
+                row,
+                col,
+                weight
+            );
+        }
+    }
+
+    // Suited above the diagonal, offsuit below, partial classes averaged
+    let grid = Range::from_notation("AKs,72o@50,AhQd")
+        .unwrap()
+        .to_grid_weights();
+    assert!((grid[0][1] - 1.0).abs() < 1e-9);
+    assert_eq!(grid[1][0], 0.0);
+    // 72o: row of the Two, column of the Seven
+    assert!((grid[12][7] - 0.5).abs() < 1e-9);
+    assert!((grid[2][0] - 1.0 / 12.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_weighted_iter_counts_combos() {
+    use fpe::solver::equity::calculate_equity_runout;
+
+    let range = Range::from_notation("AA,72o").unwrap();
+    let shares: Vec<(&Hand, f64)> = range.weighted_iter().collect();
+    assert_eq!(shares.len(), 18);
+    let total: f64 = shares.iter().map(|(_, w)| w).sum();
+    assert!((total - 1.0).abs() < 1e-12);
+    assert!(shares.iter().all(|(_, w)| (w - 1.0 / 18.0).abs() < 1e-12));
+    assert_eq!(Range::new().weighted_iter().count(), 0);
+
+    // Neither class is blocked, so the 12 offsuit combos count twice as much
+    // as the 6 pairs
+    let hero = Hand::from_str("QhQd").unwrap();
+    let board: Vec<Card> = ["Ks", "8d", "4c"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let equity = |notation: &str| {
+        calculate_equity_runout(&hero, &Range::from_notation(notation).unwrap(), &board).equity()
+    };
+    let expected = (6.0 * equity("AA") + 12.0 * equity("72o")) / 18.0;
+    assert!((equity("AA,72o") - expected).abs() < 1e-9);
+}
+
+#[test]
+fn test_complement() {
+    let aces = Range::from_notation("AA").unwrap();
+    let rest = aces.complement(&[]);
+    assert_eq!(rest.len(), 1326 - 6);
+    assert!(!rest.contains(&Hand::from_str("AhAs").unwrap()));
+    assert!(rest.contains(&Hand::from_str("KdKc").unwrap()));
+    assert!(rest.hands().all(|(_, weight)| weight == 1.0));
+
+    // Dead cards remove every combo holding them: 51 per card, less the overlap
+    let dead = [Card::from_str("Kd").unwrap(), Card::from_str("2c").unwrap()];
+    let rest = aces.complement(&dead);
+    assert_eq!(rest.len(), 1326 - 6 - (51 + 51 - 1));
+    assert!(!rest.contains(&Hand::from_str("KdQs").unwrap()));
+}
+
+#[test]
+fn test_sample_is_reproducible() {
+    let all = Range::new().complement(&[]);
+    assert_eq!(all.len(), 1326);
+
+    let sample = all.sample(10, 7);
+    assert_eq!(sample.len(), 10);
+    assert!(sample.hands().all(|(hand, _)| all.contains(hand)));
+    assert_eq!(combos(&all.sample(10, 7)), combos(&sample));
+    assert_ne!(combos(&all.sample(10, 8)), combos(&sample));
+
+    // Zero-weight combos are never drawn
+    let mut range = Range::from_notation("AA").unwrap();
+    range.add(Hand::from_str("KhKd").unwrap(), 0.0);
+    assert_eq!(range.sample(10, 1).len(), 6);
+}
+
+#[test]
+fn test_range_shape() {
+    use fpe::models::RangeShape;
+
+    let shape = |notation: &str| Range::from_notation(notation).unwrap().shape().unwrap();
+
+    let polar = shape("AA,72o");
+    assert_eq!(polar.shape, RangeShape::Polarized);
+    assert!(polar.confidence > 0.9, "{:?}", polar);
+    assert!(Range::from_notation("AA,72o").unwrap().is_polarized());
+
+    assert_eq!(shape("22+").shape, RangeShape::Linear);
+    assert!(Range::from_notation("22+").unwrap().is_linear());
+    let top = shape("TT+,AJs+,KJs+,AKo");
+    assert_eq!(top.shape, RangeShape::Linear);
+    assert_eq!(top.confidence, 1.0);
+
+    // Nothing near the top of the ranking
+    assert_eq!(shape("99-55,T9s,98s").shape, RangeShape::Capped);
+    assert_eq!(shape("JTs,T9s,98s,87s,76s").shape, RangeShape::Capped);
+
+    assert!(Range::new().shape().is_none());
+    assert!(!Range::new().is_linear());
+}
+
+#[test]
+fn test_colon_weights() {
+    let range = Range::from_notation("AA:0.5,KK").unwrap();
+    assert_eq!(range.weight(&Hand::from_str("AhAd").unwrap()).unwrap(), 0.5);
+    assert_eq!(range.weight(&Hand::from_str("KhKd").unwrap()).unwrap(), 1.0);
+    assert_eq!(
+        combos(&range),
+        combos(&Range::from_notation("AA@50,KK").unwrap())
+    );
+
+    // A weighted preset weighs all of its combos
+    let weighted = Range::from_notation("@BTN_3bet:0.25").unwrap();
+    assert_eq!(
+        weighted.len(),
+        Range::from_preset("BTN_3bet").unwrap().len()
+    );
+    assert!(weighted.hands().all(|(_, w)| w == 0.25));
+
+    for bad in ["AA:1.5", "AA:-0.1", "AA:half"] {
+        let err = Range::from_notation(bad).unwrap_err();
+        assert!(err.to_string().contains("Invalid weight"), "{}", err);
+    }
+}
+
+#[test]
+fn test_preset_ranges() {
+    use fpe::models::presets::PRESETS;
+
+    let btn = Range::from_notation("@BTN_open").unwrap();
+    assert!(btn.num_combos() > 0);
+    assert_eq!(
+        btn.num_combos(),
+        Range::from_preset("btn_open").unwrap().num_combos()
+    );
+
+    // Presets mix with regular tokens
+    let utg = Range::from_preset("UTG_open").unwrap();
+    let with_extra = Range::from_notation("@UTG_open,22").unwrap();
+    assert_eq!(with_extra.num_combos(), utg.num_combos() + 6);
+
+    for (name, notation) in PRESETS {
+        let range = Range::from_preset(name).unwrap();
+        assert!(range.num_combos() > 0, "{} ({}) is empty", name, notation);
+    }
+
+    assert!(Range::from_preset("CO_limp").is_err());
+    let err = Range::from_notation("@CO_limp").unwrap_err();
+    assert!(err.to_string().contains("Unknown preset"), "{}", err);
+}
+
+#[test]
+fn test_blocker_report_counts_blocked_nut_flushes() {
+    use fpe::solver::evaluator::HandCategory;
+
+    let board: Vec<Card> = ["Kh", "8h", "3h", "2c"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    let villain = Range::from_notation("AhQh,AhJh,AhTh,QhJh,7h6h,KdKc,QsJs,Ad5d").unwrap();
+
+    let with_ace = villain.blocker_report(&Hand::from_str("AhQs").unwrap(), &board);
+    // The ace takes out all three nut flushes, the queen the QsJs bluff
+    let nut_flushes: Vec<&Hand> = with_ace
+        .blocked_value
+        .iter()
+        .filter(|(hand, category)| {
+            *category == HandCategory::Flush && hand.contains_card(&Card::from_str("Ah").unwrap())
+        })
+        .map(|(hand, _)| hand)
+        .collect();
+    assert_eq!(nut_flushes.len(), 3);
+    assert_eq!(with_ace.blocked_value.len(), 3);
+    assert_eq!(with_ace.value_combos, 6.0);
+    assert_eq!(with_ace.value_combos_after(), 3.0);
+    assert_eq!(with_ace.bluff_combos, 2.0);
+    assert_eq!(with_ace.bluff_combos_after(), 1.0);
+
+    // Without a heart, hero blocks no flushes
+    let without = villain.blocker_report(&Hand::from_str("AdQc").unwrap(), &board);
+    assert!(without
+        .blocked_value
+        .iter()
+        .all(|(_, category)| *category != HandCategory::Flush));
+}
+
+#[test]
+fn test_combo_is_one_entry_in_either_card_order() {
+    let mut range = Range::new();
+    range.add(Hand::from_str("AsAh").unwrap(), 1.0);
+    range.add(Hand::from_str("AhAs").unwrap(), 0.5);
+    assert_eq!(range.num_combos(), 1);
+    assert_eq!(range.weight(&Hand::from_str("AsAh").unwrap()), Some(0.5));
+
+    let built = Range::from_combos([
+        (Hand::from_str("KdAc").unwrap(), 1.0),
+        (Hand::from_str("AcKd").unwrap(), 1.0),
+    ]);
+    assert_eq!(built.num_combos(), 1);
+    assert!(built.contains(&Hand::from_str("KdAc").unwrap()));
+
+    // Same keys as parsing the combo
+    let parsed = Range::from_notation("AcKd").unwrap();
+    assert_eq!(combos(&parsed), combos(&built));
+}
+
+#[test]
+fn test_bad_token_reports_its_position() {
+    let message = |notation: &str| Range::from_notation(notation).unwrap_err().to_string();
+
+    assert_eq!(
+        message("AA,ZZ,KK"),
+        "Invalid range notation 'AA,ZZ,KK': 'ZZ' at position 3: \
+         expected a hand class like AKs or QQ+, or a combo like AhKd"
+    );
+    assert!(message("QQ, JJ-").contains("'JJ-' at position 4"));
+    assert!(message("99-JJ").contains("dash range"));
+    assert!(message("AKs-QJs").contains("dash range"));
+    assert!(message("AA@150").contains("whole percent"));
+    assert!(message("AA,,KK").contains("position 3: empty token"));
+    assert!(message("AhAh").contains("'AhAh'"));
+    assert!(message("AKx").contains("'AKx'"));
+
+    // Everything pokers reads still parses
+    for notation in [
+        "aa",
+        "JJ-99",
+        "A9s-A6s",
+        "22+",
+        "A2s+",
+        "KQ@50",
+        "AhKd",
+        "@BTN_open",
+    ] {
+        assert!(Range::from_notation(notation).is_ok(), "{}", notation);
+    }
+}
@@ -0,0 +1,60 @@
+use fpe::cli::repl::{ReplResponse, Session};
+
+#[test]
+fn test_repl_scripted_session() {
+    let mut session = Session::new();
+
+    for line in [
+        "set hero AhKh",
+        "set board QhJhTh2s3d",
+        "set villain 22+",
+        "set pot 10",
+        "set stack 100",
+        "set iterations 50",
+        "set seed 7",
+    ] {
+        assert_eq!(
+            session.handle(line),
+            Ok(ReplResponse::Output(String::new())),
+            "command '{}' failed",
+            line
+        );
+    }
+
+    let solved = session.handle("solve").unwrap();
+    assert!(matches!(solved, ReplResponse::Output(ref s) if s.contains("Frequency")));
+    assert_eq!(session.last_state().unwrap().pot_size, 10.0);
+    let first = serde_json::to_string(session.last_strategy().unwrap()).unwrap();
+
+    // Tweak one field and re-solve without rebuilding the session
+    session.handle("set pot 12").unwrap();
+    session.handle("solve").unwrap();
+    assert_eq!(session.last_state().unwrap().pot_size, 12.0);
+    assert_eq!(session.last_strategy().unwrap().iterations, 50);
+
+    // Going back to a spot already solved reuses its strategy
+    session.handle("set pot 10").unwrap();
+    session.handle("solve").unwrap();
+    let again = serde_json::to_string(session.last_strategy().unwrap()).unwrap();
+    assert_eq!(again, first);
+    session.handle("set pot 12").unwrap();
+
+    let shown = session.handle("show").unwrap();
+    assert!(matches!(shown, ReplResponse::Output(ref s) if s.contains("Pot: 12.0 BB")));
+
+    assert_eq!(session.handle("quit"), Ok(ReplResponse::Quit));
+}
+
+#[test]
+fn test_repl_errors_keep_session_usable() {
+    let mut session = Session::new();
+
+    assert!(session.handle("set pot abc").is_err());
+    assert!(session.handle("set nonsense 1").is_err());
+    assert!(session.handle("frobnicate").is_err());
+
+    // Solving with incomplete inputs reports an error instead of panicking
+    let err = session.handle("solve").unwrap_err();
+    assert!(err.contains("Error parsing hero hand"), "{}", err);
+    assert!(session.last_state().is_none());
+}
@@ -0,0 +1,165 @@
+use fpe::models::{hand::Hand, range::Range};
+use fpe::solver::equity::calculate_equity;
+use fpe::solver::evaluator::{evaluate_hand, hand_category, HandCategory};
+use fpe::test_util::cards;
+use std::str::FromStr;
+
+fn hand(s: &str) -> Hand {
+    Hand::from_str(s).unwrap()
+}
+
+/// Assert every hand beats the ones after it on `board`
+fn assert_ranked(board: &str, hands: &[(&str, HandCategory)]) {
+    let board = cards(board);
+    for (h, category) in hands {
+        assert_eq!(hand_category(&hand(h), &board), *category, "{}", h);
+    }
+    for pair in hands.windows(2) {
+        let (stronger, weaker) = (pair[0].0, pair[1].0);
+        assert!(
+            evaluate_hand(&hand(stronger), &board) > evaluate_hand(&hand(weaker), &board),
+            "{} should beat {}",
+            stronger,
+            weaker
+        );
+    }
+}
+
+#[test]
+fn test_paired_board_ranking() {
+    assert_ranked(
+        "Ks Kd 5c 5h 2s",
+        &[
+            ("KhKc", HandCategory::Quads),
+            ("AhKh", HandCategory::FullHouse),
+            ("5d2d", HandCategory::FullHouse),
+            ("QhQd", HandCategory::TwoPair),
+            ("Ah3c", HandCategory::TwoPair),
+            ("Qh3c", HandCategory::TwoPair),
+        ],
+    );
+}
+
+#[test]
+fn test_full_house_ordering() {
+    // Trips rank decides first, then the pair
+    assert_ranked(
+        "9s 9d 4c 4h Jc",
+        &[
+            ("JhJd", HandCategory::FullHouse),
+            ("9cJh", HandCategory::FullHouse),
+            ("9c4d", HandCategory::FullHouse),
+            ("4dJh", HandCategory::FullHouse),
+            ("4dAh", HandCategory::FullHouse),
+        ],
+    );
+}
+
+#[test]
+fn test_monotone_board_ranking() {
+    assert_ranked(
+        "Qh Jh 7h 3d 3s",
+        &[
+            ("3h3c", HandCategory::Quads),
+            ("QsQd", HandCategory::FullHouse),
+            ("Ah2h", HandCategory::Flush),
+            ("KhTh", HandCategory::Flush),
+            ("AsQd", HandCategory::TwoPair),
+        ],
+    );
+    assert_ranked(
+        "Qh Jh 7h 3d 2s",
+        &[
+            ("Ah4h", HandCategory::Flush),
+            ("KhTh", HandCategory::Flush),
+            ("9h8h", HandCategory::Flush),
+            ("QsQd", HandCategory::Set),
+            ("Qs7d", HandCategory::TwoPair),
+            // One heart isn't a flush
+            ("AhKs", HandCategory::Air),
+        ],
+    );
+}
+
+#[test]
+fn test_straight_flush_and_wheel() {
+    assert_ranked(
+        "5h 4h 3h Kc Kd",
+        &[
+            ("7h6h", HandCategory::StraightFlush),
+            ("Ah2h", HandCategory::StraightFlush),
+            ("KhKs", HandCategory::Quads),
+            ("Th9h", HandCategory::Flush),
+            ("Ac2d", HandCategory::Straight),
+            ("Qs8c", HandCategory::Air),
+        ],
+    );
+}
+
+#[test]
+fn test_board_plays_is_a_tie() {
+    // Broadway on board: nobody improves, so every hand ties
+    let board = cards("As Kd Qc Jh Ts");
+    let score = evaluate_hand(&hand("2c3d"), &board);
+    assert_eq!(evaluate_hand(&hand("9c8d"), &board), score);
+
+    // Quads on board with an ace kicker everyone shares
+    let board = cards("7s 7d 7c 7h As");
+    assert_eq!(
+        evaluate_hand(&hand("KcQd"), &board),
+        evaluate_hand(&hand("2c3d"), &board)
+    );
+}
+
+#[test]
+fn test_showdown_equity_on_paired_board() {
+    let board = cards("Ks Kd 5c 5h 2s");
+
+    let full_house = calculate_equity(
+        &hand("AhKh"),
+        &Range::from_notation("QhQd").unwrap(),
+        &board,
+    );
+    assert_eq!(full_house.win, 1.0);
+
+    let beaten = calculate_equity(
+        &hand("AhKh"),
+        &Range::from_notation("KcQc").unwrap(),
+        &board,
+    );
+    assert_eq!(beaten.win, 0.0);
+
+    // Counterfeited: both hands play the board's two pair with an ace kicker
+    let chopped = calculate_equity(
+        &hand("Ah3c"),
+        &Range::from_notation("Ad4c").unwrap(),
+        &board,
+    );
+    assert_eq!(chopped.tie, 1.0);
+}
+
+#[test]
+fn test_equity_by_next_card_on_flush_draw() {
+    use fpe::models::card::Suit;
+    use fpe::solver::equity::equity_by_next_card;
+
+    // Nut flush draw against overpairs
+    let hero = hand("AhKh");
+    let villain = Range::from_notation("QQ,JJ").unwrap();
+    let runouts = equity_by_next_card(&hero, &villain, &cards("9h 8h 2c"), &[]);
+    assert_eq!(runouts.len(), 47);
+
+    let average = |hearts: bool| {
+        let equities: Vec<f64> = runouts
+            .iter()
+            .filter(|(card, _)| (card.suit == Suit::Hearts) == hearts)
+            .map(|(_, equity)| equity.equity())
+            .collect();
+        equities.iter().sum::<f64>() / equities.len() as f64
+    };
+    assert!(average(true) > 0.9, "{}", average(true));
+    assert!(average(true) > average(false) + 0.4);
+
+    // Only flops and turns have a next card
+    assert!(equity_by_next_card(&hero, &villain, &cards("9h 8h 2c 3d 4s"), &[]).is_empty());
+}
@@ -0,0 +1,127 @@
+use fpe::models::{Card, GameState, Hand, Position, Range, Strategy};
+use fpe::solver::{solve_with_kind, MccfrConfig, SolveCache, SolverKind};
+use std::cell::Cell;
+use std::str::FromStr;
+
+fn spot() -> GameState {
+    let board = ["Qh", "Jh", "Th", "2s", "3d"]
+        .iter()
+        .map(|c| Card::from_str(c).unwrap())
+        .collect();
+    GameState::new(
+        Hand::from_str("AhKh").unwrap(),
+        board,
+        10.0,
+        100.0,
+        0.0,
+        Position::IP,
+        Range::from_notation("QQ,JJ").unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_repeat_solve_is_served_from_cache() {
+    let cache = SolveCache::default();
+    let config = MccfrConfig::builder().iterations(100).seed(1).build();
+    let solves = Cell::new(0);
+    let solve = || {
+        solves.set(solves.get() + 1);
+        solve_with_kind(spot(), SolverKind::Mccfr, config.clone())
+    };
+
+    let first = cache
+        .get_or_solve(&spot(), SolverKind::Mccfr, &config, solve)
+        .unwrap();
+    let second = cache
+        .get_or_solve(&spot(), SolverKind::Mccfr, &config, solve)
+        .unwrap();
+    assert_eq!(solves.get(), 1);
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+
+    // A different seed is a different solve
+    let reseeded = MccfrConfig::builder().iterations(100).seed(2).build();
+    cache.solve(spot(), SolverKind::Mccfr, reseeded).unwrap();
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+}
+
+#[test]
+fn test_only_repeatable_solves_are_cached() {
+    let cache = SolveCache::default();
+    let solves = Cell::new(0);
+    let solve = || {
+        solves.set(solves.get() + 1);
+        solve_with_kind(spot(), SolverKind::Uniform, MccfrConfig::default())
+    };
+
+    // An unseeded solve samples afresh every time
+    let unseeded = MccfrConfig::builder().iterations(100).build();
+    for _ in 0..2 {
+        cache
+            .get_or_solve(&spot(), SolverKind::Mccfr, &unseeded, solve)
+            .unwrap();
+    }
+    assert_eq!(solves.get(), 2);
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 0, 0));
+
+    // Every setting is part of the key, not just the ones listed by hand
+    let seeded = MccfrConfig::builder().iterations(100).seed(1).build();
+    let mut enumerated = seeded.clone();
+    enumerated.enumerate_villain_below = 50;
+    for config in [&seeded, &enumerated, &seeded] {
+        cache
+            .get_or_solve(&spot(), SolverKind::Mccfr, config, solve)
+            .unwrap();
+    }
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+
+    // Profiling only changes the report, so it shares the entry
+    let mut profiled = seeded.clone();
+    profiled.profile = true;
+    cache
+        .get_or_solve(&spot(), SolverKind::Mccfr, &profiled, solve)
+        .unwrap();
+    assert_eq!(cache.hits(), 2);
+}
+
+#[test]
+fn test_least_recently_used_is_evicted() {
+    let cache = SolveCache::new(2);
+    let uniform = |pot: f64| {
+        let state = spot().with_pot(pot).unwrap();
+        cache
+            .solve(state, SolverKind::Uniform, MccfrConfig::default())
+            .unwrap()
+    };
+
+    uniform(10.0);
+    uniform(20.0);
+    uniform(10.0); // 20 is now the oldest
+    uniform(30.0);
+    assert_eq!(cache.len(), 2);
+    uniform(10.0);
+    assert_eq!(cache.hits(), 2);
+    uniform(20.0);
+    assert_eq!(cache.misses(), 4);
+
+    // Errors aren't cached and nothing is kept at capacity 0
+    let empty = SolveCache::new(0);
+    let solve = || solve_with_kind(spot(), SolverKind::Uniform, MccfrConfig::default());
+    let config = MccfrConfig::default();
+    empty
+        .get_or_solve(&spot(), SolverKind::Uniform, &config, solve)
+        .unwrap();
+    assert!(empty.is_empty());
+    let failed: std::result::Result<Strategy, &str> = cache.get_or_solve(
+        &spot().with_pot(40.0).unwrap(),
+        SolverKind::Uniform,
+        &config,
+        || Err("failed"),
+    );
+    assert!(failed.is_err());
+    assert_eq!(cache.len(), 2);
+}
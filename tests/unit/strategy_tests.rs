@@ -38,3 +38,261 @@ fn test_strategy_invalid_sum() {
     let strategy = Strategy::new(actions, 1000, 0.001);
     assert!(!strategy.is_valid());
 }
+
+fn two_action_strategy(check: f64, bet: f64) -> Strategy {
+    use fpe::models::action::BetSize;
+
+    let actions = vec![
+        ActionStrategy {
+            action: Action::Check,
+            frequency: check,
+            ev: 0.0,
+        },
+        ActionStrategy {
+            action: Action::Bet(BetSize::PotFraction(0.75)),
+            frequency: bet,
+            ev: 1.0,
+        },
+    ];
+    Strategy::new(actions, 1000, 0.001)
+}
+
+#[test]
+fn test_strategy_pure() {
+    let strategy = two_action_strategy(0.01, 0.99);
+    assert!(strategy.is_pure(0.05));
+    assert!(!strategy.is_mixed());
+    assert_eq!(
+        strategy.dominant_action().unwrap().action.display_name(),
+        "Bet 75% pot"
+    );
+}
+
+#[test]
+fn test_strategy_mixed() {
+    let strategy = two_action_strategy(0.5, 0.5);
+    assert!(!strategy.is_pure(0.05));
+    assert!(strategy.is_mixed());
+}
+
+#[test]
+fn test_sorting_is_stable_on_ties() {
+    use fpe::models::action::BetSize;
+
+    let actions = vec![
+        ActionStrategy {
+            action: Action::Check,
+            frequency: 0.4,
+            ev: 1.0,
+        },
+        ActionStrategy {
+            action: Action::Bet(BetSize::PotFraction(0.5)),
+            frequency: 0.2,
+            ev: f64::NAN,
+        },
+        ActionStrategy {
+            action: Action::Bet(BetSize::PotFraction(1.0)),
+            frequency: 0.4,
+            ev: 1.0,
+        },
+    ];
+    let strategy = Strategy::new(actions, 1000, 0.001);
+
+    // Equal frequencies keep action order
+    let order: Vec<String> = strategy
+        .sorted_by_frequency()
+        .iter()
+        .map(|a| a.action.display_name())
+        .collect();
+    assert_eq!(order, vec!["Check", "Bet 100% pot", "Bet 50% pot"]);
+
+    // Equal EVs resolve to the first action; NaN never wins
+    assert_eq!(strategy.best_action().unwrap().action, Action::Check);
+    assert_eq!(strategy.dominant_action().unwrap().action, Action::Check);
+}
+
+#[test]
+fn test_nan_frequencies_sort_last() {
+    let actions = vec![
+        ActionStrategy {
+            action: Action::Fold,
+            frequency: f64::NAN,
+            ev: 0.0,
+        },
+        ActionStrategy {
+            action: Action::Call,
+            frequency: 0.3,
+            ev: 0.0,
+        },
+    ];
+    let strategy = Strategy::new(actions, 1000, 0.001);
+
+    let sorted = strategy.sorted_by_frequency();
+    assert_eq!(sorted[0].action, Action::Call);
+    assert!(sorted[1].frequency.is_nan());
+}
+
+#[test]
+fn test_json_carries_schema_version() {
+    use fpe::solver::equity::Equity;
+    use fpe::JSON_SCHEMA_VERSION;
+
+    let strategy = two_action_strategy(0.5, 0.5);
+    let json = serde_json::to_value(&strategy).unwrap();
+    assert_eq!(json["schema_version"], JSON_SCHEMA_VERSION);
+
+    let equity = serde_json::to_value(Equity::new(0.5, 0.1, 0.4)).unwrap();
+    assert_eq!(equity["schema_version"], JSON_SCHEMA_VERSION);
+
+    // Output from before versioning still parses
+    let legacy: Strategy =
+        serde_json::from_str(r#"{"actions": [], "iterations": 10, "convergence": 0.0}"#).unwrap();
+    assert_eq!(legacy.schema_version, 0);
+}
+
+#[test]
+fn test_json_carries_ev_unit_and_root_ev() {
+    let actions = vec![
+        ActionStrategy {
+            action: Action::Fold,
+            frequency: 0.25,
+            ev: 0.0,
+        },
+        ActionStrategy {
+            action: Action::Call,
+            frequency: 0.75,
+            ev: 2.0,
+        },
+    ];
+    let strategy = Strategy::new(actions, 1000, 0.001);
+    let json = serde_json::to_value(&strategy).unwrap();
+
+    assert_eq!(json["ev_unit"], "BB");
+    assert!(json["actions"][1]["ev"].is_number());
+    let root_ev = json["root_ev"].as_f64().expect("numeric root EV");
+    assert!((root_ev - 1.5).abs() < 1e-12);
+
+    // Older output without the fields reads as big blinds
+    let legacy: Strategy =
+        serde_json::from_str(r#"{"actions": [], "iterations": 10, "convergence": 0.0}"#).unwrap();
+    assert_eq!(legacy.ev_unit, fpe::models::EvUnit::BigBlinds);
+}
+
+#[test]
+fn test_validity_tolerance_and_renormalize() {
+    let mut strategy = two_action_strategy(0.4, 0.602);
+    assert!(strategy.is_valid_with_tol(0.01));
+    assert!(!strategy.is_valid_with_tol(0.0001));
+
+    strategy.renormalize();
+    assert!(strategy.is_valid_with_tol(1e-12));
+    assert!((strategy.actions[0].frequency - 0.4 / 1.002).abs() < 1e-12);
+
+    let mut empty = two_action_strategy(0.0, 0.0);
+    empty.renormalize();
+    assert_eq!(empty.actions[0].frequency, 0.0);
+}
+
+#[test]
+fn test_from_json_requires_actions() {
+    use fpe::ModelError;
+
+    let strategy = two_action_strategy(0.5, 0.5);
+    let json = serde_json::to_string(&strategy).unwrap();
+    assert_eq!(Strategy::from_json(&json).unwrap().actions.len(), 2);
+
+    let empty = r#"{"actions": [], "iterations": 10, "convergence": 0.0}"#;
+    let err = Strategy::from_json(empty).unwrap_err();
+    assert!(matches!(err, ModelError::InvalidStrategy(_)));
+    assert_eq!(err.to_string(), "Invalid strategy: no actions");
+
+    assert!(matches!(
+        Strategy::from_json("{"),
+        Err(ModelError::InvalidStrategy(_))
+    ));
+}
+
+#[test]
+fn test_display_is_a_one_line_summary() {
+    let strategy = two_action_strategy(0.38, 0.62);
+    let summary = strategy.to_string();
+
+    assert!(!summary.contains('\n'));
+    assert!(
+        summary.starts_with("Bet 75% pot: 62% | Check: 38%"),
+        "{}",
+        summary
+    );
+    assert!(summary.contains("EV +0.62 BB"), "{}", summary);
+    assert!(summary.ends_with("1000 it"), "{}", summary);
+}
+
+#[test]
+fn test_bounds_safe_accessors() {
+    let strategy = two_action_strategy(0.5, 0.5);
+    assert_eq!(strategy.len(), 2);
+    assert!(!strategy.is_empty());
+    assert_eq!(strategy.action_at(0).unwrap().action, Action::Check);
+    assert!(strategy.action_at(2).is_none());
+
+    let empty = Strategy::new(Vec::new(), 0, 0.0);
+    assert_eq!(empty.len(), 0);
+    assert!(empty.is_empty());
+    assert!(empty.action_at(0).is_none());
+}
+
+#[test]
+fn test_indifference_spread() {
+    // Check at EV 0.0, bet at EV 1.0
+    assert_eq!(two_action_strategy(0.5, 0.5).indifference_spread(), 1.0);
+    // A rarely played action doesn't count
+    assert_eq!(two_action_strategy(0.99, 0.01).indifference_spread(), 0.0);
+    assert_eq!(Strategy::new(Vec::new(), 0, 0.0).indifference_spread(), 0.0);
+}
+
+#[test]
+fn test_range_strategy_json_keys_combos() {
+    use fpe::models::strategy::{ComboStrategy, RangeStrategy};
+    use fpe::models::Hand;
+    use std::str::FromStr;
+
+    let combo = |hand: &str, weight: f64, check: f64| ComboStrategy {
+        hand: Hand::from_str(hand).unwrap(),
+        weight,
+        strategy: two_action_strategy(check, 1.0 - check),
+    };
+    let range_strategy = RangeStrategy {
+        combos: vec![combo("AhKh", 1.0, 0.25), combo("7c6c", 3.0, 0.75)],
+    };
+
+    let json: serde_json::Value = serde_json::from_str(&range_strategy.to_json()).unwrap();
+    assert_eq!(json["combos"]["AhKh"]["Check"], 0.25);
+    assert_eq!(json["combos"]["AhKh"]["Bet 75% pot"], 0.75);
+    assert_eq!(json["combos"]["7c6c"]["Check"], 0.75);
+    assert_eq!(json["combos"].as_object().unwrap().len(), 2);
+    // Weighted 1:3 towards 7c6c
+    assert_eq!(json["aggregate"]["Check"], 0.625);
+    assert_eq!(json["aggregate"]["Bet 75% pot"], 0.375);
+}
+
+#[test]
+fn test_range_strategy_convergence_is_the_worst_combo() {
+    use fpe::models::strategy::{ComboStrategy, RangeStrategy};
+    use fpe::models::Hand;
+    use std::str::FromStr;
+
+    let combo = |hand: &str, convergence: f64| {
+        let mut strategy = two_action_strategy(0.5, 0.5);
+        strategy.convergence = convergence;
+        ComboStrategy {
+            hand: Hand::from_str(hand).unwrap(),
+            weight: 1.0,
+            strategy,
+        }
+    };
+    let range_strategy = RangeStrategy {
+        combos: vec![combo("AhKh", 0.002), combo("7c6c", 0.01)],
+    };
+    assert_eq!(range_strategy.convergence(), 0.01);
+    assert_eq!(RangeStrategy::default().convergence(), 0.0);
+}
@@ -100,3 +100,93 @@ fn test_board_street_mismatch() {
     )
     .is_err());
 }
+
+#[test]
+fn test_stack_from_spr() {
+    use fpe::cli::args::{resolve_stack, AnalyzeRequest};
+
+    let stack = resolve_stack(10.0, None, Some(5.0)).unwrap();
+    assert_eq!(stack, 50.0);
+    assert_eq!(resolve_stack(10.0, Some(80.0), None).unwrap(), 80.0);
+    assert!(resolve_stack(10.0, Some(80.0), Some(5.0)).is_err());
+    assert!(resolve_stack(10.0, None, None).is_err());
+
+    let request = AnalyzeRequest {
+        hero: "AhKd".to_string(),
+        villain_range: "QQ".to_string(),
+        pot: 10.0,
+        stack,
+        ..Default::default()
+    };
+    let state = request.to_game_state().unwrap();
+    assert_eq!(state.effective_stack, 50.0);
+}
+
+#[test]
+fn test_request_dead_cards() {
+    use fpe::cli::args::AnalyzeRequest;
+
+    let request = AnalyzeRequest {
+        hero: "AhKd".to_string(),
+        board: "Ks8d4c".to_string(),
+        dead: "QsQh".to_string(),
+        villain_range: "QQ".to_string(),
+        pot: 10.0,
+        stack: 100.0,
+        ..Default::default()
+    };
+    let state = request.to_game_state().unwrap();
+    assert_eq!(state.dead_cards.len(), 2);
+    // Only QcQd survives
+    assert_eq!(state.villain_range.num_combos(), 1);
+
+    let clash = AnalyzeRequest {
+        dead: "Ks".to_string(),
+        ..request.clone()
+    };
+    assert!(clash
+        .to_game_state()
+        .unwrap_err()
+        .contains("Duplicate card"));
+
+    let odd = AnalyzeRequest {
+        dead: "Qs9".to_string(),
+        ..request
+    };
+    assert_eq!(
+        odd.to_game_state().unwrap_err(),
+        "Error: Dead string length must be even"
+    );
+}
+
+#[test]
+fn test_villain_perspective_swaps_roles() {
+    use fpe::cli::args::AnalyzeRequest;
+
+    let request = AnalyzeRequest {
+        hero: "Ah Kd".to_string(),
+        villain_range: "QQ+".to_string(),
+        pot: 10.0,
+        stack: 100.0,
+        position: "oop".to_string(),
+        ..Default::default()
+    };
+    let swapped = request.villain_perspective().unwrap();
+    assert_eq!(swapped.hero, "");
+    assert_eq!(swapped.hero_range, "QQ+");
+    assert_eq!(swapped.villain_range, "AhKd");
+    assert_eq!(swapped.position, "IP");
+    assert_eq!(swapped.to_call, 0.0);
+
+    // Facing a bet, villain already acted by making it
+    let facing = AnalyzeRequest {
+        to_call: 5.0,
+        ..request.clone()
+    };
+    let err = facing.villain_perspective().unwrap_err();
+    assert!(err.contains("--to-call 0"), "{}", err);
+
+    // Swapping a range spot twice gives it back
+    let twice = swapped.villain_perspective().unwrap().villain_perspective();
+    assert_eq!(twice.unwrap(), swapped);
+}